@@ -0,0 +1,48 @@
+pub const BUILTIN_LEN: &str = "len";
+// moves a value to the heap, returning a pointer to it
+pub const BUILTIN_ALLOC: &str = "alloc";
+pub const BUILTIN_FREE: &str = "free";
+
+// the prelude
+pub const BUILTIN_PRINT: &str = "print";
+pub const BUILTIN_PRINTLN: &str = "println";
+pub const BUILTIN_ASSERT: &str = "assert";
+pub const BUILTIN_ABORT: &str = "abort";
+pub const BUILTIN_EXIT: &str = "exit";
+pub const BUILTIN_ABS: &str = "abs";
+pub const BUILTIN_MIN: &str = "min";
+pub const BUILTIN_MAX: &str = "max";
+pub const BUILTIN_SQRT: &str = "sqrt";
+pub const BUILTIN_POW: &str = "pow";
+
+pub const BUILTINS: &[&str] = &[
+    BUILTIN_LEN,
+    BUILTIN_ALLOC,
+    BUILTIN_FREE,
+    BUILTIN_PRINT,
+    BUILTIN_PRINTLN,
+    BUILTIN_ASSERT,
+    BUILTIN_ABORT,
+    BUILTIN_EXIT,
+    BUILTIN_ABS,
+    BUILTIN_MIN,
+    BUILTIN_MAX,
+    BUILTIN_SQRT,
+    BUILTIN_POW,
+];
+
+// resolved by the type of their first argument, into `print.u8` and the like,
+// as signed and unsigned integers are the same to LLVM
+pub const OVERLOADED_BUILTINS: &[&str] = &[
+    BUILTIN_PRINT,
+    BUILTIN_PRINTLN,
+    BUILTIN_ABS,
+    BUILTIN_MIN,
+    BUILTIN_MAX,
+];
+
+// the calling convention of extern functions, the only one supported
+pub const ABI_C: &str = "C";
+
+// the function a program starts in
+pub const ENTRY_POINT: &str = "main";
@@ -1,8 +1,16 @@
 pub const DECLARATION_FUNCTION: &str = "fun";
 pub const DECLARATION_VARIABLE: &str = "let";
 pub const DECLARATION_CONSTANT: &str = "const";
+pub const DECLARATION_STRUCT: &str = "struct";
+pub const DECLARATION_ENUM: &str = "enum";
+pub const DECLARATION_EXTERN: &str = "extern";
+pub const DECLARATION_PUBLIC: &str = "pub";
+pub const DECLARATION_IMPORT: &str = "import";
 
 pub const STATEMENT_RETURN: &str = "return";
 pub const STATEMENT_IF: &str = "if";
 pub const STATEMENT_ELSE: &str = "else";
 pub const STATEMENT_WHILE: &str = "while";
+pub const STATEMENT_MATCH: &str = "match";
+
+pub const EXPRESSION_CAST: &str = "as";
@@ -0,0 +1,49 @@
+use std::fmt;
+
+use crate::span::Span;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Diagnostic {
+    message: String,
+    span: Option<Span>,
+}
+
+impl Diagnostic {
+    pub fn new(message: String, span: Option<Span>) -> Self {
+        Self { message, span }
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn span(&self) -> Option<Span> {
+        self.span
+    }
+
+    pub fn offset(self, by: usize) -> Self {
+        Self {
+            message: self.message,
+            span: self.span.map(|span| span.offset(by)),
+        }
+    }
+
+    pub fn render(&self, filename: &str, source: &str) -> String {
+        match self.span {
+            Some(span) => {
+                let (line, column) = span.line_col(source);
+                format!("error: {}\n  --> {filename}:{line}:{column}", self.message)
+            }
+            None => format!("error: {}\n  --> {filename}", self.message),
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.span {
+            Some(span) => write!(f, "{} at {}..{}", self.message, span.start, span.end),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
@@ -0,0 +1,48 @@
+use std::fmt::Display;
+
+const INDENT: &str = "  ";
+
+// renders nested structures as an indented tree, one node per line
+#[derive(Default)]
+pub struct TreeWriter {
+    output: String,
+    depth: usize,
+}
+
+impl TreeWriter {
+    pub fn new() -> Self {
+        Self {
+            output: String::new(),
+            depth: 0,
+        }
+    }
+
+    pub fn leaf(&mut self, label: impl Display) {
+        self.output.push_str(&INDENT.repeat(self.depth));
+        self.output.push_str(&label.to_string());
+        self.output.push('\n');
+    }
+
+    pub fn node(&mut self, label: impl Display, children: impl FnOnce(&mut Self)) {
+        self.leaf(label);
+
+        self.depth += 1;
+        children(self);
+        self.depth -= 1;
+    }
+
+    pub fn finish(self) -> String {
+        self.output
+    }
+}
+
+pub trait TreeDump {
+    fn dump(&self, writer: &mut TreeWriter);
+
+    fn dump_tree(&self) -> String {
+        let mut writer = TreeWriter::new();
+        self.dump(&mut writer);
+
+        writer.finish()
+    }
+}
@@ -1,3 +1,6 @@
 pub mod constants;
+pub mod diagnostic;
+pub mod dump;
+pub mod span;
 pub mod types;
 pub mod util;
@@ -1,4 +1,7 @@
+use std::fmt;
+
 #[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum Type {
     String,
     Int8,
@@ -14,6 +17,15 @@ pub enum Type {
     Bool,
     Char,
     Void,
+    // a user-defined struct or enum, by name
+    Named(String),
+    // a fixed-size array of an element type
+    Array(Box<Type>, u64),
+    // a view of consecutive elements of an array, a pointer to the first one and a length
+    Slice(Box<Type>),
+    Tuple(Vec<Type>),
+    // a raw pointer to a value of the type
+    Pointer(Box<Type>),
 }
 
 impl Type {
@@ -36,4 +48,113 @@ impl Type {
             _ => None,
         }
     }
+
+    pub fn is_integer(&self) -> bool {
+        matches!(
+            self,
+            Self::Int8
+                | Self::UInt8
+                | Self::Int16
+                | Self::UInt16
+                | Self::Int32
+                | Self::UInt32
+                | Self::Int64
+                | Self::UInt64
+        )
+    }
+
+    pub fn is_signed(&self) -> bool {
+        matches!(
+            self,
+            Self::Int8 | Self::Int16 | Self::Int32 | Self::Int64 | Self::Float32 | Self::Float64
+        )
+    }
+
+    pub fn is_float(&self) -> bool {
+        matches!(self, Self::Float32 | Self::Float64)
+    }
+
+    pub fn is_numeric(&self) -> bool {
+        self.is_integer() || self.is_float()
+    }
+
+    // the type in C, with the integers of <stdint.h>, pointers to types C does
+    // not know become `void *`, strings, structs, enums, arrays, slices and tuples have none
+    pub fn c_name(&self) -> Option<String> {
+        let name = match self {
+            Self::Int8 => "int8_t",
+            Self::UInt8 => "uint8_t",
+            Self::Int16 => "int16_t",
+            Self::UInt16 => "uint16_t",
+            Self::Int32 => "int32_t",
+            Self::UInt32 => "uint32_t",
+            Self::Int64 => "int64_t",
+            Self::UInt64 => "uint64_t",
+            Self::Float32 => "float",
+            Self::Float64 => "double",
+            Self::Bool => "bool",
+            // a unicode scalar value
+            Self::Char => "uint32_t",
+            Self::Void => "void",
+            Self::Pointer(pointee) => {
+                let pointee = pointee.c_name().unwrap_or_else(|| "void".to_string());
+
+                return Some(if pointee.ends_with('*') {
+                    format!("{pointee}*")
+                } else {
+                    format!("{pointee} *")
+                });
+            }
+            Self::String | Self::Named(_) | Self::Array(..) | Self::Slice(_) | Self::Tuple(_) => {
+                return None
+            }
+        };
+
+        Some(name.to_string())
+    }
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Array(element, size) => return write!(f, "[{element}; {size}]"),
+            Self::Slice(element) => return write!(f, "[{element}]"),
+            Self::Pointer(pointee) => return write!(f, "*{pointee}"),
+            // a single element keeps its comma, `(i32)` is only an `i32`
+            Self::Tuple(elements) if elements.len() == 1 => {
+                return write!(f, "({},)", elements[0]);
+            }
+            Self::Tuple(elements) => {
+                let elements = elements
+                    .iter()
+                    .map(|element| element.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ");
+
+                return write!(f, "({elements})");
+            }
+            _ => {}
+        }
+
+        let name = match self {
+            Self::String => "String",
+            Self::Int8 => "i8",
+            Self::UInt8 => "u8",
+            Self::Int16 => "i16",
+            Self::UInt16 => "u16",
+            Self::Int32 => "i32",
+            Self::UInt32 => "u32",
+            Self::Int64 => "i64",
+            Self::UInt64 => "u64",
+            Self::Float32 => "f32",
+            Self::Float64 => "f64",
+            Self::Bool => "bool",
+            Self::Char => "char",
+            Self::Void => "void",
+            Self::Named(name) => name,
+            Self::Array(..) | Self::Slice(_) | Self::Tuple(_) | Self::Pointer(_) => unreachable!(),
+        };
+
+        write!(f, "{name}")
+    }
 }
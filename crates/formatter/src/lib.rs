@@ -0,0 +1,311 @@
+use parser::cst::{
+    red::{SyntaxElement, SyntaxNode, SyntaxToken},
+    ConcreteSyntaxTree, SyntaxKind,
+};
+
+const INDENT: &str = "  ";
+
+pub struct Formatter {}
+
+impl Default for Formatter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Formatter {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    pub fn run(&self, cst: &ConcreteSyntaxTree) -> String {
+        let mut writer = Writer::new();
+
+        writer.statements(cst.root());
+
+        let mut output = writer.output;
+        output.truncate(output.trim_end_matches('\n').len());
+        if !output.is_empty() {
+            output.push('\n');
+        }
+
+        output
+    }
+}
+
+struct Writer {
+    output: String,
+    indent: usize,
+    line_start: bool,
+    // nothing was written since the file or block started
+    block_start: bool,
+    statement_start: bool,
+    // newlines in the source since the last written token
+    newlines: usize,
+    previous: Option<SyntaxToken>,
+    previous_unary: bool,
+    // the previous token ends a "::" of a path like `util::strings`
+    previous_path: bool,
+}
+
+impl Writer {
+    fn new() -> Self {
+        Self {
+            output: String::new(),
+            indent: 0,
+            line_start: true,
+            block_start: true,
+            statement_start: false,
+            newlines: 0,
+            previous: None,
+            previous_unary: false,
+            previous_path: false,
+        }
+    }
+
+    // children of the root or a block, every node is a statement
+    fn statements(&mut self, node: &SyntaxNode) {
+        for child in node.children() {
+            match child {
+                SyntaxElement::Node(node) => self.statement(&node),
+                SyntaxElement::Token(token) if token.kind() == SyntaxKind::EOF => {}
+                SyntaxElement::Token(token) => {
+                    // stray tokens, like an unbalanced brace
+                    if !token.kind().is_trivia() {
+                        self.statement_start = true;
+                    }
+
+                    self.token(&token);
+                }
+            }
+        }
+    }
+
+    fn statement(&mut self, node: &SyntaxNode) {
+        self.statement_start = true;
+
+        if node.kind() == SyntaxKind::Block {
+            self.block(node);
+        } else {
+            self.inline(node);
+        }
+    }
+
+    fn inline(&mut self, node: &SyntaxNode) {
+        for child in node.children() {
+            match child {
+                SyntaxElement::Node(node) if node.kind() == SyntaxKind::Block => self.block(&node),
+                SyntaxElement::Node(node) if node.kind() == SyntaxKind::List => self.list(&node),
+                SyntaxElement::Node(node) => self.inline(&node),
+                SyntaxElement::Token(token) => self.token(&token),
+            }
+        }
+    }
+
+    fn block(&mut self, node: &SyntaxNode) {
+        let mut opened = false;
+        let mut closed = false;
+
+        for child in node.children() {
+            match child {
+                SyntaxElement::Token(token) if !opened && token.text() == "{" => {
+                    self.token(&token);
+
+                    opened = true;
+                    self.indent += 1;
+                    self.block_start = true;
+                }
+                SyntaxElement::Token(token) if opened && token.text() == "}" => {
+                    closed = true;
+                    self.indent -= 1;
+
+                    // empty blocks stay on one line
+                    if !self.block_start {
+                        self.line();
+                    }
+
+                    self.write(token.text());
+                    self.remember(&token);
+                    self.block_start = false;
+                }
+                SyntaxElement::Node(node) => self.statement(&node),
+                SyntaxElement::Token(token) => self.token(&token),
+            }
+        }
+
+        // unterminated block
+        if opened && !closed {
+            self.indent -= 1;
+        }
+    }
+
+    // one item per line, like the statements of a block
+    fn list(&mut self, node: &SyntaxNode) {
+        let mut opened = false;
+        let mut closed = false;
+
+        for child in node.children() {
+            match child {
+                SyntaxElement::Token(token) if !opened && token.text() == "{" => {
+                    self.token(&token);
+
+                    opened = true;
+                    self.indent += 1;
+                    self.block_start = true;
+                    self.statement_start = true;
+                }
+                SyntaxElement::Token(token) if opened && token.text() == "}" => {
+                    closed = true;
+                    self.indent -= 1;
+                    self.statement_start = false;
+
+                    if !self.block_start {
+                        self.line();
+                    }
+
+                    self.write(token.text());
+                    self.remember(&token);
+                    self.block_start = false;
+                }
+                SyntaxElement::Token(token) if token.text() == "," => {
+                    self.token(&token);
+                    self.statement_start = true;
+                }
+                SyntaxElement::Node(node) if node.kind() == SyntaxKind::Block => self.block(&node),
+                SyntaxElement::Node(node) if node.kind() == SyntaxKind::List => self.list(&node),
+                SyntaxElement::Node(node) => self.inline(&node),
+                SyntaxElement::Token(token) => self.token(&token),
+            }
+        }
+
+        if opened && !closed {
+            self.indent -= 1;
+        }
+    }
+
+    fn token(&mut self, token: &SyntaxToken) {
+        match token.kind() {
+            SyntaxKind::Whitespace => {
+                self.newlines += token.text().matches('\n').count();
+            }
+            SyntaxKind::Comment => self.comment(token),
+            SyntaxKind::EOF => {}
+            _ => {
+                if self.statement_start {
+                    self.start_line();
+                    self.statement_start = false;
+                } else if !self.line_start && self.space_before(token) {
+                    self.output.push(' ');
+                }
+
+                self.write(token.text());
+                self.remember(token);
+                self.block_start = false;
+            }
+        }
+    }
+
+    fn comment(&mut self, token: &SyntaxToken) {
+        let own_line = self.newlines > 0 || self.previous.is_none();
+
+        if own_line {
+            self.start_line();
+            self.write(token.text());
+            self.line();
+        } else {
+            self.output.push(' ');
+            self.write(token.text());
+
+            if token.text().starts_with("//") {
+                self.line();
+            }
+        }
+
+        self.newlines = 0;
+        self.block_start = false;
+    }
+
+    // a new line, keeping at most one blank line from the source
+    fn start_line(&mut self) {
+        if self.output.is_empty() {
+            self.line_start = true;
+        } else if self.newlines > 1 && !self.block_start {
+            self.line();
+            if !self.output.ends_with("\n\n") {
+                self.output.push('\n');
+            }
+        } else {
+            self.line();
+        }
+    }
+
+    fn line(&mut self) {
+        if !self.line_start {
+            self.output.push('\n');
+            self.line_start = true;
+        }
+    }
+
+    fn write(&mut self, text: &str) {
+        if self.line_start {
+            self.output.push_str(&INDENT.repeat(self.indent));
+            self.line_start = false;
+        }
+
+        self.output.push_str(text);
+    }
+
+    fn remember(&mut self, token: &SyntaxToken) {
+        self.previous_unary = token.kind() == SyntaxKind::Operator
+            && !self.is_arrow(token)
+            && self.previous.as_ref().is_none_or(starts_operand);
+        self.previous_path = token.text() == ":"
+            && self.previous.as_ref().is_some_and(|previous| {
+                previous.text() == ":" && previous.span().end == token.span().start
+            });
+        self.previous = Some(token.clone());
+        self.newlines = 0;
+    }
+
+    fn space_before(&self, token: &SyntaxToken) -> bool {
+        let Some(previous) = &self.previous else {
+            return false;
+        };
+
+        match (previous.text(), token.text()) {
+            _ if self.previous_path => false,
+            (_, ";" | "," | ")" | "]" | "." | ":") => false,
+            ("(" | "[" | ".", _) => false,
+            _ if self.is_arrow(token) => false,
+            (_, "(") => {
+                previous.kind() != SyntaxKind::Identifier
+                    && previous.text() != ")"
+                    && !self.previous_unary
+            }
+            (_, "[") => {
+                !matches!(
+                    previous.kind(),
+                    SyntaxKind::Identifier | SyntaxKind::Literal
+                ) && !matches!(previous.text(), ")" | "]")
+            }
+            _ => !self.previous_unary,
+        }
+    }
+
+    // "->" and "=>" are lexed as two operators
+    fn is_arrow(&self, token: &SyntaxToken) -> bool {
+        token.text() == ">"
+            && self.previous.as_ref().is_some_and(|previous| {
+                matches!(previous.text(), "-" | "=") && previous.span().end == token.span().start
+            })
+    }
+}
+
+// whether an operator after this token is a prefix one
+fn starts_operand(previous: &SyntaxToken) -> bool {
+    match previous.kind() {
+        SyntaxKind::Operator | SyntaxKind::Keyword => true,
+        SyntaxKind::Punctuation => !matches!(previous.text(), ")" | "]" | "}"),
+        _ => false,
+    }
+}
@@ -0,0 +1,232 @@
+use std::{fs, path::PathBuf};
+
+use formatter::Formatter;
+use lexer::lexer::{token::Token, Lexer};
+use parser::Parser;
+
+fn format(code: &str) -> String {
+    let tokens = Lexer::new().run_lossless(code);
+    let cst = Parser::new().run_lossless(&tokens);
+
+    Formatter::new().run(&cst)
+}
+
+fn significant_tokens(code: &str) -> Vec<Token> {
+    Lexer::new()
+        .run(code)
+        .into_iter()
+        .filter(|token| !matches!(token, Token::Comment(_)))
+        .collect()
+}
+
+// the source files in the directory and the projects inside it
+fn sources(directory: &str) -> Vec<PathBuf> {
+    let mut paths = vec![];
+    let mut pending = vec![PathBuf::from(directory)];
+
+    while let Some(directory) = pending.pop() {
+        for entry in fs::read_dir(directory).unwrap() {
+            let path = entry.unwrap().path();
+
+            if path.is_dir() {
+                pending.push(path);
+            } else if path
+                .extension()
+                .is_some_and(|extension| extension == "bloom")
+            {
+                paths.push(path);
+            }
+        }
+    }
+
+    paths
+}
+
+#[test]
+pub fn formatter_is_idempotent_on_examples() {
+    for path in sources("../../examples") {
+        let code = fs::read_to_string(&path).unwrap();
+
+        let formatted = format(&code);
+
+        assert_eq!(format(&formatted), formatted, "{path:?} is not idempotent");
+        assert_eq!(
+            significant_tokens(&formatted),
+            significant_tokens(&code),
+            "{path:?} changed meaning"
+        );
+        assert_eq!(formatted, code, "{path:?} is not formatted");
+    }
+}
+
+#[test]
+pub fn formatter_normalises_layout() {
+    let code = r#"fun add(a:i32,b :i32)->i32{
+return a+b*2;}
+fun   main( ) {
+    let x=-1;let y = add( x , 2 );
+
+
+
+        if x<y{x=x+1;}else if x==y {
+} else{ y=s[ 0 ]; }
+  while(x < 10){x = x + 1;}
+}"#;
+
+    let expected = r#"fun add(a: i32, b: i32) -> i32 {
+  return a + b * 2;
+}
+fun main() {
+  let x = -1;
+  let y = add(x, 2);
+
+  if x < y {
+    x = x + 1;
+  } else if x == y {} else {
+    y = s[0];
+  }
+  while (x < 10) {
+    x = x + 1;
+  }
+}
+"#;
+
+    assert_eq!(format(code), expected);
+    assert_eq!(format(expected), expected);
+}
+
+#[test]
+pub fn formatter_spaces_minus() {
+    let code = r#"fun main() -> i32 {
+  let y = f(x) -1;
+  let z = xs[0]-1;
+  let w = (y)- 1;
+  let v = z as i32 -1;
+  let x = - 5;
+  return y - -1;
+}
+"#;
+
+    let expected = r#"fun main() -> i32 {
+  let y = f(x) - 1;
+  let z = xs[0] - 1;
+  let w = (y) - 1;
+  let v = z as i32 - 1;
+  let x = -5;
+  return y - -1;
+}
+"#;
+
+    assert_eq!(format(code), expected);
+    assert_eq!(format(expected), expected);
+}
+
+#[test]
+pub fn formatter_preserves_comments() {
+    let code = r#"// leading comment
+fun main() { // opening
+    let a = 1;   // trailing
+
+
+    /* block
+       comment */
+  let b = a /* inline */ + 1;
+      // before closing
+}
+// end of file
+"#;
+
+    let expected = r#"// leading comment
+fun main() { // opening
+  let a = 1; // trailing
+
+  /* block
+       comment */
+  let b = a /* inline */ + 1;
+  // before closing
+}
+// end of file
+"#;
+
+    assert_eq!(format(code), expected);
+    assert_eq!(format(expected), expected);
+}
+
+#[test]
+pub fn formatter_handles_block_expressions_and_unary_operators() {
+    let code = "fun main()->i32{let d={let b=2;b};return -d+(-d);}";
+
+    let expected = r#"fun main() -> i32 {
+  let d = {
+    let b = 2;
+    b
+  };
+  return -d + (-d);
+}
+"#;
+
+    assert_eq!(format(code), expected);
+}
+
+#[test]
+pub fn formatter_keeps_paths_together() {
+    let code = "import util :: strings;\nfun main() {\n  let shape = Shape :: Dot;\n}\n";
+    let expected = "import util::strings;\nfun main() {\n  let shape = Shape::Dot;\n}\n";
+
+    assert_eq!(format(code), expected);
+    assert_eq!(format(expected), expected);
+}
+
+#[test]
+pub fn formatter_lays_out_types_and_matches() {
+    let code = r#"pub struct Point{x:i32,y:i64}
+enum Shape{Circle(i32),Rect{w:i32,h:i32},Empty}
+fun area(s:Shape)->i32{match s{Shape::Circle(r) if r>10=>100,Shape::Rect{w,h}=>w*h,Shape::Empty=>{let a=0;a}}}
+fun main(){let p=Point{x:1,y:2};let flag=match p.x{1=>true,_=>false};}"#;
+
+    let expected = r#"pub struct Point {
+  x: i32,
+  y: i64
+}
+enum Shape {
+  Circle(i32),
+  Rect { w: i32, h: i32 },
+  Empty
+}
+fun area(s: Shape) -> i32 {
+  match s {
+    Shape::Circle(r) if r > 10 => 100,
+    Shape::Rect { w, h } => w * h,
+    Shape::Empty => {
+      let a = 0;
+      a
+    }
+  }
+}
+fun main() {
+  let p = Point { x: 1, y: 2 };
+  let flag = match p.x {
+    1 => true,
+    _ => false
+  };
+}
+"#;
+
+    assert_eq!(format(code), expected);
+    assert_eq!(format(expected), expected);
+}
+
+#[test]
+pub fn formatter_keeps_arrays_inline() {
+    let code = "fun main(){let xs=[1,2,3];let grid=[[0;2];3];xs[0]=grid[1][0];}";
+
+    let expected = r#"fun main() {
+  let xs = [1, 2, 3];
+  let grid = [[0; 2]; 3];
+  xs[0] = grid[1][0];
+}
+"#;
+
+    assert_eq!(format(code), expected);
+    assert_eq!(format(expected), expected);
+}
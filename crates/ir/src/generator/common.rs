@@ -1,11 +1,16 @@
+use std::collections::HashMap;
+
 use common::types::Type;
 use inkwell::{
     context::Context,
-    types::BasicTypeEnum,
-    values::{BasicValueEnum, PointerValue},
+    module::{Linkage, Module},
+    types::{BasicType, BasicTypeEnum, StructType},
+    values::{BasicValueEnum, FunctionValue, PointerValue},
     AddressSpace,
 };
-use preprocessor::last::expression::Literal;
+use preprocessor::last::{declaration::Variant, expression::Literal};
+
+pub const STRING_TYPE_NAME: &str = "str";
 
 #[derive(Debug)]
 pub struct VariableData<'ctx> {
@@ -27,6 +32,32 @@ impl<'ctx> VariableData<'ctx> {
     }
 }
 
+pub fn string_type(context: &Context) -> StructType<'_> {
+    context
+        .get_struct_type(STRING_TYPE_NAME)
+        .unwrap_or_else(|| {
+            // { ptr, len }
+            let string_type = context.opaque_struct_type(STRING_TYPE_NAME);
+            string_type.set_body(
+                &[
+                    context.ptr_type(AddressSpace::default()).into(),
+                    context.i64_type().into(),
+                ],
+                false,
+            );
+            string_type
+        })
+}
+
+pub fn is_string_value(value: &BasicValueEnum) -> bool {
+    value.is_struct_value()
+        && value
+            .into_struct_value()
+            .get_type()
+            .get_name()
+            .is_some_and(|name| name.to_bytes() == STRING_TYPE_NAME.as_bytes())
+}
+
 pub fn type_for<'ctx>(context: &'ctx Context, ty: &Type) -> BasicTypeEnum<'ctx> {
     match ty {
         Type::Bool => context.bool_type().into(),
@@ -37,17 +68,180 @@ pub fn type_for<'ctx>(context: &'ctx Context, ty: &Type) -> BasicTypeEnum<'ctx>
         Type::Int32 => context.i32_type().into(),
         Type::Int64 => context.i64_type().into(),
         Type::Int8 => context.i8_type().into(),
-        Type::String => context.ptr_type(AddressSpace::default()).into(),
+        Type::String => string_type(context).into(),
         Type::UInt16 => context.i16_type().into(),
         Type::UInt32 => context.i32_type().into(),
         Type::UInt64 => context.i64_type().into(),
         Type::UInt8 => context.i8_type().into(),
         Type::Void => unreachable!(),
+        Type::Named(name) => context
+            .get_struct_type(name)
+            .unwrap_or_else(|| panic!("type `{name}` is not declared"))
+            .into(),
+        Type::Array(element, length) => {
+            type_for(context, element).array_type(*length as u32).into()
+        }
+        // `{ ptr, len }` like strings, but anonymous, the element type is given on every access
+        Type::Slice(_) => context
+            .struct_type(
+                &[
+                    context.ptr_type(AddressSpace::default()).into(),
+                    context.i64_type().into(),
+                ],
+                false,
+            )
+            .into(),
+        // tuples are anonymous structs
+        Type::Tuple(elements) => context
+            .struct_type(
+                &elements
+                    .iter()
+                    .map(|element| type_for(context, element))
+                    .collect::<Vec<BasicTypeEnum>>(),
+                false,
+            )
+            .into(),
+        // pointers are opaque, the pointee type is given on every load and store
+        Type::Pointer(_) => context.ptr_type(AddressSpace::default()).into(),
+    }
+}
+
+// allocas in the entry block are promoted to registers by mem2reg,
+// including the ones of variables declared in loop bodies
+pub fn build_entry_alloca<'ctx>(
+    context: &'ctx Context,
+    function: FunctionValue<'ctx>,
+    ty: BasicTypeEnum<'ctx>,
+    name: &str,
+) -> PointerValue<'ctx> {
+    let entry = function.get_first_basic_block().unwrap();
+    let builder = context.create_builder();
+
+    match entry.get_first_instruction() {
+        Some(instruction) => builder.position_before(&instruction),
+        None => builder.position_at_end(entry),
+    }
+
+    builder.build_alloca(ty, name).unwrap()
+}
+
+// a variant is laid out as `{ tag, fields... }`, the enum as `{ tag, [payload x i64] }`
+// with a payload large enough for every variant, values are converted through memory
+pub fn variant_type<'ctx>(context: &'ctx Context, name: &str, variant: &str) -> StructType<'ctx> {
+    context
+        .get_struct_type(&format!("{name}.{variant}"))
+        .unwrap_or_else(|| panic!("variant `{name}::{variant}` is not declared"))
+}
+
+pub fn declare_enum_types(context: &Context, name: &str, variants: &[Variant]) {
+    for variant in variants {
+        context.opaque_struct_type(&format!("{name}.{}", variant.name));
+    }
+
+    context.opaque_struct_type(name);
+}
+
+pub fn define_enum_types(context: &Context, name: &str, variants: &[Variant], sizes: &TypeSizes) {
+    let tag = context.i32_type().into();
+
+    for variant in variants {
+        let fields = std::iter::once(tag)
+            .chain(variant.fields.iter().map(|(_, ty)| type_for(context, ty)))
+            .collect::<Vec<BasicTypeEnum>>();
+
+        variant_type(context, name, &variant.name).set_body(&fields, false);
+    }
+
+    let payload = sizes.payload(variants);
+
+    context.get_struct_type(name).unwrap().set_body(
+        &[
+            tag,
+            context
+                .i64_type()
+                .array_type(payload.div_ceil(8) as u32)
+                .into(),
+        ],
+        false,
+    );
+}
+
+// upper bounds of the sizes of the declared types, every field taking
+// a multiple of eight bytes, which covers the padding of any layout
+pub struct TypeSizes {
+    structs: HashMap<String, Vec<Type>>,
+    enums: HashMap<String, Vec<Vec<Type>>>,
+}
+
+impl TypeSizes {
+    pub fn new() -> Self {
+        Self {
+            structs: HashMap::new(),
+            enums: HashMap::new(),
+        }
+    }
+
+    pub fn add_struct(&mut self, name: &str, fields: &[(String, Type)]) {
+        self.structs.insert(
+            name.to_string(),
+            fields.iter().map(|(_, ty)| ty.clone()).collect(),
+        );
+    }
+
+    pub fn add_enum(&mut self, name: &str, variants: &[Variant]) {
+        self.enums.insert(
+            name.to_string(),
+            variants
+                .iter()
+                .map(|variant| variant.fields.iter().map(|(_, ty)| ty.clone()).collect())
+                .collect(),
+        );
+    }
+
+    pub fn fields(&self, fields: &[(String, Type)]) -> u64 {
+        fields.iter().map(|(_, ty)| self.size(ty)).sum()
+    }
+
+    // the bytes an enum keeps for the fields of its largest variant
+    pub fn payload(&self, variants: &[Variant]) -> u64 {
+        variants
+            .iter()
+            .map(|variant| self.fields(&variant.fields))
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn size(&self, ty: &Type) -> u64 {
+        match ty {
+            Type::String | Type::Slice(_) => 16,
+            Type::Named(name) => match (self.structs.get(name), self.enums.get(name)) {
+                (Some(fields), _) => fields.iter().map(|ty| self.size(ty)).sum(),
+                // the tag and the largest payload
+                (_, Some(variants)) => {
+                    8 + variants
+                        .iter()
+                        .map(|fields| fields.iter().map(|ty| self.size(ty)).sum())
+                        .max()
+                        .unwrap_or(0)
+                }
+                _ => panic!("type `{name}` is not declared"),
+            },
+            Type::Array(element, length) => length * self.size(element),
+            Type::Tuple(elements) => elements.iter().map(|ty| self.size(ty)).sum(),
+            _ => 8,
+        }
+    }
+}
+
+impl Default for TypeSizes {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 pub fn generate_for_literal<'ctx>(
     context: &'ctx Context,
+    module: &Module<'ctx>,
     literal: &'ctx Literal,
 ) -> BasicValueEnum<'ctx> {
     match literal {
@@ -78,6 +272,29 @@ pub fn generate_for_literal<'ctx>(
         Literal::UInt64(u64) => context.i64_type().const_int(*u64 as u64, false).into(),
         Literal::Float32(f32) => context.f32_type().const_float(*f32 as f64).into(),
         Literal::Float64(f64) => context.f64_type().const_float(*f64).into(),
-        Literal::String(string) => context.const_string(string.as_bytes(), true).into(),
+        // the bytes end with a NUL, which the length leaves out, so that C can read them
+        Literal::String(string) => {
+            let bytes = string.as_bytes();
+
+            let global = module.add_global(
+                context.i8_type().array_type(bytes.len() as u32 + 1),
+                None,
+                ".str",
+            );
+            global.set_initializer(&context.const_string(bytes, true));
+            global.set_linkage(Linkage::Private);
+            global.set_constant(true);
+            global.set_unnamed_addr(true);
+
+            string_type(context)
+                .const_named_struct(&[
+                    global.as_pointer_value().into(),
+                    context
+                        .i64_type()
+                        .const_int(bytes.len() as u64, false)
+                        .into(),
+                ])
+                .into()
+        }
     }
 }
@@ -0,0 +1,584 @@
+use std::{cell::RefCell, collections::HashMap, path::Path};
+
+use common::{span::Span, types::Type};
+use inkwell::{
+    builder::Builder,
+    context::Context,
+    debug_info::{
+        AsDIScope, DICompileUnit, DIDerivedType, DIFile, DIFlags, DIFlagsConstants, DILocation,
+        DISubprogram, DIType, DWARFEmissionKind, DWARFSourceLanguage, DebugInfoBuilder,
+    },
+    module::{FlagBehavior, Module},
+    types::BasicTypeEnum,
+    values::{FunctionValue, PointerValue},
+    AddressSpace,
+};
+use preprocessor::last::declaration::Variant;
+
+use super::common::STRING_TYPE_NAME;
+
+const DEBUG_INFO_VERSION: u64 = 3;
+const DWARF_VERSION: u64 = 4;
+
+// DW_ATE_* type encodings
+const DW_ATE_BOOLEAN: u32 = 0x02;
+const DW_ATE_FLOAT: u32 = 0x04;
+const DW_ATE_SIGNED: u32 = 0x05;
+const DW_ATE_UNSIGNED: u32 = 0x08;
+const DW_ATE_UTF: u32 = 0x10;
+
+const POINTER_BITS: u64 = 64;
+
+// a debug type with the size and alignment of its values, in bits
+#[derive(Clone, Copy)]
+struct Described<'ctx> {
+    ty: DIType<'ctx>,
+    size: u64,
+    align: u32,
+}
+
+// a source file, its spans start at `offset`
+struct SourceFile<'ctx> {
+    file: DIFile<'ctx>,
+    source: String,
+    offset: usize,
+}
+
+// DWARF for the source files of a module, spans are turned into lines and columns
+// of the file they point into
+pub struct DebugInfo<'ctx> {
+    context: &'ctx Context,
+    builder: DebugInfoBuilder<'ctx>,
+    compile_unit: DICompileUnit<'ctx>,
+    files: Vec<SourceFile<'ctx>>,
+    // declared types are described the first time a variable is of them
+    structs: RefCell<HashMap<String, Vec<(String, Type)>>>,
+    enums: RefCell<HashMap<String, (Vec<Variant>, u64)>>,
+    types: RefCell<HashMap<String, Described<'ctx>>>,
+    // pointers into the types being described, pointing to them once they are
+    pending: RefCell<HashMap<String, Vec<DIDerivedType<'ctx>>>>,
+}
+
+impl<'ctx> DebugInfo<'ctx> {
+    pub fn new(context: &'ctx Context, module: &Module<'ctx>, path: &str, source: &str) -> Self {
+        let (filename, directory) = split_path(path);
+
+        module.add_basic_value_flag(
+            "Debug Info Version",
+            FlagBehavior::Warning,
+            context.i32_type().const_int(DEBUG_INFO_VERSION, false),
+        );
+        module.add_basic_value_flag(
+            "Dwarf Version",
+            FlagBehavior::Warning,
+            context.i32_type().const_int(DWARF_VERSION, false),
+        );
+
+        let (builder, compile_unit) = module.create_debug_info_builder(
+            true,
+            DWARFSourceLanguage::C,
+            &filename,
+            &directory,
+            "starbloom",
+            false,
+            "",
+            0,
+            "",
+            DWARFEmissionKind::Full,
+            0,
+            false,
+            false,
+            "",
+            "",
+        );
+
+        Self {
+            context,
+            builder,
+            compile_unit,
+            files: vec![SourceFile {
+                file: compile_unit.get_file(),
+                source: source.to_string(),
+                offset: 0,
+            }],
+            structs: RefCell::new(HashMap::new()),
+            enums: RefCell::new(HashMap::new()),
+            types: RefCell::new(HashMap::new()),
+            pending: RefCell::new(HashMap::new()),
+        }
+    }
+
+    // adds a file whose spans come after the ones of the files added before it
+    pub fn add_file(&mut self, path: &str, source: &str, offset: usize) {
+        let (filename, directory) = split_path(path);
+
+        self.files.push(SourceFile {
+            file: self.builder.create_file(&filename, &directory),
+            source: source.to_string(),
+            offset,
+        });
+    }
+
+    pub fn add_struct(&self, name: &str, fields: &[(String, Type)]) {
+        self.structs
+            .borrow_mut()
+            .insert(name.to_string(), fields.to_vec());
+    }
+
+    // `payload` is the number of bytes the enum keeps for the fields of its variants
+    pub fn add_enum(&self, name: &str, variants: &[Variant], payload: u64) {
+        self.enums
+            .borrow_mut()
+            .insert(name.to_string(), (variants.to_vec(), payload));
+    }
+
+    pub fn subprogram(
+        &self,
+        function: FunctionValue<'ctx>,
+        name: &str,
+        span: Option<Span>,
+        parameters: &[(String, Type)],
+        return_type: &Type,
+    ) -> DISubprogram<'ctx> {
+        let file = self.file(span).file;
+        let (line, _) = self.line_col(span);
+
+        let return_type = self.describe(return_type).map(|described| described.ty);
+        let parameter_types = parameters
+            .iter()
+            .filter_map(|(_, ty)| self.describe(ty))
+            .map(|described| described.ty)
+            .collect::<Vec<DIType>>();
+
+        let subroutine_type =
+            self.builder
+                .create_subroutine_type(file, return_type, &parameter_types, DIFlags::ZERO);
+
+        let subprogram = self.builder.create_function(
+            self.compile_unit.as_debug_info_scope(),
+            name,
+            function.get_name().to_str().ok(),
+            file,
+            line,
+            subroutine_type,
+            true,
+            true,
+            line,
+            DIFlags::ZERO,
+            false,
+        );
+
+        function.set_subprogram(subprogram);
+
+        subprogram
+    }
+
+    pub fn location(&self, scope: DISubprogram<'ctx>, span: Option<Span>) -> DILocation<'ctx> {
+        let (line, column) = self.line_col(span);
+
+        self.builder.create_debug_location(
+            self.context,
+            line,
+            column,
+            scope.as_debug_info_scope(),
+            None,
+        )
+    }
+
+    // describes a variable living in `storage` from the builder's position on,
+    // `span` is where it is declared, `ty` its bloom type when it is known
+    #[allow(clippy::too_many_arguments)]
+    pub fn declare_variable(
+        &self,
+        builder: &Builder<'ctx>,
+        scope: DISubprogram<'ctx>,
+        span: Option<Span>,
+        name: &str,
+        storage: PointerValue<'ctx>,
+        storage_type: BasicTypeEnum<'ctx>,
+        ty: Option<&Type>,
+    ) {
+        let ty = match ty {
+            Some(ty) => self.describe(ty).map(|described| described.ty),
+            None => self.di_type(storage_type),
+        };
+
+        let (Some(ty), Some(block), Some(location)) = (
+            ty,
+            builder.get_insert_block(),
+            builder.get_current_debug_location(),
+        ) else {
+            return;
+        };
+
+        let variable = self.builder.create_auto_variable(
+            scope.as_debug_info_scope(),
+            name,
+            self.file(span).file,
+            location.get_line(),
+            ty,
+            true,
+            DIFlags::ZERO,
+            0,
+        );
+
+        self.builder.insert_declare_at_end(
+            storage,
+            Some(variable),
+            Some(self.builder.create_expression(vec![])),
+            location,
+            block,
+        );
+    }
+
+    pub fn finalize(&self) {
+        self.builder.finalize();
+    }
+
+    // the debug type of a bloom type, aggregates are laid out like C lays them out
+    // on 64-bit targets, which is how LLVM lays out their types there
+    fn describe(&self, ty: &Type) -> Option<Described<'ctx>> {
+        let described = match ty {
+            Type::Void => return None,
+            Type::Bool => self.basic("bool", 8, DW_ATE_BOOLEAN),
+            Type::Char => self.basic("char", 32, DW_ATE_UTF),
+            Type::Float32 => self.basic("f32", 32, DW_ATE_FLOAT),
+            Type::Float64 => self.basic("f64", 64, DW_ATE_FLOAT),
+            Type::Int8 => self.basic("i8", 8, DW_ATE_SIGNED),
+            Type::Int16 => self.basic("i16", 16, DW_ATE_SIGNED),
+            Type::Int32 => self.basic("i32", 32, DW_ATE_SIGNED),
+            Type::Int64 => self.basic("i64", 64, DW_ATE_SIGNED),
+            Type::UInt8 => self.basic("u8", 8, DW_ATE_UNSIGNED),
+            Type::UInt16 => self.basic("u16", 16, DW_ATE_UNSIGNED),
+            Type::UInt32 => self.basic("u32", 32, DW_ATE_UNSIGNED),
+            Type::UInt64 => self.basic("u64", 64, DW_ATE_UNSIGNED),
+            // { ptr, len }
+            Type::String => {
+                if let Some(described) = self.types.borrow().get(STRING_TYPE_NAME) {
+                    return Some(*described);
+                }
+
+                let bytes = self.basic("u8", 8, DW_ATE_UNSIGNED);
+                let described = self.structure(
+                    STRING_TYPE_NAME,
+                    &[
+                        ("ptr".to_string(), self.pointer("*u8", bytes)),
+                        ("len".to_string(), self.basic("i64", 64, DW_ATE_SIGNED)),
+                    ],
+                );
+
+                self.types
+                    .borrow_mut()
+                    .insert(STRING_TYPE_NAME.to_string(), described);
+
+                described
+            }
+            Type::Array(element, length) => {
+                let element = self.describe(element)?;
+
+                Described {
+                    ty: self
+                        .builder
+                        .create_array_type(
+                            element.ty,
+                            element.size * length,
+                            element.align,
+                            std::slice::from_ref(&(0..*length as i64)),
+                        )
+                        .as_type(),
+                    size: element.size * length,
+                    align: element.align,
+                }
+            }
+            // { ptr, len }
+            Type::Slice(element) => {
+                let pointer = self.describe(&Type::Pointer(element.clone()))?;
+
+                self.structure(
+                    &ty.to_string(),
+                    &[
+                        ("ptr".to_string(), pointer),
+                        ("len".to_string(), self.basic("i64", 64, DW_ATE_SIGNED)),
+                    ],
+                )
+            }
+            Type::Tuple(elements) => {
+                let members = elements
+                    .iter()
+                    .enumerate()
+                    .map(|(index, element)| Some((index.to_string(), self.describe(element)?)))
+                    .collect::<Option<Vec<_>>>()?;
+
+                self.structure(&ty.to_string(), &members)
+            }
+            Type::Pointer(pointee) => {
+                // the type pointed to is still being described, the pointer is made once it is
+                if let Type::Named(name) = pointee.as_ref() {
+                    if let Some(pointers) = self.pending.borrow_mut().get_mut(name) {
+                        let placeholder =
+                            unsafe { self.builder.create_placeholder_derived_type(self.context) };
+                        pointers.push(placeholder);
+
+                        return Some(Described {
+                            ty: placeholder.as_type(),
+                            size: POINTER_BITS,
+                            align: POINTER_BITS as u32,
+                        });
+                    }
+                }
+
+                // like `void *` in C, pointers to nothing point to bytes
+                let pointee = self
+                    .describe(pointee)
+                    .unwrap_or_else(|| self.basic("u8", 8, DW_ATE_UNSIGNED));
+
+                self.pointer(&ty.to_string(), pointee)
+            }
+            Type::Named(name) => return Some(self.named(name)),
+        };
+
+        Some(described)
+    }
+
+    fn named(&self, name: &str) -> Described<'ctx> {
+        if let Some(described) = self.types.borrow().get(name) {
+            return *described;
+        }
+
+        self.pending.borrow_mut().insert(name.to_string(), vec![]);
+
+        let fields = self.structs.borrow().get(name).cloned();
+        let described = match fields {
+            Some(fields) => {
+                let members = fields
+                    .iter()
+                    .filter_map(|(field, ty)| Some((field.clone(), self.describe(ty)?)))
+                    .collect::<Vec<_>>();
+
+                self.structure(name, &members)
+            }
+            None => {
+                let (variants, payload) = self
+                    .enums
+                    .borrow()
+                    .get(name)
+                    .cloned()
+                    .unwrap_or_else(|| panic!("type `{name}` is not declared"));
+
+                self.enumeration(name, &variants, payload)
+            }
+        };
+
+        for placeholder in self.pending.borrow_mut().remove(name).unwrap_or_default() {
+            let pointer = self.builder.create_pointer_type(
+                &format!("*{name}"),
+                described.ty,
+                POINTER_BITS,
+                POINTER_BITS as u32,
+                AddressSpace::default(),
+            );
+
+            unsafe {
+                self.builder
+                    .replace_placeholder_derived_type(placeholder, pointer)
+            };
+        }
+
+        self.types.borrow_mut().insert(name.to_string(), described);
+
+        described
+    }
+
+    // the variants share the memory of the enum, each one starting with the tag,
+    // so they are the members of a union
+    fn enumeration(&self, name: &str, variants: &[Variant], payload: u64) -> Described<'ctx> {
+        let tag = self.basic("u32", 32, DW_ATE_UNSIGNED);
+
+        let members =
+            variants
+                .iter()
+                .map(|variant| {
+                    let fields =
+                        std::iter::once(("tag".to_string(), tag))
+                            .chain(variant.fields.iter().filter_map(|(field, ty)| {
+                                Some((field.clone(), self.describe(ty)?))
+                            }))
+                            .collect::<Vec<_>>();
+
+                    let described = self.structure(&format!("{name}::{}", variant.name), &fields);
+
+                    self.member(&variant.name, described, 0)
+                })
+                .collect::<Vec<DIType>>();
+
+        // `{ tag, [payload x i64] }`
+        let size = 64 + payload.div_ceil(8) * 64;
+
+        Described {
+            ty: self
+                .builder
+                .create_union_type(
+                    self.compile_unit.as_debug_info_scope(),
+                    name,
+                    self.compile_unit.get_file(),
+                    0,
+                    size,
+                    64,
+                    DIFlags::ZERO,
+                    &members,
+                    0,
+                    "",
+                )
+                .as_type(),
+            size,
+            align: 64,
+        }
+    }
+
+    // every member is placed at the next offset aligned for its type
+    fn structure(&self, name: &str, members: &[(String, Described<'ctx>)]) -> Described<'ctx> {
+        let mut offset = 0u64;
+        let mut align = 8;
+
+        let elements = members
+            .iter()
+            .map(|(member, described)| {
+                offset = offset.next_multiple_of(described.align as u64);
+                align = align.max(described.align);
+
+                let element = self.member(member, *described, offset);
+                offset += described.size;
+
+                element
+            })
+            .collect::<Vec<DIType>>();
+
+        let size = offset.next_multiple_of(align as u64);
+
+        Described {
+            ty: self
+                .builder
+                .create_struct_type(
+                    self.compile_unit.as_debug_info_scope(),
+                    name,
+                    self.compile_unit.get_file(),
+                    0,
+                    size,
+                    align,
+                    DIFlags::ZERO,
+                    None,
+                    &elements,
+                    0,
+                    None,
+                    "",
+                )
+                .as_type(),
+            size,
+            align,
+        }
+    }
+
+    fn member(&self, name: &str, described: Described<'ctx>, offset: u64) -> DIType<'ctx> {
+        self.builder
+            .create_member_type(
+                self.compile_unit.as_debug_info_scope(),
+                name,
+                self.compile_unit.get_file(),
+                0,
+                described.size,
+                described.align,
+                offset,
+                DIFlags::ZERO,
+                described.ty,
+            )
+            .as_type()
+    }
+
+    fn pointer(&self, name: &str, pointee: Described<'ctx>) -> Described<'ctx> {
+        Described {
+            ty: self
+                .builder
+                .create_pointer_type(
+                    name,
+                    pointee.ty,
+                    POINTER_BITS,
+                    POINTER_BITS as u32,
+                    AddressSpace::default(),
+                )
+                .as_type(),
+            size: POINTER_BITS,
+            align: POINTER_BITS as u32,
+        }
+    }
+
+    fn basic(&self, name: &str, size: u64, encoding: u32) -> Described<'ctx> {
+        Described {
+            ty: self
+                .builder
+                .create_basic_type(name, size, encoding, DIFlags::ZERO)
+                .expect("basic types have a size")
+                .as_type(),
+            size,
+            align: size as u32,
+        }
+    }
+
+    // scalar types only, for variables whose bloom type is not known
+    fn di_type(&self, ty: BasicTypeEnum<'ctx>) -> Option<DIType<'ctx>> {
+        let (name, size, encoding) = match ty {
+            BasicTypeEnum::IntType(ty) if ty.get_bit_width() == 1 => {
+                ("bool".to_string(), 8, DW_ATE_BOOLEAN)
+            }
+            BasicTypeEnum::IntType(ty) => (
+                format!("i{}", ty.get_bit_width()),
+                ty.get_bit_width() as u64,
+                DW_ATE_SIGNED,
+            ),
+            BasicTypeEnum::FloatType(ty) if ty == self.context.f32_type() => {
+                ("f32".to_string(), 32, DW_ATE_FLOAT)
+            }
+            BasicTypeEnum::FloatType(_) => ("f64".to_string(), 64, DW_ATE_FLOAT),
+            _ => return None,
+        };
+
+        self.builder
+            .create_basic_type(&name, size, encoding, DIFlags::ZERO)
+            .ok()
+            .map(|ty| ty.as_type())
+    }
+
+    // the file a span points into, the root file for missing spans
+    fn file(&self, span: Option<Span>) -> &SourceFile<'ctx> {
+        span.and_then(|span| {
+            self.files
+                .iter()
+                .rev()
+                .find(|file| span.start >= file.offset)
+        })
+        .unwrap_or(&self.files[0])
+    }
+
+    fn line_col(&self, span: Option<Span>) -> (u32, u32) {
+        span.map(|span| {
+            let file = self.file(Some(span));
+
+            Span::new(span.start - file.offset, span.end - file.offset).line_col(&file.source)
+        })
+        .map(|(line, column)| (line as u32, column as u32))
+        .unwrap_or((0, 0))
+    }
+}
+
+fn split_path(path: &str) -> (String, String) {
+    let path = Path::new(path);
+    let filename = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let directory = path
+        .parent()
+        .map(|parent| parent.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    (filename, directory)
+}
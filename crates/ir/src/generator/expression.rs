@@ -1,10 +1,23 @@
 use std::{cell::RefCell, rc::Rc};
 
+use common::{
+    constants::builtins::{
+        BUILTIN_ABORT, BUILTIN_ABS, BUILTIN_ALLOC, BUILTIN_ASSERT, BUILTIN_EXIT, BUILTIN_FREE,
+        BUILTIN_LEN, BUILTIN_MAX, BUILTIN_MIN, BUILTIN_POW, BUILTIN_PRINT, BUILTIN_PRINTLN,
+        BUILTIN_SQRT,
+    },
+    types::Type,
+};
 use inkwell::{
     builder::Builder,
     context::Context,
-    values::{BasicMetadataValueEnum, BasicValueEnum},
-    IntPredicate,
+    module::Module,
+    types::{ArrayType, BasicType, BasicTypeEnum},
+    values::{
+        BasicMetadataValueEnum, BasicValueEnum, FloatValue, FunctionValue, IntValue, PointerValue,
+        StructValue,
+    },
+    FloatPredicate, IntPredicate,
 };
 use preprocessor::last::{
     expression::Expression,
@@ -12,14 +25,16 @@ use preprocessor::last::{
 };
 
 use super::{
-    common::generate_for_literal,
+    common::{build_entry_alloca, generate_for_literal, is_string_value, type_for, variant_type},
     function::{StackFrame, SSA},
     module::FunctionStack,
+    runtime::LLVMRuntime,
 };
 
 pub struct LLVMExpressionGenerator<'ctx> {
     builder: Rc<Builder<'ctx>>,
     context: &'ctx Context,
+    module: Rc<Module<'ctx>>,
     stack_frame: Rc<RefCell<StackFrame<'ctx>>>,
     ssa: Rc<RefCell<SSA<'ctx>>>,
     function_stack: Rc<RefCell<FunctionStack<'ctx>>>,
@@ -28,6 +43,7 @@ pub struct LLVMExpressionGenerator<'ctx> {
 impl<'ctx> LLVMExpressionGenerator<'ctx> {
     pub fn new(
         context: &'ctx Context,
+        module: Rc<Module<'ctx>>,
         builder: Rc<Builder<'ctx>>,
         stack_frame: Rc<RefCell<StackFrame<'ctx>>>,
         ssa: Rc<RefCell<SSA<'ctx>>>,
@@ -36,6 +52,7 @@ impl<'ctx> LLVMExpressionGenerator<'ctx> {
         Self {
             builder,
             context,
+            module,
             stack_frame,
             ssa,
             function_stack,
@@ -49,7 +66,7 @@ impl<'ctx> LLVMExpressionGenerator<'ctx> {
     ) -> Option<BasicValueEnum<'ctx>> {
         match expression {
             Expression::Literal(literal) => {
-                return Some(generate_for_literal(self.context, literal));
+                Some(generate_for_literal(self.context, &self.module, literal))
             }
             Expression::Identifier(ident) => {
                 let value = if let Some(&basic) = self.ssa.borrow().get(ident) {
@@ -62,317 +79,1226 @@ impl<'ctx> LLVMExpressionGenerator<'ctx> {
                         .unwrap()
                 };
 
-                return Some(value);
+                Some(value)
             }
             Expression::FunctionInvokation { args, name } => {
                 let params = args
-                    .into_iter()
-                    .map(|param| {
-                        self.generate_from_ast(
-                            param,
-                            match param {
-                                Expression::Identifier(ident) => Some(ident),
-                                _ => unreachable!(),
-                            },
-                        )
-                        .unwrap()
-                    })
-                    .map(|param| param.into())
-                    .collect::<Vec<BasicMetadataValueEnum<'ctx>>>();
+                    .iter()
+                    .map(|param| self.generate_operand(param))
+                    .collect::<Vec<BasicValueEnum<'ctx>>>();
+
+                let function = self.function_stack.borrow().get(name).copied();
+
+                let Some(function) = function else {
+                    return self.generate_builtin(name, &params, store_in.unwrap());
+                };
 
                 let instruct = self
                     .builder
                     .build_call(
-                        *self.function_stack.borrow().get(name).unwrap(),
-                        &params,
+                        function,
+                        &self.generate_arguments(function, params),
                         store_in.unwrap(),
                     )
                     .unwrap();
 
-                return instruct.try_as_basic_value().left();
+                instruct.try_as_basic_value().left()
+            }
+            Expression::Index {
+                target,
+                index,
+                element,
+            } => {
+                // arrays are indexed where they are stored, like a place
+                if self.is_array(target) {
+                    let (element, ty) = self.generate_place(expression, true);
+
+                    return Some(
+                        self.builder
+                            .build_load(ty, element, store_in.unwrap())
+                            .unwrap(),
+                    );
+                }
+
+                let target = self.generate_operand(target);
+                let index = self.generate_operand(index).into_int_value();
+
+                if !is_string_value(&target) {
+                    let ty = type_for(
+                        self.context,
+                        element
+                            .as_ref()
+                            .expect("the element type of a slice is known"),
+                    );
+                    let element =
+                        self.generate_slice_element(target.into_struct_value(), ty, index);
+
+                    return Some(
+                        self.builder
+                            .build_load(ty, element, store_in.unwrap())
+                            .unwrap(),
+                    );
+                }
+
+                let index = self
+                    .builder
+                    .build_int_cast_sign_flag(index, self.context.i64_type(), true, "")
+                    .unwrap();
+
+                let runtime = LLVMRuntime::new(self.context, Rc::clone(&self.module));
+
+                self.builder
+                    .build_call(
+                        runtime.str_index(),
+                        &[target.into(), index.into()],
+                        store_in.unwrap(),
+                    )
+                    .unwrap()
+                    .try_as_basic_value()
+                    .left()
+            }
+            Expression::Cast {
+                expression,
+                from,
+                ty,
+            } => {
+                let value = self.generate_operand(expression);
+
+                Some(self.generate_cast(value, from.as_ref(), ty, store_in.unwrap()))
+            }
+            Expression::Struct { name, fields } => {
+                let struct_type = type_for(self.context, &Type::Named(name.clone()));
+                let mut value = struct_type.into_struct_type().get_undef();
+
+                for (index, field) in fields.iter().enumerate() {
+                    let field = self.generate_operand(field);
+                    let name = if index + 1 == fields.len() {
+                        store_in.unwrap()
+                    } else {
+                        ""
+                    };
+
+                    value = self
+                        .builder
+                        .build_insert_value(value, field, index as u32, name)
+                        .unwrap()
+                        .into_struct_value();
+                }
+
+                Some(value.into())
+            }
+            Expression::FieldAccess { target, index, .. } => {
+                let target = self.generate_operand(target).into_struct_value();
+
+                Some(
+                    self.builder
+                        .build_extract_value(target, *index, store_in.unwrap())
+                        .unwrap(),
+                )
+            }
+            Expression::Variant {
+                name,
+                variant,
+                tag,
+                fields,
+            } => {
+                let variant_type = variant_type(self.context, name, variant);
+                let mut value = self
+                    .builder
+                    .build_insert_value(
+                        variant_type.get_undef(),
+                        self.context.i32_type().const_int(*tag as u64, false),
+                        0,
+                        "",
+                    )
+                    .unwrap()
+                    .into_struct_value();
+
+                for (index, field) in fields.iter().enumerate() {
+                    let field = self.generate_operand(field);
+
+                    value = self
+                        .builder
+                        .build_insert_value(value, field, index as u32 + 1, "")
+                        .unwrap()
+                        .into_struct_value();
+                }
+
+                let enum_type = type_for(self.context, &Type::Named(name.clone()));
+                let storage = self.build_alloca(enum_type);
+                self.builder.build_store(storage, value).unwrap();
+
+                Some(
+                    self.builder
+                        .build_load(enum_type, storage, store_in.unwrap())
+                        .unwrap(),
+                )
+            }
+            Expression::Tag(target) => {
+                let target = self.generate_operand(target).into_struct_value();
+
+                Some(
+                    self.builder
+                        .build_extract_value(target, 0, store_in.unwrap())
+                        .unwrap(),
+                )
+            }
+            Expression::Payload {
+                target,
+                name,
+                variant,
+                index,
+            } => {
+                let target = self.generate_operand(target);
+
+                let storage = self.build_alloca(target.get_type());
+                self.builder.build_store(storage, target).unwrap();
+
+                let variant_type = variant_type(self.context, name, variant);
+                let value = self
+                    .builder
+                    .build_load(variant_type, storage, "")
+                    .unwrap()
+                    .into_struct_value();
+
+                Some(
+                    self.builder
+                        .build_extract_value(value, index + 1, store_in.unwrap())
+                        .unwrap(),
+                )
+            }
+            Expression::Tuple(elements) => {
+                let elements = elements
+                    .iter()
+                    .map(|element| self.generate_operand(element))
+                    .collect::<Vec<BasicValueEnum<'ctx>>>();
+
+                let ty = self.context.struct_type(
+                    &elements
+                        .iter()
+                        .map(|element| element.get_type())
+                        .collect::<Vec<BasicTypeEnum<'ctx>>>(),
+                    false,
+                );
+                let mut value = ty.get_undef();
+
+                for (index, element) in elements.iter().enumerate() {
+                    let name = if index + 1 == elements.len() {
+                        store_in.unwrap()
+                    } else {
+                        ""
+                    };
+
+                    value = self
+                        .builder
+                        .build_insert_value(value, *element, index as u32, name)
+                        .unwrap()
+                        .into_struct_value();
+                }
+
+                Some(value.into())
+            }
+            Expression::Array(elements) => {
+                let elements = elements
+                    .iter()
+                    .map(|element| self.generate_operand(element))
+                    .collect::<Vec<BasicValueEnum<'ctx>>>();
+
+                let ty = elements[0].get_type().array_type(elements.len() as u32);
+                let mut value = ty.get_undef();
+
+                for (index, element) in elements.iter().enumerate() {
+                    let name = if index + 1 == elements.len() {
+                        store_in.unwrap()
+                    } else {
+                        ""
+                    };
+
+                    value = self
+                        .builder
+                        .build_insert_value(value, *element, index as u32, name)
+                        .unwrap()
+                        .into_array_value();
+                }
+
+                Some(value.into())
+            }
+            Expression::ArrayRepeat { value, count } => {
+                let value = self.generate_operand(value);
+                Some(self.generate_repeat(value, *count, store_in.unwrap()))
+            }
+            Expression::Slice {
+                target,
+                start,
+                end,
+                element,
+            } => {
+                let i64_type = self.context.i64_type();
+
+                let (pointer, len, ty) = if self.is_array(target) {
+                    let (storage, ty) = self.generate_place(target, true);
+                    let ty = ty.into_array_type();
+
+                    (
+                        storage,
+                        i64_type.const_int(ty.len() as u64, false),
+                        ty.get_element_type(),
+                    )
+                } else {
+                    let slice = self.generate_operand(target).into_struct_value();
+                    let ty = type_for(
+                        self.context,
+                        element
+                            .as_ref()
+                            .expect("the element type of a slice is known"),
+                    );
+
+                    (
+                        self.builder
+                            .build_extract_value(slice, 0, "")
+                            .unwrap()
+                            .into_pointer_value(),
+                        self.builder
+                            .build_extract_value(slice, 1, "")
+                            .unwrap()
+                            .into_int_value(),
+                        ty,
+                    )
+                };
+
+                let bound = |bound: &'ctx Option<Rc<Expression>>, default| match bound {
+                    Some(bound) => self.widen_index(self.generate_operand(bound).into_int_value()),
+                    None => default,
+                };
+                let start = bound(start, i64_type.const_zero());
+                let end = bound(end, len);
+
+                self.generate_range_check(start, end, len);
+
+                let pointer = unsafe {
+                    self.builder
+                        .build_in_bounds_gep(ty, pointer, &[start], "")
+                        .unwrap()
+                };
+                let len = self.builder.build_int_sub(end, start, "").unwrap();
+
+                let slice_type = type_for(self.context, &Type::Slice(Box::new(Type::Void)));
+                let slice = self
+                    .builder
+                    .build_insert_value(slice_type.into_struct_type().get_undef(), pointer, 0, "")
+                    .unwrap();
+                let slice = self
+                    .builder
+                    .build_insert_value(slice, len, 1, store_in.unwrap())
+                    .unwrap()
+                    .into_struct_value();
+
+                Some(slice.into())
+            }
+            // constants have no storage, so they are copied into some first
+            Expression::AddressOf(place) => Some(self.generate_place(place, true).0.into()),
+            Expression::Deref { target, ty } => {
+                let target = self.generate_operand(target).into_pointer_value();
+
+                Some(
+                    self.builder
+                        .build_load(type_for(self.context, ty), target, store_in.unwrap())
+                        .unwrap(),
+                )
             }
             Expression::BinaryExpression {
                 left,
                 right,
                 operation,
+                operands,
             } => {
-                println!("binary expression: {operation:?}");
+                if let Operation::Assignment = operation {
+                    let rhs = self.generate_operand(right);
+                    let (lhs, _) = self.generate_place(left, false);
 
-                let op_res = match operation {
-                    Operation::Algebraic(alg) => {
-                        let lhs = self
-                            .generate_from_ast(
-                                left,
-                                match left.as_ref() {
-                                    Expression::Identifier(ident) => Some(ident),
-                                    _ => unreachable!(),
-                                },
-                            )
-                            .unwrap()
-                            .into_int_value();
-
-                        let rhs = self
-                            .generate_from_ast(
-                                right,
-                                match right.as_ref() {
-                                    Expression::Identifier(ident) => Some(ident),
-                                    _ => unreachable!(),
-                                },
-                            )
-                            .unwrap()
-                            .into_int_value();
-                        Some(match alg {
-                            AlgebraicOperation::Addition => {
-                                self.builder.build_int_add(lhs, rhs, store_in.unwrap())
-                            }
-                            AlgebraicOperation::Division => {
-                                self.builder
-                                    .build_int_signed_div(lhs, rhs, store_in.unwrap())
-                            }
-                            AlgebraicOperation::Multiplication => {
-                                self.builder.build_int_mul(lhs, rhs, store_in.unwrap())
-                            }
-                            AlgebraicOperation::Subtraction => {
-                                self.builder.build_int_sub(lhs, rhs, store_in.unwrap())
-                            }
-                        })
-                    }
-                    Operation::Assignment => {
-                        let identifier = match left.as_ref() {
-                            Expression::Identifier(ident) => ident,
-                            _ => unreachable!(),
-                        };
-
-                        let rhs = self
-                            .generate_from_ast(
-                                right,
-                                match right.as_ref() {
-                                    Expression::Identifier(ident) => Some(ident),
-                                    _ => unreachable!(),
-                                },
-                            )
-                            .unwrap()
-                            .into_int_value();
+                    self.builder.build_store(lhs, rhs).unwrap();
 
-                        let lhs = self
-                            .stack_frame
-                            .borrow()
-                            .get(identifier.as_str())
-                            .unwrap()
-                            .ptr();
+                    return None;
+                }
 
-                        self.builder.build_store(lhs, rhs).unwrap();
+                let lhs = self.generate_operand(left);
+                let rhs = self.generate_operand(right);
 
-                        println!("build store for: {identifier:?}");
+                if is_string_value(&lhs) && is_string_value(&rhs) {
+                    return self.generate_string_operation(lhs, rhs, operation, store_in.unwrap());
+                }
 
-                        None
-                    }
-                    Operation::Logical(log) => match log {
-                        LogicalOperation::Or => {
-                            let lhs = self
-                                .generate_from_ast(
-                                    left,
-                                    match left.as_ref() {
-                                        Expression::Identifier(ident) => Some(ident),
-                                        _ => unreachable!(),
-                                    },
-                                )
-                                .unwrap()
-                                .into_int_value();
-
-                            let rhs = self
-                                .generate_from_ast(
-                                    right,
-                                    match right.as_ref() {
-                                        Expression::Identifier(ident) => Some(ident),
-                                        _ => unreachable!(),
-                                    },
-                                )
-                                .unwrap()
-                                .into_int_value();
-
-                            Some(self.builder.build_or(lhs, rhs, store_in.unwrap()))
-                        }
-                        LogicalOperation::And => {
-                            let lhs = self
-                                .generate_from_ast(
-                                    left,
-                                    match left.as_ref() {
-                                        Expression::Identifier(ident) => Some(ident),
-                                        _ => unreachable!(),
-                                    },
-                                )
-                                .unwrap()
-                                .into_int_value();
-
-                            let rhs = self
-                                .generate_from_ast(
-                                    right,
-                                    match right.as_ref() {
-                                        Expression::Identifier(ident) => Some(ident),
-                                        _ => unreachable!(),
-                                    },
-                                )
-                                .unwrap()
-                                .into_int_value();
-
-                            Some(self.builder.build_and(lhs, rhs, store_in.unwrap()))
-                        }
-                        LogicalOperation::Equal => {
-                            let lhs = self
-                                .generate_from_ast(
-                                    left,
-                                    match left.as_ref() {
-                                        Expression::Identifier(ident) => Some(ident),
-                                        _ => unreachable!(),
-                                    },
-                                )
-                                .unwrap()
-                                .into_int_value();
-
-                            let rhs = self
-                                .generate_from_ast(
-                                    right,
-                                    match right.as_ref() {
-                                        Expression::Identifier(ident) => Some(ident),
-                                        _ => unreachable!(),
-                                    },
-                                )
-                                .unwrap()
-                                .into_int_value();
-
-                            Some(self.builder.build_int_compare(
-                                IntPredicate::EQ,
-                                lhs,
-                                rhs,
-                                store_in.unwrap(),
-                            ))
+                if lhs.is_float_value() && rhs.is_float_value() {
+                    return Some(self.generate_float_operation(
+                        lhs.into_float_value(),
+                        rhs.into_float_value(),
+                        operation,
+                        store_in.unwrap(),
+                    ));
+                }
+
+                // without a type from the checker integers are taken as signed
+                let unsigned = operands
+                    .as_ref()
+                    .is_some_and(|ty| ty.is_integer() && !ty.is_signed());
+                let predicate = |signed, unsigned_predicate| match unsigned {
+                    true => unsigned_predicate,
+                    false => signed,
+                };
+
+                let (lhs, rhs) =
+                    self.unify_int_operands(lhs.into_int_value(), rhs.into_int_value(), unsigned);
+                let name = store_in.unwrap();
+
+                let op_res = match operation {
+                    Operation::Algebraic(alg) => match alg {
+                        AlgebraicOperation::Addition => self.builder.build_int_add(lhs, rhs, name),
+                        AlgebraicOperation::Division if unsigned => {
+                            self.builder.build_int_unsigned_div(lhs, rhs, name)
                         }
-                        LogicalOperation::Less => {
-                            let lhs = self
-                                .generate_from_ast(
-                                    left,
-                                    match left.as_ref() {
-                                        Expression::Identifier(ident) => Some(ident),
-                                        _ => unreachable!(),
-                                    },
-                                )
-                                .unwrap()
-                                .into_int_value();
-
-                            let rhs = self
-                                .generate_from_ast(
-                                    right,
-                                    match right.as_ref() {
-                                        Expression::Identifier(ident) => Some(ident),
-                                        _ => unreachable!(),
-                                    },
-                                )
-                                .unwrap()
-                                .into_int_value();
-
-                            Some(self.builder.build_int_compare(
-                                IntPredicate::SLT,
-                                lhs,
-                                rhs,
-                                store_in.unwrap(),
-                            ))
+                        AlgebraicOperation::Division => {
+                            self.builder.build_int_signed_div(lhs, rhs, name)
                         }
-                        LogicalOperation::Greater => {
-                            let lhs = self
-                                .generate_from_ast(
-                                    left,
-                                    match left.as_ref() {
-                                        Expression::Identifier(ident) => Some(ident),
-                                        _ => unreachable!(),
-                                    },
-                                )
-                                .unwrap()
-                                .into_int_value();
-
-                            let rhs = self
-                                .generate_from_ast(
-                                    right,
-                                    match right.as_ref() {
-                                        Expression::Identifier(ident) => Some(ident),
-                                        _ => unreachable!(),
-                                    },
-                                )
-                                .unwrap()
-                                .into_int_value();
-
-                            Some(self.builder.build_int_compare(
-                                IntPredicate::SGT,
-                                lhs,
-                                rhs,
-                                store_in.unwrap(),
-                            ))
+                        AlgebraicOperation::Multiplication => {
+                            self.builder.build_int_mul(lhs, rhs, name)
                         }
-                        LogicalOperation::LessOrEqual => {
-                            let lhs = self
-                                .generate_from_ast(
-                                    left,
-                                    match left.as_ref() {
-                                        Expression::Identifier(ident) => Some(ident),
-                                        _ => unreachable!(),
-                                    },
-                                )
-                                .unwrap()
-                                .into_int_value();
-
-                            let rhs = self
-                                .generate_from_ast(
-                                    right,
-                                    match right.as_ref() {
-                                        Expression::Identifier(ident) => Some(ident),
-                                        _ => unreachable!(),
-                                    },
-                                )
-                                .unwrap()
-                                .into_int_value();
-
-                            Some(self.builder.build_int_compare(
-                                IntPredicate::SLE,
-                                lhs,
-                                rhs,
-                                store_in.unwrap(),
-                            ))
+                        AlgebraicOperation::Subtraction => {
+                            self.builder.build_int_sub(lhs, rhs, name)
                         }
-                        LogicalOperation::GreaterOrEqual => {
-                            let lhs = self
-                                .generate_from_ast(
-                                    left,
-                                    match left.as_ref() {
-                                        Expression::Identifier(ident) => Some(ident),
-                                        _ => unreachable!(),
-                                    },
-                                )
-                                .unwrap()
-                                .into_int_value();
-
-                            let rhs = self
-                                .generate_from_ast(
-                                    right,
-                                    match right.as_ref() {
-                                        Expression::Identifier(ident) => Some(ident),
-                                        _ => unreachable!(),
-                                    },
-                                )
-                                .unwrap()
-                                .into_int_value();
-
-                            Some(self.builder.build_int_compare(
-                                IntPredicate::SGE,
-                                lhs,
-                                rhs,
-                                store_in.unwrap(),
-                            ))
+                    },
+                    Operation::Logical(log) => match log {
+                        LogicalOperation::Or => self.builder.build_or(lhs, rhs, name),
+                        LogicalOperation::And => self.builder.build_and(lhs, rhs, name),
+                        LogicalOperation::Equal => {
+                            self.builder
+                                .build_int_compare(IntPredicate::EQ, lhs, rhs, name)
                         }
+                        LogicalOperation::Less => self.builder.build_int_compare(
+                            predicate(IntPredicate::SLT, IntPredicate::ULT),
+                            lhs,
+                            rhs,
+                            name,
+                        ),
+                        LogicalOperation::Greater => self.builder.build_int_compare(
+                            predicate(IntPredicate::SGT, IntPredicate::UGT),
+                            lhs,
+                            rhs,
+                            name,
+                        ),
+                        LogicalOperation::LessOrEqual => self.builder.build_int_compare(
+                            predicate(IntPredicate::SLE, IntPredicate::ULE),
+                            lhs,
+                            rhs,
+                            name,
+                        ),
+                        LogicalOperation::GreaterOrEqual => self.builder.build_int_compare(
+                            predicate(IntPredicate::SGE, IntPredicate::UGE),
+                            lhs,
+                            rhs,
+                            name,
+                        ),
                     },
+                    Operation::Assignment => unreachable!(),
                 };
 
-                return op_res.map(|res| res.unwrap()).map(|intv| intv.into());
+                Some(op_res.unwrap().into())
             }
+        }
+    }
+
+    fn generate_operand(&self, operand: &'ctx Expression) -> BasicValueEnum<'ctx> {
+        self.generate_from_ast(
+            operand,
+            match operand {
+                Expression::Identifier(ident) => Some(ident),
+                _ => unreachable!(),
+            },
+        )
+        .unwrap()
+    }
+
+    // strings go to C functions as pointers to their bytes, which end with a NUL,
+    // variadic arguments are promoted like in C, floats to doubles and small integers to ints
+    fn generate_arguments(
+        &self,
+        function: FunctionValue<'ctx>,
+        params: Vec<BasicValueEnum<'ctx>>,
+    ) -> Vec<BasicMetadataValueEnum<'ctx>> {
+        params
+            .into_iter()
+            .enumerate()
+            .map(|(index, param)| {
+                // nothing is expected of variadic arguments
+                let expected = function.get_nth_param(index as u32);
+
+                match param {
+                    param
+                        if is_string_value(&param)
+                            && expected.is_none_or(|expected| expected.is_pointer_value()) =>
+                    {
+                        self.builder
+                            .build_extract_value(param.into_struct_value(), 0, "")
+                            .unwrap()
+                    }
+                    BasicValueEnum::FloatValue(value)
+                        if expected.is_none() && value.get_type() == self.context.f32_type() =>
+                    {
+                        self.builder
+                            .build_float_ext(value, self.context.f64_type(), "")
+                            .unwrap()
+                            .into()
+                    }
+                    BasicValueEnum::IntValue(value)
+                        if expected.is_none() && value.get_type().get_bit_width() < 32 =>
+                    {
+                        let i32_type = self.context.i32_type();
+                        let is_signed = value.get_type().get_bit_width() != 1;
+
+                        self.builder
+                            .build_int_cast_sign_flag(value, i32_type, is_signed, "")
+                            .unwrap()
+                            .into()
+                    }
+                    param => param,
+                }
+                .into()
+            })
+            .collect()
+    }
+
+    fn build_alloca(&self, ty: BasicTypeEnum<'ctx>) -> PointerValue<'ctx> {
+        let function = self
+            .builder
+            .get_insert_block()
+            .and_then(|block| block.get_parent())
+            .unwrap();
+
+        build_entry_alloca(self.context, function, ty, "")
+    }
+
+    // a loop storing the value into every element, instead of
+    // one instruction per element of a long array
+    fn generate_repeat(
+        &self,
+        value: BasicValueEnum<'ctx>,
+        count: u64,
+        name: &str,
+    ) -> BasicValueEnum<'ctx> {
+        let ty = value.get_type().array_type(count as u32);
+        let storage = self.build_alloca(ty.into());
+
+        let i64_type = self.context.i64_type();
+        let entry_block = self.builder.get_insert_block().unwrap();
+        let function = entry_block.get_parent().unwrap();
+
+        let header_block = self.context.append_basic_block(function, "repeat.header");
+        let body_block = self.context.append_basic_block(function, "repeat.body");
+        let exit_block = self.context.append_basic_block(function, "repeat.exit");
+
+        self.builder
+            .build_unconditional_branch(header_block)
+            .unwrap();
+        self.builder.position_at_end(header_block);
+
+        let index = self.builder.build_phi(i64_type, "").unwrap();
+        let done = self
+            .builder
+            .build_int_compare(
+                IntPredicate::UGE,
+                index.as_basic_value().into_int_value(),
+                i64_type.const_int(count, false),
+                "",
+            )
+            .unwrap();
+        self.builder
+            .build_conditional_branch(done, exit_block, body_block)
+            .unwrap();
+
+        self.builder.position_at_end(body_block);
+
+        let element = unsafe {
+            self.builder
+                .build_in_bounds_gep(
+                    ty,
+                    storage,
+                    &[
+                        i64_type.const_zero(),
+                        index.as_basic_value().into_int_value(),
+                    ],
+                    "",
+                )
+                .unwrap()
         };
+        self.builder.build_store(element, value).unwrap();
+
+        let next = self
+            .builder
+            .build_int_add(
+                index.as_basic_value().into_int_value(),
+                i64_type.const_int(1, false),
+                "",
+            )
+            .unwrap();
+        self.builder
+            .build_unconditional_branch(header_block)
+            .unwrap();
+
+        index.add_incoming(&[(&i64_type.const_zero(), entry_block), (&next, body_block)]);
+
+        self.builder.position_at_end(exit_block);
+        self.builder.build_load(ty, storage, name).unwrap()
+    }
+
+    // arrays are indexed and sliced where they are stored, the preprocessor only
+    // leaves places of arrays there, strings and slices are in variables of their own
+    fn is_array(&self, target: &Expression) -> bool {
+        let Expression::Identifier(identifier) = target else {
+            return true;
+        };
+
+        let ty = match self.ssa.borrow().get(identifier) {
+            Some(value) => value.get_type(),
+            None => self
+                .stack_frame
+                .borrow()
+                .get(identifier.as_str())
+                .unwrap()
+                .ty(),
+        };
+
+        ty.is_array_type()
+    }
+
+    // a pointer to an element of the array in storage, behind a bounds check
+    fn generate_element(
+        &self,
+        storage: PointerValue<'ctx>,
+        ty: ArrayType<'ctx>,
+        index: IntValue<'ctx>,
+    ) -> PointerValue<'ctx> {
+        let len = self.context.i64_type().const_int(ty.len() as u64, false);
+        let index = self.generate_bounds_check(index, len);
+
+        unsafe {
+            self.builder
+                .build_in_bounds_gep(
+                    ty,
+                    storage,
+                    &[self.context.i64_type().const_zero(), index],
+                    "",
+                )
+                .unwrap()
+        }
+    }
+
+    // a pointer to an element the slice points to, behind a bounds check
+    fn generate_slice_element(
+        &self,
+        slice: StructValue<'ctx>,
+        ty: BasicTypeEnum<'ctx>,
+        index: IntValue<'ctx>,
+    ) -> PointerValue<'ctx> {
+        let pointer = self
+            .builder
+            .build_extract_value(slice, 0, "")
+            .unwrap()
+            .into_pointer_value();
+        let len = self
+            .builder
+            .build_extract_value(slice, 1, "")
+            .unwrap()
+            .into_int_value();
+
+        let index = self.generate_bounds_check(index, len);
+
+        unsafe {
+            self.builder
+                .build_in_bounds_gep(ty, pointer, &[index], "")
+                .unwrap()
+        }
+    }
+
+    // indices are zero-extended and compared unsigned, so negative ones are out of
+    // bounds too, the check folds away under -O once the index is known to be in range
+    fn generate_bounds_check(&self, index: IntValue<'ctx>, len: IntValue<'ctx>) -> IntValue<'ctx> {
+        let index = self.widen_index(index);
+
+        let in_bounds = self
+            .builder
+            .build_int_compare(IntPredicate::ULT, index, len, "in_bounds")
+            .unwrap();
+
+        let runtime = LLVMRuntime::new(self.context, Rc::clone(&self.module));
+        self.generate_check(
+            in_bounds,
+            runtime.index_out_of_bounds(),
+            &[index.into(), len.into()],
+        );
+
+        index
+    }
+
+    // like a bounds check, the range has to end before the length and not before it starts
+    fn generate_range_check(
+        &self,
+        start: IntValue<'ctx>,
+        end: IntValue<'ctx>,
+        len: IntValue<'ctx>,
+    ) {
+        let fits = self
+            .builder
+            .build_int_compare(IntPredicate::ULE, end, len, "")
+            .unwrap();
+        let ordered = self
+            .builder
+            .build_int_compare(IntPredicate::ULE, start, end, "")
+            .unwrap();
+        let in_bounds = self.builder.build_and(fits, ordered, "in_bounds").unwrap();
+
+        let runtime = LLVMRuntime::new(self.context, Rc::clone(&self.module));
+        self.generate_check(
+            in_bounds,
+            runtime.slice_out_of_bounds(),
+            &[start.into(), end.into(), len.into()],
+        );
+    }
+
+    // continues where the condition holds, otherwise calls the failing function,
+    // which does not return
+    fn generate_check(
+        &self,
+        condition: IntValue<'ctx>,
+        fail: FunctionValue<'ctx>,
+        arguments: &[BasicMetadataValueEnum<'ctx>],
+    ) {
+        let function = self
+            .builder
+            .get_insert_block()
+            .and_then(|block| block.get_parent())
+            .unwrap();
+        let in_bounds_block = self.context.append_basic_block(function, "in_bounds");
+        let out_of_bounds_block = self.context.append_basic_block(function, "out_of_bounds");
+
+        self.builder
+            .build_conditional_branch(condition, in_bounds_block, out_of_bounds_block)
+            .unwrap();
+
+        self.builder.position_at_end(out_of_bounds_block);
+        self.builder.build_call(fail, arguments, "").unwrap();
+        self.builder.build_unreachable().unwrap();
+
+        self.builder.position_at_end(in_bounds_block);
+    }
+
+    // indices and bounds are compared as i64
+    fn widen_index(&self, index: IntValue<'ctx>) -> IntValue<'ctx> {
+        let i64_type = self.context.i64_type();
+
+        if index.get_type().get_bit_width() < 64 {
+            self.builder
+                .build_int_z_extend(index, i64_type, "")
+                .unwrap()
+        } else {
+            index
+        }
+    }
+
+    // the storage of a variable, of a field or of an element of it, or the pointee
+    // of a pointer, with `spill` constants are copied into storage of their own
+    fn generate_place(
+        &self,
+        place: &'ctx Expression,
+        spill: bool,
+    ) -> (PointerValue<'ctx>, BasicTypeEnum<'ctx>) {
+        match place {
+            Expression::Identifier(identifier) => {
+                if let Some(variable) = self.stack_frame.borrow().get(identifier.as_str()) {
+                    return (variable.ptr(), variable.ty());
+                }
+
+                let value = match self.ssa.borrow().get(identifier) {
+                    Some(&value) if spill => value,
+                    _ => panic!("cannot assign to constant `{identifier}`"),
+                };
+
+                let storage = self.build_alloca(value.get_type());
+                self.builder.build_store(storage, value).unwrap();
+
+                (storage, value.get_type())
+            }
+            Expression::Deref { target, ty } => (
+                self.generate_operand(target).into_pointer_value(),
+                type_for(self.context, ty),
+            ),
+            Expression::FieldAccess { target, index, .. } => {
+                let (target, ty) = self.generate_place(target, spill);
+                let ty = ty.into_struct_type();
+
+                let field = self
+                    .builder
+                    .build_struct_gep(ty, target, *index, "")
+                    .unwrap();
+
+                (field, ty.get_field_type_at_index(*index).unwrap())
+            }
+            Expression::Index {
+                target,
+                index,
+                element,
+            } => {
+                // the elements of a slice are behind its pointer
+                if !self.is_array(target) {
+                    let slice = self.generate_operand(target).into_struct_value();
+                    let index = self.generate_operand(index).into_int_value();
+                    let ty = type_for(
+                        self.context,
+                        element
+                            .as_ref()
+                            .expect("the element type of a slice is known"),
+                    );
+
+                    return (self.generate_slice_element(slice, ty, index), ty);
+                }
+
+                let (target, ty) = self.generate_place(target, spill);
+                let ty = ty.into_array_type();
+                let index = self.generate_operand(index).into_int_value();
+
+                (
+                    self.generate_element(target, ty, index),
+                    ty.get_element_type(),
+                )
+            }
+            other => panic!("cannot assign to {other:?}"),
+        }
+    }
+
+    // widen the narrower operand, so that i64 lengths
+    // can be compared against i32 literals
+    fn unify_int_operands(
+        &self,
+        lhs: IntValue<'ctx>,
+        rhs: IntValue<'ctx>,
+        unsigned: bool,
+    ) -> (IntValue<'ctx>, IntValue<'ctx>) {
+        let lhs_width = lhs.get_type().get_bit_width();
+        let rhs_width = rhs.get_type().get_bit_width();
+
+        let extend = |value: IntValue<'ctx>, ty| match unsigned {
+            true => self.builder.build_int_z_extend(value, ty, "").unwrap(),
+            false => self.builder.build_int_s_extend(value, ty, "").unwrap(),
+        };
+
+        if lhs_width < rhs_width {
+            (extend(lhs, rhs.get_type()), rhs)
+        } else if rhs_width < lhs_width {
+            (lhs, extend(rhs, lhs.get_type()))
+        } else {
+            (lhs, rhs)
+        }
+    }
+
+    // `from` picks the signedness of the operand, without it integers are taken as signed
+    fn generate_cast(
+        &self,
+        value: BasicValueEnum<'ctx>,
+        from: Option<&Type>,
+        ty: &Type,
+        name: &str,
+    ) -> BasicValueEnum<'ctx> {
+        let target = type_for(self.context, ty);
+
+        if value.get_type() == target {
+            return value;
+        }
+
+        // bools and chars never carry a sign
+        let is_signed = match from {
+            Some(from) => from.is_signed(),
+            None => match value {
+                BasicValueEnum::IntValue(value) => {
+                    !matches!(ty, Type::Char) && value.get_type().get_bit_width() != 1
+                }
+                _ => true,
+            },
+        };
+
+        match (value, target) {
+            // anything non-zero is true, truncating would only keep the lowest bit
+            (BasicValueEnum::IntValue(value), _) if *ty == Type::Bool => self
+                .builder
+                .build_int_compare(IntPredicate::NE, value, value.get_type().const_zero(), name)
+                .unwrap()
+                .into(),
+            (BasicValueEnum::IntValue(value), BasicTypeEnum::IntType(target)) => self
+                .builder
+                .build_int_cast_sign_flag(value, target, is_signed, name)
+                .unwrap()
+                .into(),
+            (BasicValueEnum::IntValue(value), BasicTypeEnum::FloatType(target)) if is_signed => {
+                self.builder
+                    .build_signed_int_to_float(value, target, name)
+                    .unwrap()
+                    .into()
+            }
+            (BasicValueEnum::IntValue(value), BasicTypeEnum::FloatType(target)) => self
+                .builder
+                .build_unsigned_int_to_float(value, target, name)
+                .unwrap()
+                .into(),
+            (BasicValueEnum::FloatValue(value), BasicTypeEnum::IntType(target))
+                if ty.is_signed() =>
+            {
+                self.builder
+                    .build_float_to_signed_int(value, target, name)
+                    .unwrap()
+                    .into()
+            }
+            (BasicValueEnum::FloatValue(value), BasicTypeEnum::IntType(target)) => self
+                .builder
+                .build_float_to_unsigned_int(value, target, name)
+                .unwrap()
+                .into(),
+            (BasicValueEnum::FloatValue(value), BasicTypeEnum::FloatType(target)) => self
+                .builder
+                .build_float_cast(value, target, name)
+                .unwrap()
+                .into(),
+            (value, _) => panic!("unsupported cast from {value:?} to {ty:?}"),
+        }
+    }
+
+    fn generate_string_operation(
+        &self,
+        lhs: BasicValueEnum<'ctx>,
+        rhs: BasicValueEnum<'ctx>,
+        operation: &Operation,
+        name: &str,
+    ) -> Option<BasicValueEnum<'ctx>> {
+        let runtime = LLVMRuntime::new(self.context, Rc::clone(&self.module));
+
+        let function = match operation {
+            Operation::Algebraic(AlgebraicOperation::Addition) => runtime.str_concat(),
+            Operation::Logical(LogicalOperation::Equal) => runtime.str_eq(),
+            other => panic!("unsupported operation on strings: {other:?}"),
+        };
+
+        self.builder
+            .build_call(function, &[lhs.into(), rhs.into()], name)
+            .unwrap()
+            .try_as_basic_value()
+            .left()
+    }
+
+    // comparisons are ordered, they are false when either operand is NaN
+    fn generate_float_operation(
+        &self,
+        lhs: FloatValue<'ctx>,
+        rhs: FloatValue<'ctx>,
+        operation: &Operation,
+        name: &str,
+    ) -> BasicValueEnum<'ctx> {
+        let compare = |predicate| {
+            self.builder
+                .build_float_compare(predicate, lhs, rhs, name)
+                .unwrap()
+                .into()
+        };
+
+        match operation {
+            Operation::Algebraic(AlgebraicOperation::Addition) => {
+                self.builder.build_float_add(lhs, rhs, name).unwrap().into()
+            }
+            Operation::Algebraic(AlgebraicOperation::Subtraction) => {
+                self.builder.build_float_sub(lhs, rhs, name).unwrap().into()
+            }
+            Operation::Algebraic(AlgebraicOperation::Multiplication) => {
+                self.builder.build_float_mul(lhs, rhs, name).unwrap().into()
+            }
+            Operation::Algebraic(AlgebraicOperation::Division) => {
+                self.builder.build_float_div(lhs, rhs, name).unwrap().into()
+            }
+            Operation::Logical(LogicalOperation::Equal) => compare(FloatPredicate::OEQ),
+            Operation::Logical(LogicalOperation::Less) => compare(FloatPredicate::OLT),
+            Operation::Logical(LogicalOperation::Greater) => compare(FloatPredicate::OGT),
+            Operation::Logical(LogicalOperation::LessOrEqual) => compare(FloatPredicate::OLE),
+            Operation::Logical(LogicalOperation::GreaterOrEqual) => compare(FloatPredicate::OGE),
+            other => panic!("unsupported operation on floats: {other:?}"),
+        }
+    }
+
+    fn generate_builtin(
+        &self,
+        name: &str,
+        args: &[BasicValueEnum<'ctx>],
+        store_in: &str,
+    ) -> Option<BasicValueEnum<'ctx>> {
+        let runtime = LLVMRuntime::new(self.context, Rc::clone(&self.module));
+
+        // overloaded builtins carry the type of their arguments, as in `print.u8`
+        let (name, ty) = match name.split_once('.') {
+            Some((name, ty)) => (name, Type::from(ty)),
+            None => (name, None),
+        };
+
+        match name {
+            // the length of an array is part of its type
+            BUILTIN_LEN if args.len() == 1 && args[0].is_array_value() => Some(
+                self.context
+                    .i64_type()
+                    .const_int(args[0].into_array_value().get_type().len() as u64, false)
+                    .into(),
+            ),
+            BUILTIN_LEN if args.len() == 1 && is_string_value(&args[0]) => self
+                .builder
+                .build_call(runtime.str_len(), &[args[0].into()], store_in)
+                .unwrap()
+                .try_as_basic_value()
+                .left(),
+            // slices keep their length next to the pointer
+            BUILTIN_LEN if args.len() == 1 && args[0].is_struct_value() => Some(
+                self.builder
+                    .build_extract_value(args[0].into_struct_value(), 1, store_in)
+                    .unwrap(),
+            ),
+            // the value is moved into memory of its own size
+            BUILTIN_ALLOC if args.len() == 1 => {
+                let size = args[0].get_type().size_of().unwrap();
+                let pointer = self
+                    .builder
+                    .build_call(runtime.malloc(), &[size.into()], store_in)
+                    .unwrap()
+                    .try_as_basic_value()
+                    .left()
+                    .unwrap()
+                    .into_pointer_value();
+
+                self.builder.build_store(pointer, args[0]).unwrap();
+
+                Some(pointer.into())
+            }
+            BUILTIN_FREE if args.len() == 1 => {
+                self.builder
+                    .build_call(runtime.free(), &[args[0].into()], "")
+                    .unwrap();
+
+                None
+            }
+            BUILTIN_PRINT if args.len() == 1 => {
+                self.generate_print(args.first(), ty.as_ref(), false);
+
+                None
+            }
+            BUILTIN_PRINTLN if args.len() <= 1 => {
+                self.generate_print(args.first(), ty.as_ref(), true);
+
+                None
+            }
+            BUILTIN_ASSERT if args.len() == 2 => {
+                let function = self
+                    .builder
+                    .get_insert_block()
+                    .unwrap()
+                    .get_parent()
+                    .unwrap();
+                let failed_block = self.context.append_basic_block(function, "assert.failed");
+                let ok_block = self.context.append_basic_block(function, "assert.ok");
+
+                self.builder
+                    .build_conditional_branch(args[0].into_int_value(), ok_block, failed_block)
+                    .unwrap();
+
+                self.builder.position_at_end(failed_block);
+                self.builder
+                    .build_call(runtime.assert_failed(), &[args[1].into()], "")
+                    .unwrap();
+                self.builder.build_unreachable().unwrap();
+
+                self.builder.position_at_end(ok_block);
+
+                None
+            }
+            BUILTIN_ABORT if args.is_empty() => {
+                self.builder.build_call(runtime.abort(), &[], "").unwrap();
+
+                None
+            }
+            BUILTIN_EXIT if args.len() == 1 => {
+                let code = self.generate_cast(args[0], None, &Type::Int32, "code");
+
+                self.builder
+                    .build_call(runtime.exit(), &[code.into()], "")
+                    .unwrap();
+
+                None
+            }
+            // the absolute value of an unsigned integer is itself
+            BUILTIN_ABS if args.len() == 1 && ty.as_ref().is_some_and(|ty| !ty.is_signed()) => {
+                Some(args[0])
+            }
+            BUILTIN_ABS if args.len() == 1 && args[0].is_float_value() => {
+                self.generate_intrinsic(&runtime, "llvm.fabs", args, store_in)
+            }
+            BUILTIN_ABS if args.len() == 1 => {
+                // the minimum integer is left as it is rather than being poison
+                let is_int_min_poison = self.context.bool_type().const_zero();
+
+                self.generate_intrinsic(
+                    &runtime,
+                    "llvm.abs",
+                    &[args[0], is_int_min_poison.into()],
+                    store_in,
+                )
+            }
+            BUILTIN_MIN | BUILTIN_MAX if args.len() == 2 => {
+                let intrinsic = match (name, ty.as_ref()) {
+                    (BUILTIN_MIN, Some(ty)) if ty.is_float() => "llvm.minnum",
+                    (BUILTIN_MAX, Some(ty)) if ty.is_float() => "llvm.maxnum",
+                    (BUILTIN_MIN, Some(ty)) if ty.is_signed() => "llvm.smin",
+                    (BUILTIN_MAX, Some(ty)) if ty.is_signed() => "llvm.smax",
+                    (BUILTIN_MIN, _) => "llvm.umin",
+                    _ => "llvm.umax",
+                };
+
+                self.generate_intrinsic(&runtime, intrinsic, args, store_in)
+            }
+            BUILTIN_SQRT if args.len() == 1 => {
+                self.generate_intrinsic(&runtime, "llvm.sqrt", args, store_in)
+            }
+            BUILTIN_POW if args.len() == 2 => {
+                self.generate_intrinsic(&runtime, "llvm.pow", args, store_in)
+            }
+            // the type checker rejects calls to unknown functions
+            other => unreachable!("unknown function: {other}"),
+        }
+    }
+
+    // intrinsics are overloaded on the type of their first argument
+    fn generate_intrinsic(
+        &self,
+        runtime: &LLVMRuntime<'ctx>,
+        name: &str,
+        args: &[BasicValueEnum<'ctx>],
+        store_in: &str,
+    ) -> Option<BasicValueEnum<'ctx>> {
+        let function = runtime.intrinsic(name, &[args[0].get_type()]);
+        let args = args
+            .iter()
+            .map(|arg| (*arg).into())
+            .collect::<Vec<BasicMetadataValueEnum<'ctx>>>();
+
+        self.builder
+            .build_call(function, &args, store_in)
+            .unwrap()
+            .try_as_basic_value()
+            .left()
+    }
+
+    // printed to stdout with `dprintf`, which leaves no buffer to flush at exit
+    fn generate_print(
+        &self,
+        value: Option<&BasicValueEnum<'ctx>>,
+        ty: Option<&Type>,
+        newline: bool,
+    ) {
+        let runtime = LLVMRuntime::new(self.context, Rc::clone(&self.module));
+        let i32_type = self.context.i32_type();
+        let i64_type = self.context.i64_type();
+
+        let (format, args): (&str, Vec<BasicMetadataValueEnum<'ctx>>) = match (value, ty) {
+            (None, _) => ("", vec![]),
+            (Some(value), Some(Type::String)) => {
+                let value = value.into_struct_value();
+                let ptr = self.builder.build_extract_value(value, 0, "ptr").unwrap();
+                let len = self
+                    .builder
+                    .build_extract_value(value, 1, "len")
+                    .unwrap()
+                    .into_int_value();
+                let len = self
+                    .builder
+                    .build_int_truncate(len, i32_type, "len")
+                    .unwrap();
+
+                ("%.*s", vec![len.into(), ptr.into()])
+            }
+            (Some(value), Some(Type::Bool)) => {
+                let true_string = self
+                    .builder
+                    .build_global_string_ptr("true", "true")
+                    .unwrap();
+                let false_string = self
+                    .builder
+                    .build_global_string_ptr("false", "false")
+                    .unwrap();
+                let string = self
+                    .builder
+                    .build_select(
+                        value.into_int_value(),
+                        true_string.as_pointer_value(),
+                        false_string.as_pointer_value(),
+                        "bool",
+                    )
+                    .unwrap();
+
+                ("%s", vec![string.into()])
+            }
+            (Some(value), Some(Type::Char)) => {
+                self.builder
+                    .build_call(runtime.print_char(), &[(*value).into()], "")
+                    .unwrap();
+
+                ("", vec![])
+            }
+            // varargs take doubles, and the widest integers cover all the others
+            (Some(value), Some(ty)) if ty.is_float() => {
+                let value = self
+                    .builder
+                    .build_float_cast(value.into_float_value(), self.context.f64_type(), "")
+                    .unwrap();
+
+                ("%g", vec![value.into()])
+            }
+            (Some(value), Some(ty)) if ty.is_signed() => {
+                let value = self
+                    .builder
+                    .build_int_s_extend_or_bit_cast(value.into_int_value(), i64_type, "")
+                    .unwrap();
+
+                ("%lld", vec![value.into()])
+            }
+            (Some(value), _) => {
+                let value = self
+                    .builder
+                    .build_int_z_extend_or_bit_cast(value.into_int_value(), i64_type, "")
+                    .unwrap();
+
+                ("%llu", vec![value.into()])
+            }
+        };
+
+        let format = if newline {
+            format!("{format}\n")
+        } else {
+            format.to_string()
+        };
+
+        if format.is_empty() {
+            return;
+        }
+
+        let format = self
+            .builder
+            .build_global_string_ptr(&format, "format")
+            .unwrap();
+        let stdout = i32_type.const_int(1, false);
+
+        let mut args = args;
+        args.insert(0, format.as_pointer_value().into());
+        args.insert(0, stdout.into());
+
+        self.builder
+            .build_call(runtime.dprintf(), &args, "")
+            .unwrap();
     }
 }
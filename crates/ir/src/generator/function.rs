@@ -1,21 +1,36 @@
+use common::{span::Span, types::Type};
 use inkwell::{
     builder::Builder,
     context::Context,
-    values::{BasicValueEnum, FunctionValue},
+    debug_info::DISubprogram,
+    module::Module,
+    types::BasicTypeEnum,
+    values::{BasicValueEnum, FunctionValue, PointerValue},
 };
 use preprocessor::last::{declaration::Declaration, statement::Statement, unit::LASTUnit};
-use std::{cell::RefCell, collections::HashMap, ptr, rc::Rc};
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    ptr,
+    rc::Rc,
+};
 
 use super::{
-    common::VariableData, expression::LLVMExpressionGenerator, module::FunctionStack,
-    statement::LLVMStatementGenerator, variable::LLVMVariableGenerator,
+    common::{build_entry_alloca, VariableData},
+    debug::DebugInfo,
+    expression::LLVMExpressionGenerator,
+    module::FunctionStack,
+    statement::LLVMStatementGenerator,
+    variable::LLVMVariableGenerator,
 };
 
 pub type StackFrame<'ctx> = HashMap<String, VariableData<'ctx>>;
 pub type SSA<'ctx> = HashMap<String, BasicValueEnum<'ctx>>;
+pub type DebugScope<'ctx> = (Rc<DebugInfo<'ctx>>, DISubprogram<'ctx>);
 
 pub struct LLVMFunctionGenerator<'ctx> {
     context: &'ctx Context,
+    module: Rc<Module<'ctx>>,
     builder: Rc<Builder<'ctx>>,
     stack_frame: Rc<RefCell<StackFrame<'ctx>>>,
     ssa: Rc<RefCell<SSA<'ctx>>>,
@@ -23,20 +38,30 @@ pub struct LLVMFunctionGenerator<'ctx> {
     function: FunctionValue<'ctx>,
     is_void: bool,
     expr_gen: LLVMExpressionGenerator<'ctx>,
+    debug: Option<DebugScope<'ctx>>,
+    // the span of the statement being generated
+    location: Cell<Option<Span>>,
 }
 
 impl<'ctx> LLVMFunctionGenerator<'ctx> {
     pub fn new(
         context: &'ctx Context,
+        module: Rc<Module<'ctx>>,
         function: FunctionValue<'ctx>,
         param_names: &[&str],
         function_stack: Rc<RefCell<FunctionStack<'ctx>>>,
         builder: Rc<Builder<'ctx>>,
+        debug: Option<DebugScope<'ctx>>,
     ) -> Self {
         let entry = context.append_basic_block(function, "entry");
 
         builder.position_at_end(entry);
 
+        // every instruction needs a location once the function has debug info
+        if let Some((debug_info, subprogram)) = &debug {
+            builder.set_current_debug_location(debug_info.location(*subprogram, None));
+        }
+
         let is_void = function.get_type().get_return_type().is_none();
 
         let mut ssa = SSA::new();
@@ -50,6 +75,7 @@ impl<'ctx> LLVMFunctionGenerator<'ctx> {
 
         let expr_gen = LLVMExpressionGenerator::new(
             context,
+            Rc::clone(&module),
             Rc::clone(&builder),
             Rc::clone(&stack_frame),
             Rc::clone(&ssa),
@@ -58,6 +84,7 @@ impl<'ctx> LLVMFunctionGenerator<'ctx> {
 
         Self {
             context,
+            module,
             builder,
             stack_frame,
             ssa,
@@ -65,13 +92,77 @@ impl<'ctx> LLVMFunctionGenerator<'ctx> {
             function,
             is_void,
             expr_gen,
+            debug,
+            location: Cell::new(None),
+        }
+    }
+
+    pub fn build_entry_alloca(&self, ty: BasicTypeEnum<'ctx>, name: &str) -> PointerValue<'ctx> {
+        build_entry_alloca(self.context, self.function, ty, name)
+    }
+
+    pub fn is_void(&self) -> bool {
+        self.is_void
+    }
+
+    // values of the constants and temporaries, branches start from the ones before them
+    pub fn ssa(&self) -> SSA<'ctx> {
+        self.ssa.borrow().clone()
+    }
+
+    pub fn set_ssa(&self, ssa: SSA<'ctx>) {
+        *self.ssa.borrow_mut() = ssa;
+    }
+
+    pub fn set_location(&self, span: Span) {
+        self.location.set(Some(span));
+
+        if let Some((debug_info, subprogram)) = &self.debug {
+            self.builder
+                .set_current_debug_location(debug_info.location(*subprogram, Some(span)));
+        }
+    }
+
+    pub fn has_debug_info(&self) -> bool {
+        self.debug.is_some()
+    }
+
+    pub fn declare_variable(
+        &self,
+        name: &str,
+        storage: PointerValue<'ctx>,
+        storage_type: BasicTypeEnum<'ctx>,
+        ty: Option<&Type>,
+    ) {
+        if let Some((debug_info, subprogram)) = &self.debug {
+            debug_info.declare_variable(
+                &self.builder,
+                *subprogram,
+                self.location.get(),
+                name,
+                storage,
+                storage_type,
+                ty,
+            );
         }
     }
 
     pub fn generate_from_ast(&'ctx self, ast: Vec<Rc<LASTUnit>>) {
         self.internal_generate_from_ast(ast);
+
+        let block = self.builder.get_insert_block().unwrap();
+
+        if block.get_terminator().is_some() {
+            return;
+        }
+
         if self.is_void {
             self.builder.build_return(None).unwrap();
+        } else if block.get_first_use().is_none()
+            && self.function.get_first_basic_block() != Some(block)
+        {
+            // nothing continues after branches that all return
+            self.builder.build_unreachable().unwrap();
         }
     }
 
@@ -81,28 +172,40 @@ impl<'ctx> LLVMFunctionGenerator<'ctx> {
 
             match unit.as_ref() {
                 LASTUnit::Declaration(decl) => match decl {
-                    Declaration::FunctionDeclaration { .. } => {
+                    Declaration::FunctionDeclaration { .. }
+                    | Declaration::ExternFunctionDeclaration { .. } => {
                         panic!("dont declare functions within functions pls")
                     }
+                    Declaration::TypeDeclaration { .. } | Declaration::EnumDeclaration { .. } => {
+                        panic!("types are declared at the top level")
+                    }
                     Declaration::VariableDeclaration {
                         allocation,
                         expression,
                         identifier,
+                        ty,
                     } => {
                         let var_gen = LLVMVariableGenerator::new(
                             self.context,
+                            Rc::clone(&self.module),
                             Rc::clone(&self.builder),
                             Rc::clone(&self.stack_frame),
                             Rc::clone(&self.ssa),
                             Rc::clone(&self.function_stack),
                             self,
                         );
-                        var_gen.generate_for_ast(allocation, identifier, expression.as_ref());
+                        var_gen.generate_for_ast(
+                            allocation,
+                            identifier,
+                            ty.as_ref(),
+                            expression.as_ref(),
+                        );
                     }
                 },
                 LASTUnit::Expression(expr) => {
                     self.expr_gen.generate_from_ast(&expr, None);
                 }
+                LASTUnit::Location(span) => self.set_location(*span),
                 LASTUnit::Statement(stmt) => {
                     let remaining_instruct = ast
                         .iter()
@@ -1,6 +1,8 @@
 pub mod common;
+pub mod debug;
 pub mod expression;
 pub mod function;
 pub mod module;
+pub mod runtime;
 pub mod statement;
 pub mod variable;
@@ -1,16 +1,18 @@
 use std::{cell::RefCell, collections::HashMap, ptr, rc::Rc};
 
-use common::types::Type;
+use common::{constants::builtins::ENTRY_POINT, diagnostic::Diagnostic, span::Span, types::Type};
 use inkwell::{
     context::Context,
-    module::Module,
-    types::{BasicMetadataTypeEnum, BasicType},
+    module::{Linkage, Module},
+    types::{BasicMetadataTypeEnum, BasicType, BasicTypeEnum},
     values::{BasicValueEnum, FunctionValue},
+    AddressSpace,
 };
 use preprocessor::last::{declaration::Declaration, unit::LASTUnit, LoweredAbstractSyntaxTree};
 
 use super::{
-    common::type_for,
+    common::{declare_enum_types, define_enum_types, type_for, TypeSizes},
+    debug::DebugInfo,
     function::{LLVMFunctionGenerator, StackFrame, SSA},
 };
 
@@ -18,8 +20,10 @@ pub type FunctionStack<'ctx> = HashMap<String, FunctionValue<'ctx>>;
 
 pub struct LLVMModuleGenerator<'ctx> {
     context: &'ctx Context,
-    module: Module<'ctx>,
+    module: Rc<Module<'ctx>>,
     function_stack: Rc<RefCell<FunctionStack<'ctx>>>,
+    verify: bool,
+    debug_info: Option<Rc<DebugInfo<'ctx>>>,
 }
 
 impl<'ctx> LLVMModuleGenerator<'ctx> {
@@ -32,44 +36,288 @@ impl<'ctx> LLVMModuleGenerator<'ctx> {
 
         Self {
             context,
-            module,
+            module: Rc::new(module),
             function_stack: Rc::new(RefCell::new(FunctionStack::new())),
+            verify: true,
+            debug_info: None,
         }
     }
 
-    pub fn generate_from_ast(&self, ast: LoweredAbstractSyntaxTree) {
+    // whether functions and the module are checked by the LLVM verifier
+    pub fn set_verify(&mut self, verify: bool) {
+        self.verify = verify;
+    }
+
+    // emits DWARF for the given source file, locations come from the spans in the tree
+    pub fn set_debug_info(&mut self, path: &str, source: &str) {
+        self.debug_info = Some(Rc::new(DebugInfo::new(
+            self.context,
+            &self.module,
+            path,
+            source,
+        )));
+    }
+
+    // adds a file imported by the source set with `set_debug_info`, its spans start at `offset`
+    pub fn add_debug_file(&mut self, path: &str, source: &str, offset: usize) {
+        if let Some(debug_info) = self.debug_info.as_mut().and_then(Rc::get_mut) {
+            debug_info.add_file(path, source, offset);
+        }
+    }
+
+    pub fn generate_from_ast(&self, ast: LoweredAbstractSyntaxTree) -> Result<(), Diagnostic> {
+        self.declare_structs(&ast);
+        self.declare_functions(&ast);
+
+        let mut location = None;
+
         for unit in ast.root() {
-            self.__generate_from_ast(Rc::clone(unit));
+            if let LASTUnit::Location(span) = unit.as_ref() {
+                location = Some(*span);
+                continue;
+            }
+
+            if let Some(function) = self.__generate_from_ast(Rc::clone(unit), &ast, location) {
+                let name = function.get_name().to_string_lossy();
+
+                if self.verify && !function.verify(false) {
+                    return Err(self
+                        .verification_error(&format!("function `{}`", ast.function_name(&name))));
+                }
+            }
+        }
+
+        if let Some(entry_point) = ast.entry_point() {
+            self.generate_entry_point(entry_point);
+        }
+
+        if let Some(debug_info) = &self.debug_info {
+            debug_info.finalize();
         }
+
+        if self.verify && self.module.verify().is_err() {
+            return Err(self.verification_error(&format!("module `{}`", self.module_name())));
+        }
+
+        Ok(())
     }
 
-    fn __generate_from_ast(&self, unit: Rc<LASTUnit>) {
-        match unit.as_ref() {
-            LASTUnit::Declaration(decl) => match decl {
+    // named first and given bodies after, so that fields can be of any struct or enum type
+    fn declare_structs(&self, ast: &LoweredAbstractSyntaxTree) {
+        let structs = ast
+            .root()
+            .iter()
+            .filter_map(|unit| match unit.as_ref() {
+                LASTUnit::Declaration(Declaration::TypeDeclaration { identifier, fields }) => {
+                    Some((identifier, fields))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        let enums = ast
+            .root()
+            .iter()
+            .filter_map(|unit| match unit.as_ref() {
+                LASTUnit::Declaration(Declaration::EnumDeclaration {
+                    identifier,
+                    variants,
+                }) => Some((identifier, variants)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        let mut sizes = TypeSizes::new();
+
+        for (identifier, fields) in &structs {
+            self.context.opaque_struct_type(identifier);
+            sizes.add_struct(identifier, fields);
+        }
+
+        for (identifier, variants) in &enums {
+            declare_enum_types(self.context, identifier, variants);
+            sizes.add_enum(identifier, variants);
+        }
+
+        for (identifier, variants) in enums {
+            define_enum_types(self.context, identifier, variants, &sizes);
+
+            if let Some(debug_info) = &self.debug_info {
+                debug_info.add_enum(identifier, variants, sizes.payload(variants));
+            }
+        }
+
+        for (identifier, fields) in structs {
+            if let Some(debug_info) = &self.debug_info {
+                debug_info.add_struct(identifier, fields);
+            }
+
+            let fields = fields
+                .iter()
+                .map(|(_, ty)| type_for(self.context, ty))
+                .collect::<Vec<BasicTypeEnum<'ctx>>>();
+
+            self.context
+                .get_struct_type(identifier)
+                .unwrap()
+                .set_body(&fields, false);
+        }
+    }
+
+    fn module_name(&self) -> String {
+        self.module.get_name().to_string_lossy().to_string()
+    }
+
+    // the function verifier only prints, the module one reports why
+    fn verification_error(&self, subject: &str) -> Diagnostic {
+        let reason = self
+            .module
+            .verify()
+            .err()
+            .map(|error| error.to_string())
+            .unwrap_or_default();
+
+        Diagnostic::new(
+            format!("invalid LLVM IR generated for {subject}: {}", reason.trim()),
+            None,
+        )
+    }
+
+    // declared before any body is generated, so that functions can call the ones after them
+    fn declare_functions(&self, ast: &LoweredAbstractSyntaxTree) {
+        for unit in ast.root() {
+            let LASTUnit::Declaration(declaration) = unit.as_ref() else {
+                continue;
+            };
+
+            let (identifier, function) = match declaration {
                 Declaration::FunctionDeclaration {
-                    body,
                     identifier,
+                    exported,
                     parameters,
                     return_type,
+                    ..
                 } => {
-                    let params = &parameters
-                        .into_iter()
+                    let params = parameters
+                        .iter()
                         .map(|(_, ty)| type_for(self.context, ty).into())
                         .collect::<Vec<BasicMetadataTypeEnum<'ctx>>>();
 
-                    let function = self.module.add_function(
-                        identifier.as_str(),
-                        match return_type {
-                            Type::Void => None,
-                            other => Some(other),
-                        }
-                        .map(|ty| type_for(self.context, &ty).fn_type(&params, false))
-                        .unwrap_or(self.context.void_type().fn_type(&params, false)),
-                        None,
-                    );
+                    let fn_type = match return_type {
+                        Type::Void => self.context.void_type().fn_type(&params, false),
+                        ty => type_for(self.context, ty).fn_type(&params, false),
+                    };
+
+                    // only exported functions are seen by a C host,
+                    // the numbered names of the others would clash between modules
+                    let linkage = match exported {
+                        true => Linkage::External,
+                        false => Linkage::Internal,
+                    };
+
+                    let function = self.module.add_function(identifier, fn_type, Some(linkage));
+
+                    (identifier, function)
+                }
+                // strings are passed to C as pointers to their bytes
+                Declaration::ExternFunctionDeclaration {
+                    identifier,
+                    parameters,
+                    variadic,
+                    return_type,
+                } => {
+                    let params = parameters
+                        .iter()
+                        .map(|(_, ty)| match ty {
+                            Type::String => self.context.ptr_type(AddressSpace::default()).into(),
+                            ty => type_for(self.context, ty).into(),
+                        })
+                        .collect::<Vec<BasicMetadataTypeEnum<'ctx>>>();
+
+                    let fn_type = match return_type {
+                        Type::Void => self.context.void_type().fn_type(&params, *variadic),
+                        ty => type_for(self.context, ty).fn_type(&params, *variadic),
+                    };
+
+                    // the runtime may have declared it already
+                    let function = self.module.get_function(identifier).unwrap_or_else(|| {
+                        self.module
+                            .add_function(identifier, fn_type, Some(Linkage::External))
+                    });
+
+                    (identifier, function)
+                }
+                _ => continue,
+            };
+
+            self.function_stack
+                .borrow_mut()
+                .insert(identifier.clone(), function);
+        }
+    }
+
+    // C-ABI `main` calling the bloom one, its return value becomes the exit code,
+    // the type checker makes sure it takes nothing and returns an integer or nothing
+    fn generate_entry_point(&self, entry_point: &str) {
+        let bloom_main = *self
+            .function_stack
+            .borrow()
+            .get(entry_point)
+            .expect("main is declared");
+
+        let i32_type = self.context.i32_type();
+        let function = self
+            .module
+            .add_function(ENTRY_POINT, i32_type.fn_type(&[], false), None);
+
+        let builder = self.context.create_builder();
+        builder.position_at_end(self.context.append_basic_block(function, "entry"));
+
+        let exit_code = builder
+            .build_call(bloom_main, &[], "exit_code")
+            .unwrap()
+            .try_as_basic_value()
+            .left();
+
+        let exit_code = match exit_code {
+            None => i32_type.const_zero(),
+            Some(BasicValueEnum::IntValue(value)) => {
+                let is_signed = value.get_type().get_bit_width() != 1;
+
+                builder
+                    .build_int_cast_sign_flag(value, i32_type, is_signed, "exit_code")
+                    .unwrap()
+            }
+            Some(_) => unreachable!("main returns an integer or nothing"),
+        };
+
+        builder.build_return(Some(&exit_code)).unwrap();
+    }
+
+    fn __generate_from_ast(
+        &self,
+        unit: Rc<LASTUnit>,
+        ast: &LoweredAbstractSyntaxTree,
+        location: Option<Span>,
+    ) -> Option<FunctionValue<'ctx>> {
+        match unit.as_ref() {
+            LASTUnit::Declaration(decl) => match decl {
+                Declaration::FunctionDeclaration {
+                    body,
+                    identifier,
+                    parameters,
+                    return_type,
+                    ..
+                } => {
+                    let function = *self
+                        .function_stack
+                        .borrow()
+                        .get(identifier)
+                        .expect("functions are declared before their bodies");
 
                     let fn_gen = LLVMFunctionGenerator::new(
                         self.context,
+                        Rc::clone(&self.module),
                         function,
                         parameters
                             .iter()
@@ -78,14 +326,33 @@ impl<'ctx> LLVMModuleGenerator<'ctx> {
                             .as_slice(),
                         Rc::clone(&self.function_stack),
                         Rc::new(self.context.create_builder()),
+                        self.debug_info.as_ref().map(|debug_info| {
+                            let name = ast.function_name(identifier);
+
+                            (
+                                Rc::clone(debug_info),
+                                debug_info.subprogram(
+                                    function,
+                                    name,
+                                    location,
+                                    parameters,
+                                    return_type,
+                                ),
+                            )
+                        }),
                     );
+                    if let Some(span) = location {
+                        fn_gen.set_location(span);
+                    }
                     unsafe { (&fn_gen as *const LLVMFunctionGenerator).as_ref().unwrap() }
                         .generate_from_ast(body.clone());
 
-                    self.function_stack
-                        .borrow_mut()
-                        .insert(identifier.clone(), function);
+                    Some(function)
                 }
+                // declared up front
+                Declaration::ExternFunctionDeclaration { .. }
+                | Declaration::TypeDeclaration { .. }
+                | Declaration::EnumDeclaration { .. } => None,
                 _ => todo!(),
             },
             _ => todo!(),
@@ -0,0 +1,649 @@
+use std::rc::Rc;
+
+use inkwell::{
+    attributes::{Attribute, AttributeLoc},
+    context::Context,
+    intrinsics::Intrinsic,
+    module::{Linkage, Module},
+    types::BasicTypeEnum,
+    values::FunctionValue,
+    AddressSpace, IntPredicate,
+};
+
+use super::common::string_type;
+
+pub const RUNTIME_STR_LEN: &str = "bloom.str.len";
+pub const RUNTIME_STR_CONCAT: &str = "bloom.str.concat";
+pub const RUNTIME_STR_EQ: &str = "bloom.str.eq";
+pub const RUNTIME_STR_INDEX: &str = "bloom.str.index";
+pub const RUNTIME_INDEX_OUT_OF_BOUNDS: &str = "bloom.index.out_of_bounds";
+pub const RUNTIME_SLICE_OUT_OF_BOUNDS: &str = "bloom.slice.out_of_bounds";
+pub const RUNTIME_PRINT_CHAR: &str = "bloom.print.char";
+pub const RUNTIME_ASSERT_FAILED: &str = "bloom.assert.failed";
+
+pub struct LLVMRuntime<'ctx> {
+    context: &'ctx Context,
+    module: Rc<Module<'ctx>>,
+}
+
+impl<'ctx> LLVMRuntime<'ctx> {
+    pub fn new(context: &'ctx Context, module: Rc<Module<'ctx>>) -> Self {
+        Self { context, module }
+    }
+
+    pub fn str_len(&self) -> FunctionValue<'ctx> {
+        if let Some(function) = self.module.get_function(RUNTIME_STR_LEN) {
+            return function;
+        }
+
+        let function = self.module.add_function(
+            RUNTIME_STR_LEN,
+            self.context
+                .i64_type()
+                .fn_type(&[string_type(self.context).into()], false),
+            Some(Linkage::Internal),
+        );
+
+        let builder = self.context.create_builder();
+        builder.position_at_end(self.context.append_basic_block(function, "entry"));
+
+        let string = function.get_nth_param(0).unwrap().into_struct_value();
+        let len = builder.build_extract_value(string, 1, "len").unwrap();
+
+        builder.build_return(Some(&len)).unwrap();
+
+        function
+    }
+
+    // the result is a new heap string owned by no one: strings have no destructor, and
+    // literals live in globals, so `free` can't tell them apart and concatenations stay
+    // allocated until the program exits
+    pub fn str_concat(&self) -> FunctionValue<'ctx> {
+        if let Some(function) = self.module.get_function(RUNTIME_STR_CONCAT) {
+            return function;
+        }
+
+        let str_type = string_type(self.context);
+
+        let function = self.module.add_function(
+            RUNTIME_STR_CONCAT,
+            str_type.fn_type(&[str_type.into(), str_type.into()], false),
+            Some(Linkage::Internal),
+        );
+
+        let builder = self.context.create_builder();
+        builder.position_at_end(self.context.append_basic_block(function, "entry"));
+
+        let lhs = function.get_nth_param(0).unwrap().into_struct_value();
+        let rhs = function.get_nth_param(1).unwrap().into_struct_value();
+
+        let lhs_ptr = builder
+            .build_extract_value(lhs, 0, "lhs.ptr")
+            .unwrap()
+            .into_pointer_value();
+        let lhs_len = builder
+            .build_extract_value(lhs, 1, "lhs.len")
+            .unwrap()
+            .into_int_value();
+        let rhs_ptr = builder
+            .build_extract_value(rhs, 0, "rhs.ptr")
+            .unwrap()
+            .into_pointer_value();
+        let rhs_len = builder
+            .build_extract_value(rhs, 1, "rhs.len")
+            .unwrap()
+            .into_int_value();
+
+        let len = builder.build_int_add(lhs_len, rhs_len, "len").unwrap();
+        // one more byte for the NUL, like the one after literals
+        let size = builder
+            .build_int_add(len, self.context.i64_type().const_int(1, false), "size")
+            .unwrap();
+        let ptr = builder
+            .build_call(self.malloc(), &[size.into()], "ptr")
+            .unwrap()
+            .try_as_basic_value()
+            .left()
+            .unwrap()
+            .into_pointer_value();
+
+        builder.build_memcpy(ptr, 1, lhs_ptr, 1, lhs_len).unwrap();
+        let tail = unsafe {
+            builder
+                .build_in_bounds_gep(self.context.i8_type(), ptr, &[lhs_len], "tail")
+                .unwrap()
+        };
+        builder.build_memcpy(tail, 1, rhs_ptr, 1, rhs_len).unwrap();
+        let end = unsafe {
+            builder
+                .build_in_bounds_gep(self.context.i8_type(), ptr, &[len], "end")
+                .unwrap()
+        };
+        builder
+            .build_store(end, self.context.i8_type().const_zero())
+            .unwrap();
+
+        let result = builder
+            .build_insert_value(str_type.get_undef(), ptr, 0, "")
+            .unwrap();
+        let result = builder
+            .build_insert_value(result, len, 1, "")
+            .unwrap()
+            .into_struct_value();
+
+        builder.build_return(Some(&result)).unwrap();
+
+        function
+    }
+
+    pub fn str_eq(&self) -> FunctionValue<'ctx> {
+        if let Some(function) = self.module.get_function(RUNTIME_STR_EQ) {
+            return function;
+        }
+
+        let str_type = string_type(self.context);
+
+        let function = self.module.add_function(
+            RUNTIME_STR_EQ,
+            self.context
+                .bool_type()
+                .fn_type(&[str_type.into(), str_type.into()], false),
+            Some(Linkage::Internal),
+        );
+
+        let builder = self.context.create_builder();
+        let entry_block = self.context.append_basic_block(function, "entry");
+        let compare_block = self.context.append_basic_block(function, "compare");
+        let mismatch_block = self.context.append_basic_block(function, "mismatch");
+
+        builder.position_at_end(entry_block);
+
+        let lhs = function.get_nth_param(0).unwrap().into_struct_value();
+        let rhs = function.get_nth_param(1).unwrap().into_struct_value();
+
+        let lhs_len = builder
+            .build_extract_value(lhs, 1, "lhs.len")
+            .unwrap()
+            .into_int_value();
+        let rhs_len = builder
+            .build_extract_value(rhs, 1, "rhs.len")
+            .unwrap()
+            .into_int_value();
+
+        let same_len = builder
+            .build_int_compare(IntPredicate::EQ, lhs_len, rhs_len, "same_len")
+            .unwrap();
+        builder
+            .build_conditional_branch(same_len, compare_block, mismatch_block)
+            .unwrap();
+
+        // lengths match, compare the bytes
+        builder.position_at_end(compare_block);
+
+        let lhs_ptr = builder.build_extract_value(lhs, 0, "lhs.ptr").unwrap();
+        let rhs_ptr = builder.build_extract_value(rhs, 0, "rhs.ptr").unwrap();
+        let cmp = builder
+            .build_call(
+                self.memcmp(),
+                &[lhs_ptr.into(), rhs_ptr.into(), lhs_len.into()],
+                "cmp",
+            )
+            .unwrap()
+            .try_as_basic_value()
+            .left()
+            .unwrap()
+            .into_int_value();
+        let eq = builder
+            .build_int_compare(
+                IntPredicate::EQ,
+                cmp,
+                self.context.i32_type().const_zero(),
+                "eq",
+            )
+            .unwrap();
+        builder.build_return(Some(&eq)).unwrap();
+
+        builder.position_at_end(mismatch_block);
+        builder
+            .build_return(Some(&self.context.bool_type().const_zero()))
+            .unwrap();
+
+        function
+    }
+
+    pub fn str_index(&self) -> FunctionValue<'ctx> {
+        if let Some(function) = self.module.get_function(RUNTIME_STR_INDEX) {
+            return function;
+        }
+
+        let function = self.module.add_function(
+            RUNTIME_STR_INDEX,
+            self.context.i8_type().fn_type(
+                &[
+                    string_type(self.context).into(),
+                    self.context.i64_type().into(),
+                ],
+                false,
+            ),
+            Some(Linkage::Internal),
+        );
+
+        let builder = self.context.create_builder();
+        let entry_block = self.context.append_basic_block(function, "entry");
+        let in_bounds_block = self.context.append_basic_block(function, "in_bounds");
+        let out_of_bounds_block = self.context.append_basic_block(function, "out_of_bounds");
+
+        builder.position_at_end(entry_block);
+
+        let string = function.get_nth_param(0).unwrap().into_struct_value();
+        let index = function.get_nth_param(1).unwrap().into_int_value();
+
+        let len = builder
+            .build_extract_value(string, 1, "len")
+            .unwrap()
+            .into_int_value();
+        // unsigned comparison also rejects negative indices
+        let in_bounds = builder
+            .build_int_compare(IntPredicate::ULT, index, len, "in_bounds")
+            .unwrap();
+        builder
+            .build_conditional_branch(in_bounds, in_bounds_block, out_of_bounds_block)
+            .unwrap();
+
+        builder.position_at_end(in_bounds_block);
+
+        let ptr = builder
+            .build_extract_value(string, 0, "ptr")
+            .unwrap()
+            .into_pointer_value();
+        let byte_ptr = unsafe {
+            builder
+                .build_in_bounds_gep(self.context.i8_type(), ptr, &[index], "byte_ptr")
+                .unwrap()
+        };
+        let byte = builder
+            .build_load(self.context.i8_type(), byte_ptr, "byte")
+            .unwrap();
+        builder.build_return(Some(&byte)).unwrap();
+
+        builder.position_at_end(out_of_bounds_block);
+        builder
+            .build_call(self.index_out_of_bounds(), &[index.into(), len.into()], "")
+            .unwrap();
+        builder.build_unreachable().unwrap();
+
+        function
+    }
+
+    // prints the index and the length to stderr and traps, being cold and
+    // noreturn keeps the failing branch of a bounds check off the hot path
+    pub fn index_out_of_bounds(&self) -> FunctionValue<'ctx> {
+        if let Some(function) = self.module.get_function(RUNTIME_INDEX_OUT_OF_BOUNDS) {
+            return function;
+        }
+
+        let i64_type = self.context.i64_type();
+
+        let function = self.module.add_function(
+            RUNTIME_INDEX_OUT_OF_BOUNDS,
+            self.context
+                .void_type()
+                .fn_type(&[i64_type.into(), i64_type.into()], false),
+            Some(Linkage::Internal),
+        );
+
+        self.add_cold_attributes(function);
+
+        let builder = self.context.create_builder();
+        builder.position_at_end(self.context.append_basic_block(function, "entry"));
+
+        let index = function.get_nth_param(0).unwrap();
+        let len = function.get_nth_param(1).unwrap();
+
+        let message = builder
+            .build_global_string_ptr(
+                "index out of bounds: the length is %llu but the index is %llu\n",
+                "index_out_of_bounds",
+            )
+            .unwrap();
+
+        // stderr
+        let fd = self.context.i32_type().const_int(2, false);
+
+        builder
+            .build_call(
+                self.dprintf(),
+                &[
+                    fd.into(),
+                    message.as_pointer_value().into(),
+                    len.into(),
+                    index.into(),
+                ],
+                "",
+            )
+            .unwrap();
+        builder.build_call(self.trap(), &[], "").unwrap();
+        builder.build_unreachable().unwrap();
+
+        function
+    }
+
+    // like `index_out_of_bounds`, for a range that does not fit the length
+    // or ends before it starts
+    pub fn slice_out_of_bounds(&self) -> FunctionValue<'ctx> {
+        if let Some(function) = self.module.get_function(RUNTIME_SLICE_OUT_OF_BOUNDS) {
+            return function;
+        }
+
+        let i64_type = self.context.i64_type();
+
+        let function = self.module.add_function(
+            RUNTIME_SLICE_OUT_OF_BOUNDS,
+            self.context
+                .void_type()
+                .fn_type(&[i64_type.into(), i64_type.into(), i64_type.into()], false),
+            Some(Linkage::Internal),
+        );
+
+        self.add_cold_attributes(function);
+
+        let builder = self.context.create_builder();
+        builder.position_at_end(self.context.append_basic_block(function, "entry"));
+
+        let start = function.get_nth_param(0).unwrap();
+        let end = function.get_nth_param(1).unwrap();
+        let len = function.get_nth_param(2).unwrap();
+
+        let message = builder
+            .build_global_string_ptr(
+                "slice out of bounds: the length is %llu but the range is %llu..%llu\n",
+                "slice_out_of_bounds",
+            )
+            .unwrap();
+
+        // stderr
+        let fd = self.context.i32_type().const_int(2, false);
+
+        builder
+            .build_call(
+                self.dprintf(),
+                &[
+                    fd.into(),
+                    message.as_pointer_value().into(),
+                    len.into(),
+                    start.into(),
+                    end.into(),
+                ],
+                "",
+            )
+            .unwrap();
+        builder.build_call(self.trap(), &[], "").unwrap();
+        builder.build_unreachable().unwrap();
+
+        function
+    }
+
+    fn add_cold_attributes(&self, function: FunctionValue<'ctx>) {
+        for attribute in ["noreturn", "cold", "noinline"] {
+            function.add_attribute(
+                AttributeLoc::Function,
+                self.context
+                    .create_enum_attribute(Attribute::get_named_enum_kind_id(attribute), 0),
+            );
+        }
+    }
+
+    // writes the UTF-8 encoding of a char to stdout, which takes one to four bytes
+    pub fn print_char(&self) -> FunctionValue<'ctx> {
+        if let Some(function) = self.module.get_function(RUNTIME_PRINT_CHAR) {
+            return function;
+        }
+
+        let i32_type = self.context.i32_type();
+        let i8_type = self.context.i8_type();
+
+        let function = self.module.add_function(
+            RUNTIME_PRINT_CHAR,
+            self.context.void_type().fn_type(&[i32_type.into()], false),
+            Some(Linkage::Internal),
+        );
+
+        let builder = self.context.create_builder();
+        let entry_block = self.context.append_basic_block(function, "entry");
+
+        builder.position_at_end(entry_block);
+
+        let code_point = function.get_nth_param(0).unwrap().into_int_value();
+        let buffer = builder
+            .build_alloca(i8_type.array_type(4), "buffer")
+            .unwrap();
+
+        // one byte more past each of the limits
+        let mut len = i32_type.const_int(1, false);
+        for limit in [0x80, 0x800, 0x10000] {
+            let above = builder
+                .build_int_compare(
+                    IntPredicate::UGE,
+                    code_point,
+                    i32_type.const_int(limit, false),
+                    "above",
+                )
+                .unwrap();
+            let above = builder.build_int_z_extend(above, i32_type, "").unwrap();
+            len = builder.build_int_add(len, above, "len").unwrap();
+        }
+
+        let format = builder
+            .build_global_string_ptr("%.*s", "print_char")
+            .unwrap();
+
+        let mut cases = vec![];
+        for bytes in 1..=4u64 {
+            let block = self
+                .context
+                .append_basic_block(function, &format!("bytes{bytes}"));
+            builder.position_at_end(block);
+
+            // the leading byte tells how many follow it, each following one has six bits
+            for byte in 0..bytes {
+                let shift = i32_type.const_int(6 * (bytes - byte - 1), false);
+                let bits = builder
+                    .build_right_shift(code_point, shift, false, "")
+                    .unwrap();
+                let (mask, marker) = match (byte, bytes) {
+                    (0, 1) => (0x7f, 0x00),
+                    (0, 2) => (0x1f, 0xc0),
+                    (0, 3) => (0x0f, 0xe0),
+                    (0, _) => (0x07, 0xf0),
+                    _ => (0x3f, 0x80),
+                };
+                let bits = builder
+                    .build_and(bits, i32_type.const_int(mask, false), "")
+                    .unwrap();
+                let bits = builder
+                    .build_or(bits, i32_type.const_int(marker, false), "")
+                    .unwrap();
+                let bits = builder.build_int_truncate(bits, i8_type, "").unwrap();
+
+                let ptr = unsafe {
+                    builder
+                        .build_in_bounds_gep(
+                            i8_type,
+                            buffer,
+                            &[self.context.i64_type().const_int(byte, false)],
+                            "",
+                        )
+                        .unwrap()
+                };
+                builder.build_store(ptr, bits).unwrap();
+            }
+
+            builder
+                .build_call(
+                    self.dprintf(),
+                    &[
+                        i32_type.const_int(1, false).into(),
+                        format.as_pointer_value().into(),
+                        i32_type.const_int(bytes, false).into(),
+                        buffer.into(),
+                    ],
+                    "",
+                )
+                .unwrap();
+            builder.build_return(None).unwrap();
+
+            cases.push((i32_type.const_int(bytes, false), block));
+        }
+
+        builder.position_at_end(entry_block);
+        builder.build_switch(len, cases[0].1, &cases[1..]).unwrap();
+
+        function
+    }
+
+    // prints the message to stderr and aborts, cold and noreturn like `index_out_of_bounds`
+    pub fn assert_failed(&self) -> FunctionValue<'ctx> {
+        if let Some(function) = self.module.get_function(RUNTIME_ASSERT_FAILED) {
+            return function;
+        }
+
+        let function = self.module.add_function(
+            RUNTIME_ASSERT_FAILED,
+            self.context
+                .void_type()
+                .fn_type(&[string_type(self.context).into()], false),
+            Some(Linkage::Internal),
+        );
+
+        self.add_cold_attributes(function);
+
+        let builder = self.context.create_builder();
+        builder.position_at_end(self.context.append_basic_block(function, "entry"));
+
+        let message = function.get_nth_param(0).unwrap().into_struct_value();
+        let ptr = builder.build_extract_value(message, 0, "ptr").unwrap();
+        let len = builder
+            .build_extract_value(message, 1, "len")
+            .unwrap()
+            .into_int_value();
+        // the precision of `%.*s` is an int
+        let len = builder
+            .build_int_truncate(len, self.context.i32_type(), "len")
+            .unwrap();
+
+        let format = builder
+            .build_global_string_ptr("assertion failed: %.*s\n", "assert_failed")
+            .unwrap();
+
+        builder
+            .build_call(
+                self.dprintf(),
+                &[
+                    self.context.i32_type().const_int(2, false).into(),
+                    format.as_pointer_value().into(),
+                    len.into(),
+                    ptr.into(),
+                ],
+                "",
+            )
+            .unwrap();
+        builder.build_call(self.abort(), &[], "").unwrap();
+        builder.build_unreachable().unwrap();
+
+        function
+    }
+
+    pub fn malloc(&self) -> FunctionValue<'ctx> {
+        self.module.get_function("malloc").unwrap_or_else(|| {
+            self.module.add_function(
+                "malloc",
+                self.context
+                    .ptr_type(AddressSpace::default())
+                    .fn_type(&[self.context.i64_type().into()], false),
+                Some(Linkage::External),
+            )
+        })
+    }
+
+    pub fn free(&self) -> FunctionValue<'ctx> {
+        self.module.get_function("free").unwrap_or_else(|| {
+            self.module.add_function(
+                "free",
+                self.context.void_type().fn_type(
+                    &[self.context.ptr_type(AddressSpace::default()).into()],
+                    false,
+                ),
+                Some(Linkage::External),
+            )
+        })
+    }
+
+    fn memcmp(&self) -> FunctionValue<'ctx> {
+        self.module.get_function("memcmp").unwrap_or_else(|| {
+            let ptr_type = self.context.ptr_type(AddressSpace::default());
+
+            self.module.add_function(
+                "memcmp",
+                self.context.i32_type().fn_type(
+                    &[
+                        ptr_type.into(),
+                        ptr_type.into(),
+                        self.context.i64_type().into(),
+                    ],
+                    false,
+                ),
+                Some(Linkage::External),
+            )
+        })
+    }
+
+    pub fn abort(&self) -> FunctionValue<'ctx> {
+        self.module.get_function("abort").unwrap_or_else(|| {
+            self.module.add_function(
+                "abort",
+                self.context.void_type().fn_type(&[], false),
+                Some(Linkage::External),
+            )
+        })
+    }
+
+    pub fn exit(&self) -> FunctionValue<'ctx> {
+        self.module.get_function("exit").unwrap_or_else(|| {
+            self.module.add_function(
+                "exit",
+                self.context
+                    .void_type()
+                    .fn_type(&[self.context.i32_type().into()], false),
+                Some(Linkage::External),
+            )
+        })
+    }
+
+    pub fn dprintf(&self) -> FunctionValue<'ctx> {
+        self.module.get_function("dprintf").unwrap_or_else(|| {
+            self.module.add_function(
+                "dprintf",
+                self.context.i32_type().fn_type(
+                    &[
+                        self.context.i32_type().into(),
+                        self.context.ptr_type(AddressSpace::default()).into(),
+                    ],
+                    true,
+                ),
+                Some(Linkage::External),
+            )
+        })
+    }
+
+    fn trap(&self) -> FunctionValue<'ctx> {
+        self.intrinsic("llvm.trap", &[])
+    }
+
+    // overloaded intrinsics are declared for the types they are called with
+    pub fn intrinsic(&self, name: &str, types: &[BasicTypeEnum<'ctx>]) -> FunctionValue<'ctx> {
+        Intrinsic::find(name)
+            .unwrap()
+            .get_declaration(&self.module, types)
+            .unwrap()
+    }
+}
@@ -4,11 +4,14 @@ use inkwell::{
     basic_block::BasicBlock,
     builder::Builder,
     context::Context,
-    values::{BasicValue, FunctionValue},
+    values::{BasicValue, BasicValueEnum, FunctionValue},
 };
 use preprocessor::last::{expression::Expression, statement::Statement, unit::LASTUnit};
 
-use super::{expression::LLVMExpressionGenerator, function::LLVMFunctionGenerator};
+use super::{
+    expression::LLVMExpressionGenerator,
+    function::{LLVMFunctionGenerator, SSA},
+};
 
 pub struct LLVMStatementGenerator<'ctx> {
     builder: &'ctx Builder<'ctx>,
@@ -37,6 +40,10 @@ impl<'ctx> LLVMStatementGenerator<'ctx> {
 
     pub fn generate_from_ast(&self, stmt: &'ctx Statement, next: Vec<Rc<LASTUnit>>) {
         match stmt {
+            // the value of a void function is only the implicit return of its last statement
+            Statement::Return(_) if self.fn_gen.is_void() => {
+                self.builder.build_return(None).unwrap();
+            }
             Statement::Return(ret) => {
                 let ret_value = self.expression_gen.generate_from_ast(ret.as_ref(), None);
 
@@ -58,8 +65,15 @@ impl<'ctx> LLVMStatementGenerator<'ctx> {
                 execute.clone(),
                 alternative.clone(),
                 next,
-                None,
             ),
+            Statement::Switch {
+                value,
+                cases,
+                default,
+            } => self.generate_switch(Rc::clone(value), cases.clone(), default.clone(), next),
+            Statement::Unreachable => {
+                self.builder.build_unreachable().unwrap();
+            }
         }
     }
 
@@ -70,26 +84,16 @@ impl<'ctx> LLVMStatementGenerator<'ctx> {
         body: Vec<Rc<LASTUnit>>,
         next: Vec<Rc<LASTUnit>>,
     ) {
-        let entry_block = self.builder.get_insert_block().unwrap();
-
         let header_block = self
             .context
             .append_basic_block(self.function, "while.header");
-        self.builder.position_at_end(header_block);
-
         let body_block = self.context.append_basic_block(self.function, "body");
-        self.builder.position_at_end(body_block);
+        let exit_block = self.context.append_basic_block(self.function, "exit");
 
-        self.fn_gen.internal_generate_from_ast(body);
         self.builder
             .build_unconditional_branch(header_block)
             .unwrap();
 
-        let exit_block = self.context.append_basic_block(self.function, "exit");
-        self.builder.position_at_end(exit_block);
-
-        self.fn_gen.internal_generate_from_ast(next);
-
         self.builder.position_at_end(header_block);
 
         self.fn_gen.internal_generate_from_ast(header);
@@ -107,14 +111,25 @@ impl<'ctx> LLVMStatementGenerator<'ctx> {
             .build_conditional_branch(cmp, body_block, exit_block)
             .unwrap();
 
-        self.builder.position_at_end(entry_block);
-        self.builder
-            .build_unconditional_branch(header_block)
-            .unwrap();
+        self.builder.position_at_end(body_block);
+
+        self.fn_gen.internal_generate_from_ast(body);
 
-        // position at the end of exit
-        // because entry block will not execute instructions after "br"
+        if self
+            .builder
+            .get_insert_block()
+            .and_then(|block| block.get_terminator())
+            .is_none()
+        {
+            self.builder
+                .build_unconditional_branch(header_block)
+                .unwrap();
+        }
+
+        // whatever is after the loop, the builder stays where it ends
         self.builder.position_at_end(exit_block);
+
+        self.fn_gen.internal_generate_from_ast(next);
     }
 
     fn generate_control_flow(
@@ -123,7 +138,6 @@ impl<'ctx> LLVMStatementGenerator<'ctx> {
         execute: Vec<Rc<LASTUnit>>,
         alternative: Option<Vec<Rc<LASTUnit>>>,
         next: Vec<Rc<LASTUnit>>,
-        continue_block: Option<BasicBlock<'ctx>>,
     ) {
         let cmp = self
             .expression_gen
@@ -138,59 +152,164 @@ impl<'ctx> LLVMStatementGenerator<'ctx> {
         // later append the "br" instruction with
         // both branches provided
         let cf_entry_block = self.builder.get_insert_block().unwrap();
+        let ssa = self.fn_gen.ssa();
+        let mut edges = vec![];
 
-        // whatever is after the control flow statement
-        let continue_block = if let Some(block) = continue_block {
-            block
-        } else {
-            let continue_block = self.context.append_basic_block(self.function, "continue");
-            self.builder.position_at_end(continue_block);
+        // the block to execute if condition is true
+        let execute_block = self.generate_branch("execute", execute, &ssa, &mut edges);
 
-            let next_ast = next.iter().map(Rc::clone).collect();
-            self.fn_gen.internal_generate_from_ast(next_ast);
+        // the block to execute if condition is false,
+        // an empty one falls through to whatever is after the statement
+        let alternative_block = self.generate_branch(
+            "alternative",
+            alternative.unwrap_or_default(),
+            &ssa,
+            &mut edges,
+        );
 
-            continue_block
-        };
+        self.builder.position_at_end(cf_entry_block);
+        self.builder
+            .build_conditional_branch(cmp, execute_block, alternative_block)
+            .unwrap();
 
-        // the block to execute if condition is true
-        let execute_block = self.context.append_basic_block(self.function, "execute");
-        self.builder.position_at_end(execute_block);
+        self.generate_continue(edges, next);
+    }
+
+    fn generate_switch(
+        &self,
+        value: Rc<Expression>,
+        cases: Vec<(u64, Vec<Rc<LASTUnit>>)>,
+        default: Option<Vec<Rc<LASTUnit>>>,
+        next: Vec<Rc<LASTUnit>>,
+    ) {
+        let value = self
+            .expression_gen
+            .generate_from_ast(
+                unsafe { (value.as_ref() as *const Expression).as_ref().unwrap() },
+                None,
+            )
+            .unwrap()
+            .into_int_value();
+
+        let switch_entry_block = self.builder.get_insert_block().unwrap();
+        let ssa = self.fn_gen.ssa();
+        let mut edges = vec![];
 
-        let execute_ast = execute;
-        self.fn_gen.internal_generate_from_ast(execute_ast);
+        let cases = cases
+            .into_iter()
+            .map(|(case, units)| {
+                (
+                    value.get_type().const_int(case, false),
+                    self.generate_branch("case", units, &ssa, &mut edges),
+                )
+            })
+            .collect::<Vec<_>>();
 
+        let default_block = match default {
+            Some(units) => self.generate_branch("default", units, &ssa, &mut edges),
+            None => {
+                // the value is always one of the cases
+                let default_block = self.context.append_basic_block(self.function, "default");
+                self.builder.position_at_end(default_block);
+                self.builder.build_unreachable().unwrap();
+
+                default_block
+            }
+        };
+
+        self.builder.position_at_end(switch_entry_block);
         self.builder
-            .build_unconditional_branch(continue_block)
+            .build_switch(value, default_block, &cases)
             .unwrap();
 
-        // the block to execute if condition is false
-        let alternative_block = if let Some(alternative) = alternative {
-            let alternative_block = self
-                .context
-                .append_basic_block(self.function, "alternative");
-            self.builder.position_at_end(alternative_block);
+        self.generate_continue(edges, next);
+    }
+
+    // generates a branch starting from the given values, a branch that does not
+    // return or the like is an edge into whatever is after the statement
+    fn generate_branch(
+        &self,
+        name: &str,
+        units: Vec<Rc<LASTUnit>>,
+        ssa: &SSA<'ctx>,
+        edges: &mut Vec<(BasicBlock<'ctx>, SSA<'ctx>)>,
+    ) -> BasicBlock<'ctx> {
+        let block = self.context.append_basic_block(self.function, name);
+        self.builder.position_at_end(block);
+        self.fn_gen.set_ssa(ssa.clone());
+
+        self.fn_gen.internal_generate_from_ast(units);
 
-            self.fn_gen.internal_generate_from_ast(alternative.clone());
+        let end = self.builder.get_insert_block().unwrap();
+
+        if end.get_terminator().is_none() {
+            edges.push((end, self.fn_gen.ssa()));
+        }
+
+        block
+    }
+
+    // values defined on every edge are merged, by phis when they differ
+    fn generate_continue(
+        &self,
+        edges: Vec<(BasicBlock<'ctx>, SSA<'ctx>)>,
+        next: Vec<Rc<LASTUnit>>,
+    ) {
+        let continue_block = self.context.append_basic_block(self.function, "continue");
+
+        for (block, _) in &edges {
+            self.builder.position_at_end(*block);
             self.builder
                 .build_unconditional_branch(continue_block)
                 .unwrap();
+        }
 
-            Some(alternative_block)
-        } else {
-            None
+        self.builder.position_at_end(continue_block);
+
+        // nothing is after a statement whose branches all return
+        let Some((_, first)) = edges.first() else {
+            return;
         };
 
-        self.builder.position_at_end(cf_entry_block);
-        self.builder
-            .build_conditional_branch(
-                cmp,
-                execute_block,
-                alternative_block.unwrap_or(continue_block),
-            )
-            .unwrap();
+        let mut names = first.keys().collect::<Vec<&String>>();
+        names.sort();
 
-        // position at the end of continue
-        // because entry block will not execute instructions after "br"
-        self.builder.position_at_end(continue_block);
+        let mut merged = SSA::new();
+
+        for name in names {
+            let incoming = edges
+                .iter()
+                .map(|(block, ssa)| ssa.get(name).map(|value| (*value, *block)))
+                .collect::<Option<Vec<(BasicValueEnum<'ctx>, BasicBlock<'ctx>)>>>();
+
+            let Some(incoming) = incoming else {
+                continue;
+            };
+
+            let (value, _) = incoming[0];
+
+            if incoming.iter().all(|(other, _)| *other == value) {
+                merged.insert(name.clone(), value);
+                continue;
+            }
+
+            if incoming
+                .iter()
+                .any(|(other, _)| other.get_type() != value.get_type())
+            {
+                continue;
+            }
+
+            let phi = self.builder.build_phi(value.get_type(), name).unwrap();
+
+            for (value, block) in &incoming {
+                phi.add_incoming(&[(value as &dyn BasicValue, *block)]);
+            }
+
+            merged.insert(name.clone(), phi.as_basic_value());
+        }
+
+        self.fn_gen.set_ssa(merged);
+        self.fn_gen.internal_generate_from_ast(next);
     }
 }
@@ -1,6 +1,7 @@
 use std::{cell::RefCell, rc::Rc};
 
-use inkwell::{builder::Builder, context::Context};
+use common::types::Type;
+use inkwell::{builder::Builder, context::Context, module::Module};
 use preprocessor::last::{declaration::VariableAllocation, expression::Expression, unit::LASTUnit};
 
 use super::{
@@ -13,6 +14,7 @@ use super::{
 pub struct LLVMVariableGenerator<'ctx> {
     builder: Rc<Builder<'ctx>>,
     context: &'ctx Context,
+    module: Rc<Module<'ctx>>,
     stack_frame: Rc<RefCell<StackFrame<'ctx>>>,
     ssa: Rc<RefCell<SSA<'ctx>>>,
     function_stack: Rc<RefCell<FunctionStack<'ctx>>>,
@@ -22,6 +24,7 @@ pub struct LLVMVariableGenerator<'ctx> {
 impl<'ctx> LLVMVariableGenerator<'ctx> {
     pub fn new(
         context: &'ctx Context,
+        module: Rc<Module<'ctx>>,
         builder: Rc<Builder<'ctx>>,
         stack_frame: Rc<RefCell<StackFrame<'ctx>>>,
         ssa: Rc<RefCell<SSA<'ctx>>>,
@@ -31,6 +34,7 @@ impl<'ctx> LLVMVariableGenerator<'ctx> {
         Self {
             builder,
             context,
+            module,
             stack_frame,
             ssa,
             function_stack,
@@ -42,12 +46,12 @@ impl<'ctx> LLVMVariableGenerator<'ctx> {
         &self,
         allocation: &VariableAllocation,
         identifier: &String,
+        ty: Option<&Type>,
         expression: &'ctx Expression,
     ) {
-        let var_type = self.context.i32_type();
-
         let value = LLVMExpressionGenerator::new(
             self.context,
+            Rc::clone(&self.module),
             Rc::clone(&self.builder),
             Rc::clone(&self.stack_frame),
             Rc::clone(&self.ssa),
@@ -59,25 +63,36 @@ impl<'ctx> LLVMVariableGenerator<'ctx> {
                 Expression::Identifier(ident) => Some(ident),
                 _ => Some(identifier),
             },
-        )
-        .unwrap();
+        );
+
+        // calls of void functions leave nothing to declare
+        let Some(value) = value else {
+            return;
+        };
 
         if *allocation == VariableAllocation::Stack {
             // mutable variable declaration
 
-            let var = self
-                .builder
-                .build_alloca(var_type, identifier.as_str())
-                .unwrap();
-            self.stack_frame.borrow_mut().insert(
-                identifier.to_string(),
-                VariableData::new(var, var_type.into()),
-            );
+            let var_type = value.get_type();
+            let var = self.fn_gen.build_entry_alloca(var_type, identifier);
+            self.stack_frame
+                .borrow_mut()
+                .insert(identifier.to_string(), VariableData::new(var, var_type));
             self.builder.build_store(var, value).unwrap();
+            self.fn_gen.declare_variable(identifier, var, var_type, ty);
         } else {
             // immutable variable declaration
 
             self.ssa.borrow_mut().insert(identifier.to_string(), value);
+
+            // constants have no storage, debuggers get a copy of them,
+            // temporaries of the preprocessor are named by numbers
+            if self.fn_gen.has_debug_info() && identifier.parse::<u64>().is_err() {
+                let copy = self.fn_gen.build_entry_alloca(value.get_type(), identifier);
+                self.builder.build_store(copy, value).unwrap();
+                self.fn_gen
+                    .declare_variable(identifier, copy, value.get_type(), ty);
+            }
         }
     }
 }
@@ -0,0 +1,68 @@
+use common::constants::builtins::ENTRY_POINT;
+use inkwell::{
+    context::Context,
+    passes::PassBuilderOptions,
+    targets::{CodeModel, InitializationConfig, RelocMode, Target, TargetMachine},
+    OptimizationLevel,
+};
+use preprocessor::last::LoweredAbstractSyntaxTree;
+
+use crate::generator::module::LLVMModuleGenerator;
+
+// compiles the program in memory, runs the pass pipeline over it if one
+// is given and calls its `main`, returning the exit code
+pub fn run_main(
+    last: LoweredAbstractSyntaxTree,
+    module_name: &str,
+    opt_level: OptimizationLevel,
+    passes: Option<&str>,
+    args: &[&str],
+) -> Result<i32, String> {
+    if last.entry_point().is_none() {
+        return Err("the program has no main function".to_string());
+    }
+
+    Target::initialize_native(&InitializationConfig::default())?;
+
+    let context = Context::create();
+    let module_generator = LLVMModuleGenerator::new(&context, module_name);
+
+    // running unverified code can crash the compiler itself
+    module_generator
+        .generate_from_ast(last)
+        .map_err(|diagnostic| diagnostic.message().to_string())?;
+
+    let module = module_generator.module();
+
+    if let Some(passes) = passes {
+        let triple = TargetMachine::get_default_triple();
+        let machine = Target::from_triple(&triple)
+            .map_err(|error| error.to_string())?
+            .create_target_machine(
+                &triple,
+                &TargetMachine::get_host_cpu_name().to_string(),
+                &TargetMachine::get_host_cpu_features().to_string(),
+                opt_level,
+                RelocMode::Default,
+                CodeModel::JITDefault,
+            )
+            .ok_or_else(|| "cannot create a machine for the host".to_string())?;
+
+        module
+            .run_passes(passes, &machine, PassBuilderOptions::create())
+            .map_err(|error| format!("cannot run passes {passes}: {error}"))?;
+    }
+
+    let engine = module
+        .create_jit_execution_engine(opt_level)
+        .map_err(|error| error.to_string())?;
+
+    let main = engine
+        .get_function_value(ENTRY_POINT)
+        .map_err(|error| error.to_string())?;
+
+    // the shim only has the C signature of `main`, args are passed as argv
+    let exit_code = unsafe { engine.run_function_as_main(main, args) };
+
+    Ok(exit_code)
+}
@@ -0,0 +1,447 @@
+use inkwell::OptimizationLevel;
+use lexer::lexer::Lexer;
+use parser::Parser;
+use preprocessor::Preprocessor;
+
+fn run(code: &str) -> Result<i32, String> {
+    run_with_passes(code, None)
+}
+
+fn run_with_passes(code: &str, passes: Option<&str>) -> Result<i32, String> {
+    let tokens = Lexer::new().run(code);
+    let ast = Parser::new().run(&tokens);
+    let last = Preprocessor::new().run(ast);
+
+    ir::jit::run_main(last, "test", OptimizationLevel::None, passes, &[])
+}
+
+#[test]
+pub fn run_main_returns_exit_code() {
+    let code = r#"
+    fun main() -> i32 {
+      return 42;
+    }
+    "#;
+
+    assert_eq!(run(code), Ok(42));
+}
+
+#[test]
+pub fn run_main_loops_work() {
+    let code = r#"
+    fun main() -> i32 {
+      let i = 0;
+
+      while i < 10 {
+        i = i + 1;
+      }
+
+      return i;
+    }
+    "#;
+
+    assert_eq!(run(code), Ok(10));
+}
+
+#[test]
+pub fn run_main_control_flow_works() {
+    let code = r#"
+    fun pick(a: i32, b: i32) -> i32 {
+      if a > b {
+        return a;
+      } else {
+        return b;
+      }
+    }
+
+    fun main() -> i32 {
+      return pick(3, 7);
+    }
+    "#;
+
+    assert_eq!(run(code), Ok(7));
+}
+
+#[test]
+pub fn run_main_runs_pass_pipelines() {
+    let code = r#"
+    fun main() -> i32 {
+      let i = 0;
+
+      while i < 10 {
+        i = i + 1;
+      }
+
+      return i;
+    }
+    "#;
+
+    assert_eq!(run_with_passes(code, Some("mem2reg,instcombine")), Ok(10));
+    assert_eq!(run_with_passes(code, Some("default<O3>")), Ok(10));
+    assert!(run_with_passes(code, Some("not-a-pass"))
+        .is_err_and(|error| error.starts_with("cannot run passes not-a-pass")));
+}
+
+#[test]
+pub fn run_main_void_exits_with_zero() {
+    let code = r#"
+    fun main() {
+      let x = 1;
+    }
+    "#;
+
+    assert_eq!(run(code), Ok(0));
+}
+
+#[test]
+pub fn run_main_requires_main() {
+    let code = r#"
+    fun add(x: i32, y: i32) -> i32 {
+      return x + y;
+    }
+    "#;
+
+    assert!(run(code).is_err());
+}
+
+#[test]
+pub fn run_main_typed_variables_work() {
+    let code = r#"
+    fun main() -> i32 {
+      let big: i64 = 40;
+      let small = big as i32;
+      let i = 0;
+
+      while i < 2 {
+        let step: i64 = 1;
+        small = small + 1;
+        i = i + 1;
+      }
+
+      return small;
+    }
+    "#;
+
+    assert_eq!(run(code), Ok(42));
+}
+
+#[test]
+pub fn run_main_numeric_casts_work() {
+    let code = r#"
+    fun main() -> i32 {
+      let wrapped = 300 as u8;
+      let widened = -1 as i8 as i64;
+      let truncated = 7 as f32 as f64 as i32;
+      let back = 3 as f64 as u16;
+
+      return wrapped as i32 + widened as i32 + truncated + back as i32;
+    }
+    "#;
+
+    assert_eq!(run(code), Ok(53));
+}
+
+#[test]
+pub fn run_main_calls_functions_declared_later() {
+    let code = r#"
+    fun main() -> i32 {
+      if is_even(10) {
+        return twice(21);
+      } else {
+        return 0;
+      }
+    }
+
+    fun is_even(n: i32) -> bool {
+      if n == 0 {
+        return true;
+      } else {
+        return is_odd(n - 1);
+      }
+    }
+
+    fun is_odd(n: i32) -> bool {
+      if n == 0 {
+        return false;
+      } else {
+        return is_even(n - 1);
+      }
+    }
+
+    fun twice(n: i32) -> i32 {
+      return n * 2;
+    }
+    "#;
+
+    assert_eq!(run(code), Ok(42));
+}
+
+#[test]
+pub fn run_main_unsigned_arithmetic_works() {
+    let code = r#"
+    fun main() -> i32 {
+      let a = 250 as u8;
+      let quotient = a / 5;
+      let flags = 0;
+
+      if a > 5 {
+        flags = 1;
+      } else {
+        flags = 0;
+      }
+      if a >= (200 as u32) {
+        flags = flags + 2;
+      } else {
+        flags = flags - 2;
+      }
+
+      return quotient as i32 + flags;
+    }
+    "#;
+
+    assert_eq!(run(code), Ok(53));
+}
+
+#[test]
+pub fn run_main_float_arithmetic_works() {
+    let code = r#"
+    fun main() -> i32 {
+      let a = 7.5;
+      let b = 2.5 as f32;
+      let sum = (a + 0.5) * 2.0 - a / 2.5;
+      let half = b / 2.0;
+      let count = 0;
+
+      if sum == 13.0 {
+        count = count + 1;
+      } else {
+        count = count + 0;
+      }
+      if half < 1.5 {
+        count = count + 10;
+      } else {
+        count = count + 0;
+      }
+      if a >= 7.5 {
+        count = count + 100;
+      } else {
+        count = count + 0;
+      }
+
+      return count + sum as i32;
+    }
+    "#;
+
+    assert_eq!(run(code), Ok(124));
+}
+
+#[test]
+pub fn run_main_structs_work() {
+    let code = r#"
+    struct Point { x: i32, y: i64 }
+    struct Line { from: Point, to: Point }
+
+    fun length(line: Line) -> i32 {
+      return line.to.x - line.from.x;
+    }
+
+    fun main() -> i32 {
+      let line = Line { from: Point { x: 1, y: 2 }, to: Point { x: 5, y: 6 } };
+      line.to.x = 43;
+
+      return length(line);
+    }
+    "#;
+
+    assert_eq!(run(code), Ok(42));
+}
+
+#[test]
+pub fn run_main_enums_work() {
+    let code = r#"
+    enum Shape { Circle(i32), Rect { w: i32, h: i32 }, Empty }
+
+    fun area(s: Shape) -> i32 {
+      match s {
+        Shape::Circle(r) if r > 10 => 100,
+        Shape::Circle(r) => r * r,
+        Shape::Rect { w, h } => w * h,
+        Shape::Empty => 0
+      }
+    }
+
+    fun main() -> i32 {
+      let total = area(Shape::Circle(3)) + area(Shape::Rect { w: 2, h: 5 }) + area(Shape::Empty) + area(Shape::Circle(11));
+      let bonus = match total > 100 {
+        true => 1,
+        false => 0
+      };
+
+      return total + bonus;
+    }
+    "#;
+
+    assert_eq!(run(code), Ok(120));
+}
+
+#[test]
+pub fn run_main_arrays_work() {
+    let code = r#"
+    fun sum(xs: [i32; 4]) -> i32 {
+      let total = 0;
+      let i = 0;
+      while i < xs.len() {
+        total = total + xs[i];
+        i = i + 1;
+      }
+      return total;
+    }
+
+    fun main() -> i32 {
+      let xs = [1, 2, 3, 4];
+      let grid = [[0; 2]; 3];
+      xs[3] = 10;
+      grid[2][1] = 7;
+      return sum(xs) + grid[2][1] + grid[0][0];
+    }
+    "#;
+
+    assert_eq!(run(code), Ok(23));
+}
+
+#[test]
+pub fn run_main_slices_work() {
+    let code = r#"
+    fun sum(xs: [i32]) -> i32 {
+      let total = 0;
+      let i = 0;
+      while i < xs.len() {
+        total = total + xs[i];
+        i = i + 1;
+      }
+      return total;
+    }
+
+    fun fill(xs: [i32], value: i32) {
+      let i = 0;
+      while i < xs.len() {
+        xs[i] = value;
+        i = i + 1;
+      }
+    }
+
+    fun main() -> i32 {
+      let xs = [1, 2, 3, 4, 5];
+      let middle = xs[1..4];
+      fill(xs[3..], 10);
+      let tail = middle[1..];
+      return sum(middle) + sum(tail) * 100 + (xs[..2].len() as i32) * 1000;
+    }
+    "#;
+
+    assert_eq!(run(code), Ok(3315));
+}
+
+#[test]
+pub fn run_main_tuples_work() {
+    let code = r#"
+    fun divide(a: i32, b: i32) -> (i32, bool) {
+      match b {
+        0 => (0, false),
+        _ => (a / b, true)
+      }
+    }
+
+    fun main() -> i32 {
+      let (quotient, ok) = divide(42, 2);
+      let failed = divide(1, 0);
+      let pair = ((1, 2), 3);
+      pair.0.1 = 20;
+
+      let status = match failed {
+        (_, false) => 100,
+        (q, _) => q
+      };
+
+      return quotient + pair.0.1 + status;
+    }
+    "#;
+
+    assert_eq!(run(code), Ok(141));
+}
+
+#[test]
+pub fn run_main_pointers_work() {
+    let code = r#"
+    enum List { Cons(i32, *List), Nil }
+
+    fun sum(list: *List) -> i32 {
+      match *list {
+        List::Cons(value, next) => value + sum(next),
+        List::Nil => 0
+      }
+    }
+
+    fun main() -> i32 {
+      let x = 1;
+      let p = &x;
+      *p = *p + 9;
+
+      let pair = (1, 2);
+      let second = &pair.1;
+      *second = 20;
+
+      let nil = alloc(List::Nil);
+      let tail = alloc(List::Cons(x, nil));
+      let head = alloc(List::Cons(pair.1, tail));
+      let total = sum(head);
+
+      free(head);
+      free(tail);
+      free(nil);
+
+      return total + x;
+    }
+    "#;
+
+    assert_eq!(run(code), Ok(40));
+}
+
+#[test]
+pub fn run_main_extern_functions_work() {
+    let code = r#"
+    extern fun abs(x: i32) -> i32;
+    extern fun strlen(s: String) -> u64;
+    extern "C" fun snprintf(buffer: *u8, size: u64, format: String, ...) -> i32;
+
+    fun main() -> i32 {
+      let buffer = [0 as u8; 16];
+      let written = snprintf(&buffer[0], 16 as u64, "%d-%s", 42, "ab");
+
+      return abs(-5) + strlen("hello" + "!") as i32 + written + buffer[2] as i32;
+    }
+    "#;
+
+    assert_eq!(run(code), Ok(61));
+}
+
+#[test]
+pub fn run_main_prelude_works() {
+    let code = r#"
+    fun main() -> i32 {
+      let x = max(3, 7) - min(3, 7);
+      let r = sqrt(16 as f64);
+      let p = pow(2 as f64, 3 as f64);
+
+      assert(r == 4 as f64, "the square root of 16 is 4");
+      print("x is ");
+      println(x);
+      println(r < p);
+      println();
+
+      return x + p as i32 + r as i32 + abs(-2);
+    }
+    "#;
+
+    assert_eq!(run(code), Ok(18));
+}
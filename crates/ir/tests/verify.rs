@@ -0,0 +1,240 @@
+use inkwell::{context::Context, module::Linkage};
+use ir::generator::{
+    module::LLVMModuleGenerator,
+    runtime::{RUNTIME_INDEX_OUT_OF_BOUNDS, RUNTIME_STR_INDEX},
+};
+use lexer::lexer::Lexer;
+use parser::Parser;
+use preprocessor::Preprocessor;
+
+#[test]
+pub fn generated_module_verifies() {
+    let code = r#"
+    fun add(x: i32, y: i32) -> i32 {
+      return x + y;
+    }
+
+    fun main() -> i32 {
+      let i = 0;
+
+      while i < 3 {
+        i = add(i, 1);
+      }
+
+      if i == 3 {
+        return 0;
+      } else {
+        return 1;
+      }
+    }
+    "#;
+
+    let tokens = Lexer::new().run(code);
+    let ast = Parser::new().run(&tokens);
+    let last = Preprocessor::new().run(ast);
+
+    let context = Context::create();
+    let module_generator = LLVMModuleGenerator::new(&context, "verify");
+
+    assert_eq!(module_generator.generate_from_ast(last), Ok(()));
+    assert!(module_generator.module().verify().is_ok());
+    assert!(module_generator.module().get_function("main").is_some());
+}
+
+#[test]
+pub fn exported_functions_keep_their_names() {
+    let code = r#"
+    fun twice(x: i32) -> i32 {
+      return x * 2;
+    }
+
+    pub extern fun add(x: i32, y: i32) -> i32 {
+      return twice(x) + y;
+    }
+    "#;
+
+    let tokens = Lexer::new().run(code);
+    let ast = Parser::new().run(&tokens);
+    let last = Preprocessor::new().run(ast);
+
+    let context = Context::create();
+    let module_generator = LLVMModuleGenerator::new(&context, "exports");
+
+    assert_eq!(module_generator.generate_from_ast(last), Ok(()));
+
+    let module = module_generator.module();
+    let add = module.get_function("add").unwrap();
+
+    assert_eq!(add.get_linkage(), Linkage::External);
+    assert_eq!(add.get_call_conventions(), 0);
+    assert!(module.get_function("twice").is_none());
+    assert_eq!(
+        module.get_function("0").unwrap().get_linkage(),
+        Linkage::Internal
+    );
+}
+
+#[test]
+pub fn invalid_functions_are_reported() {
+    let code = r#"
+    fun count(s: String) -> i64 {
+      return len(s);
+    }
+    "#;
+
+    let tokens = Lexer::new().run(code);
+    let ast = Parser::new().run(&tokens);
+    let last = Preprocessor::new().run(ast);
+
+    let context = Context::create();
+    let module_generator = LLVMModuleGenerator::new(&context, "invalid");
+
+    // the string runtime is reused when already declared, this one takes the wrong argument
+    module_generator.module().add_function(
+        "bloom.str.len",
+        context
+            .i64_type()
+            .fn_type(&[context.i32_type().into()], false),
+        None,
+    );
+
+    let diagnostic = module_generator.generate_from_ast(last).unwrap_err();
+
+    assert!(diagnostic
+        .message()
+        .starts_with("invalid LLVM IR generated for function `count`"));
+    assert_eq!(diagnostic.span(), None);
+}
+
+#[test]
+pub fn debug_info_points_into_each_file() {
+    let root = "fun main() -> i32 {\n  return helper();\n}\n";
+    let imported = "\nfun helper() -> i32 {\n  return 2;\n}\n";
+
+    // the spans of an imported file come after the ones of the root file
+    let (tokens, spans) = Lexer::new()
+        .try_run_spanned(&format!("{root}\n{imported}"))
+        .unwrap();
+    let ast = Parser::new().run_spanned(&tokens, &spans);
+    let last = Preprocessor::new().run(ast);
+
+    let context = Context::create();
+    let mut module_generator = LLVMModuleGenerator::new(&context, "debug");
+    module_generator.set_debug_info("main.bloom", root);
+    module_generator.add_debug_file("lib/helper.bloom", imported, root.len() + 1);
+
+    assert_eq!(module_generator.generate_from_ast(last), Ok(()));
+
+    let ir = module_generator.module().print_to_string().to_string();
+    let helper = ir
+        .lines()
+        .find(|line| line.contains("DISubprogram(name: \"helper\""))
+        .unwrap();
+
+    assert!(ir.contains("DIFile(filename: \"helper.bloom\", directory: \"lib\")"));
+    assert!(helper.contains("line: 2,"));
+}
+
+#[test]
+pub fn debug_info_describes_bloom_types() {
+    let code = r#"struct Point { x: u8, y: i64 }
+enum List { Cons(u32, *List), Nil }
+
+fun main() -> i32 {
+  let small: u32 = 1;
+  let letter = 'a';
+  let point = Point { x: 2 as u8, y: 3 };
+  let list = alloc(List::Cons(small, alloc(List::Nil)));
+  let pair = (small, letter);
+  let name = "bloom";
+  let digits = [1, 2, 3];
+  let view = digits[1..];
+
+  return 0;
+}
+"#;
+
+    let (tokens, spans) = Lexer::new().try_run_spanned(code).unwrap();
+    let ast = Parser::new().run_spanned(&tokens, &spans);
+    let last = Preprocessor::new().run(ast);
+
+    let context = Context::create();
+    let mut module_generator = LLVMModuleGenerator::new(&context, "debug");
+    module_generator.set_debug_info("main.bloom", code);
+
+    assert_eq!(module_generator.generate_from_ast(last), Ok(()));
+
+    let ir = module_generator.module().print_to_string().to_string();
+
+    assert!(ir.contains("DIBasicType(name: \"u32\", size: 32, encoding: DW_ATE_unsigned)"));
+    assert!(ir.contains("DIBasicType(name: \"char\", size: 32, encoding: DW_ATE_UTF)"));
+    assert!(ir.contains("DICompositeType(tag: DW_TAG_structure_type, name: \"Point\""));
+    assert!(ir.contains("DICompositeType(tag: DW_TAG_union_type, name: \"List\""));
+    assert!(ir.contains("DIDerivedType(tag: DW_TAG_pointer_type, name: \"*List\""));
+    assert!(ir.contains("DICompositeType(tag: DW_TAG_structure_type, name: \"[i32]\""));
+
+    for variable in ["small", "letter", "point", "list", "pair", "name", "view"] {
+        assert!(ir.contains(&format!("DILocalVariable(name: \"{variable}\"")));
+    }
+}
+
+#[test]
+pub fn array_elements_are_read_in_place() {
+    let code = r#"
+    fun main() -> i32 {
+      let xs = [1, 2, 3, 4];
+      let i = 2;
+      return xs[i] + xs[1];
+    }
+    "#;
+
+    let tokens = Lexer::new().run(code);
+    let ast = Parser::new().run(&tokens);
+    let last = Preprocessor::new().run(ast);
+
+    let context = Context::create();
+    let module_generator = LLVMModuleGenerator::new(&context, "in_place");
+
+    assert_eq!(module_generator.generate_from_ast(last), Ok(()));
+
+    let ir = module_generator.module().print_to_string().to_string();
+
+    // only the variable has storage, the elements are not copied out of it to be read
+    assert_eq!(ir.matches("alloca [4 x i32]").count(), 1);
+}
+
+#[test]
+pub fn string_indices_report_out_of_bounds() {
+    let code = r#"
+    fun main() -> i32 {
+      let s = "abc";
+      let i = 5;
+      return s[i] as i32;
+    }
+    "#;
+
+    let tokens = Lexer::new().run(code);
+    let ast = Parser::new().run(&tokens);
+    let last = Preprocessor::new().run(ast);
+
+    let context = Context::create();
+    let module_generator = LLVMModuleGenerator::new(&context, "str_index");
+
+    assert_eq!(module_generator.generate_from_ast(last), Ok(()));
+    assert!(module_generator.module().verify().is_ok());
+
+    let ir = module_generator.module().print_to_string().to_string();
+    let index = ir
+        .split("define ")
+        .find(|function| {
+            // the signature, not a call to it
+            function
+                .lines()
+                .next()
+                .is_some_and(|signature| signature.contains(&format!("@{RUNTIME_STR_INDEX}(")))
+        })
+        .unwrap();
+
+    // the failing branch prints the index and the length like array indices do
+    assert!(index.contains(&format!("call void @{RUNTIME_INDEX_OUT_OF_BOUNDS}(")));
+}
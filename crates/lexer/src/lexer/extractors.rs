@@ -1,5 +1,6 @@
 use super::keywords::KEYWORDS;
-use common::types::Type;
+use common::{diagnostic::Diagnostic, span::Span, types::Type};
+use unicode_xid::UnicodeXID;
 
 pub fn extract_number(input: &str) -> String {
     let mut chars = input.chars().peekable();
@@ -13,11 +14,9 @@ pub fn extract_number(input: &str) -> String {
     }
 
     while let Some(char) = chars.next() {
-        if char.is_ascii_whitespace() {
-            continue;
-        } else if char.is_ascii_digit() {
-            number.push(char);
-        } else if char == '.' && chars.peek().is_some() && chars.peek().unwrap().is_ascii_digit() {
+        if char.is_ascii_digit()
+            || char == '.' && chars.peek().is_some_and(|next| next.is_ascii_digit())
+        {
             number.push(char);
         } else {
             break;
@@ -31,12 +30,37 @@ pub fn extract_number(input: &str) -> String {
     }
 }
 
+pub fn is_identifier_start(char: char) -> bool {
+    char == '_' || char.is_xid_start()
+}
+
+pub fn is_identifier_continue(char: char) -> bool {
+    char.is_xid_continue()
+}
+
+// the longest identifier-like prefix, keywords, types and
+// booleans only match when they span the whole word
+pub fn extract_word(input: &str) -> &str {
+    let mut chars = input.char_indices();
+
+    match chars.next() {
+        Some((_, char)) if is_identifier_start(char) => {}
+        _ => return "",
+    }
+
+    let end = chars
+        .find(|(_, char)| !is_identifier_continue(*char))
+        .map(|(pos, _)| pos)
+        .unwrap_or(input.len());
+
+    &input[..end]
+}
+
 pub fn extract_keyword(input: &str) -> Option<String> {
-    let split = input.split_once(' ');
-    let string = split.map(|(left, _)| left).unwrap_or(input);
+    let word = extract_word(input);
 
-    if KEYWORDS.contains(&string) {
-        Some(string.to_string())
+    if KEYWORDS.contains(&word) {
+        Some(word.to_string())
     } else {
         None
     }
@@ -48,19 +72,20 @@ const OPERATORS: &[&str] = &[
 ];
 
 pub fn extract_operator(input: &str) -> Option<String> {
-    if input.len() > 1 && OPERATORS.contains(&&input[0..2]) {
-        Some(input[0..2].to_string())
-    } else if OPERATORS.contains(&&input[0..1]) {
-        Some(input[0..1].to_string())
+    if let Some(operator) = input.get(0..2).filter(|op| OPERATORS.contains(op)) {
+        Some(operator.to_string())
     } else {
-        None
+        input
+            .get(0..1)
+            .filter(|op| OPERATORS.contains(op))
+            .map(str::to_string)
     }
 }
 
-const PUNCTUATION: &[&str] = &["{", "}", ";", "(", ")", ".", ",", ":"];
+const PUNCTUATION: &[&str] = &["{", "}", ";", "(", ")", "[", "]", ".", ",", ":"];
 
 pub fn extract_punctuation(input: &str) -> Option<char> {
-    if PUNCTUATION.contains(&&input[0..1]) {
+    if input.get(0..1).is_some_and(|p| PUNCTUATION.contains(&p)) {
         Some(input.as_bytes()[0] as char)
     } else {
         None
@@ -83,41 +108,197 @@ pub fn extract_comment(input: &str) -> Option<String> {
     }
 }
 
-pub fn extract_string(input: &str) -> String {
-    let string_end = &input[1..].find('"').map(|idx| idx + 1);
-    input[1..string_end.unwrap_or(input.len())].to_string()
+// returns the unescaped string and the length of the literal in the source
+pub fn extract_string(input: &str) -> Result<(String, usize), Diagnostic> {
+    let mut string = String::new();
+    // opening quote
+    let mut pos = 1;
+
+    while let Some(char) = input[pos..].chars().next() {
+        match char {
+            '"' => return Ok((string, pos + 1)),
+            '\\' => {
+                let (escaped, len) =
+                    extract_escape(&input[pos..]).map_err(|diagnostic| diagnostic.offset(pos))?;
+
+                if let Some(escaped) = escaped {
+                    string.push(escaped);
+                }
+
+                pos += len;
+            }
+            other => {
+                string.push(other);
+                pos += other.len_utf8();
+            }
+        }
+    }
+
+    Err(Diagnostic::new(
+        "unterminated string literal".to_string(),
+        Some(Span::new(0, input.len())),
+    ))
 }
 
-pub fn extract_type(input: &str) -> Option<(Type, usize)> {
-    let input = &input[..input
-        .chars()
-        .position(|c| !c.is_ascii_alphanumeric())
-        .unwrap_or(input.len())];
+// returns the character and the length of the literal in the source
+pub fn extract_char(input: &str) -> Result<(char, usize), Diagnostic> {
+    let mut chars = vec![];
+    let mut terminated = false;
+    // opening quote
+    let mut pos = 1;
+
+    while let Some(char) = input[pos..].chars().next() {
+        match char {
+            '\'' => {
+                pos += 1;
+                terminated = true;
+                break;
+            }
+            '\n' => {
+                return Err(Diagnostic::new(
+                    "unterminated character literal".to_string(),
+                    Some(Span::new(0, pos)),
+                ))
+            }
+            '\\' => {
+                let (escaped, len) =
+                    extract_escape(&input[pos..]).map_err(|diagnostic| diagnostic.offset(pos))?;
 
-    Type::from(input).map(|t| (t, input.len()))
+                chars.extend(escaped);
+                pos += len;
+            }
+            other => {
+                chars.push(other);
+                pos += other.len_utf8();
+            }
+        }
+    }
+
+    if !terminated {
+        return Err(Diagnostic::new(
+            "unterminated character literal".to_string(),
+            Some(Span::new(0, pos)),
+        ));
+    }
+
+    match chars[..] {
+        [char] => Ok((char, pos)),
+        [] => Err(Diagnostic::new(
+            "empty character literal".to_string(),
+            Some(Span::new(0, pos)),
+        )),
+        _ => Err(Diagnostic::new(
+            "character literal must contain exactly one character".to_string(),
+            Some(Span::new(0, pos)),
+        )),
+    }
 }
 
-pub fn extract_identifier(input: &str) -> String {
-    let mut ident = String::new();
-    let mut chars = input.chars().peekable();
+pub fn is_raw_string(input: &str) -> bool {
+    input.starts_with('r') && input[1..].trim_start_matches('#').starts_with('"')
+}
 
-    while let Some(char) = chars.next() {
-        if !char.is_ascii_alphanumeric() {
-            break;
+// r"..." or r#"..."#, with any number of hashes
+pub fn extract_raw_string(input: &str) -> Result<(String, usize), Diagnostic> {
+    let hashes = input[1..].chars().take_while(|&char| char == '#').count();
+    // "r" + hashes + quote
+    let opening = hashes + 2;
+    let closing = format!("\"{}", "#".repeat(hashes));
+
+    match input[opening..].find(&closing) {
+        Some(end) => Ok((
+            input[opening..(opening + end)].to_string(),
+            opening + end + closing.len(),
+        )),
+        None => Err(Diagnostic::new(
+            "unterminated raw string literal".to_string(),
+            Some(Span::new(0, input.len())),
+        )),
+    }
+}
+
+// input starts at the backslash, returns the escaped character
+// (none for a line continuation) and the length of the escape sequence
+pub fn extract_escape(input: &str) -> Result<(Option<char>, usize), Diagnostic> {
+    let escaped = match input[1..].chars().next() {
+        Some('n') => '\n',
+        Some('t') => '\t',
+        Some('r') => '\r',
+        Some('0') => '\0',
+        Some('\\') => '\\',
+        Some('"') => '"',
+        Some('\'') => '\'',
+        Some('u') => return extract_unicode_escape(input).map(|(char, len)| (Some(char), len)),
+        Some('\n') => {
+            // line continuation, skip the newline and the indentation of the next line
+            let indentation = input[2..]
+                .chars()
+                .take_while(|char| char.is_whitespace())
+                .map(char::len_utf8)
+                .sum::<usize>();
+
+            return Ok((None, 2 + indentation));
+        }
+        Some(other) => {
+            return Err(Diagnostic::new(
+                format!("invalid escape sequence: \\{other}"),
+                Some(Span::new(0, 1 + other.len_utf8())),
+            ))
+        }
+        None => {
+            return Err(Diagnostic::new(
+                "unterminated escape sequence".to_string(),
+                Some(Span::new(0, 1)),
+            ))
         }
+    };
+
+    Ok((Some(escaped), 2))
+}
+
+// \u{1F600}
+fn extract_unicode_escape(input: &str) -> Result<(char, usize), Diagnostic> {
+    let invalid = |len: usize| {
+        Diagnostic::new(
+            format!("invalid unicode escape: {}", &input[..len]),
+            Some(Span::new(0, len)),
+        )
+    };
 
-        ident.push(char);
+    if !input[2..].starts_with('{') {
+        return Err(invalid(2));
     }
 
-    ident
+    let Some(closing) = input.find('}') else {
+        return Err(invalid(input.len()));
+    };
+
+    let digits = &input[3..closing];
+
+    if digits.is_empty() || digits.len() > 6 {
+        return Err(invalid(closing + 1));
+    }
+
+    u32::from_str_radix(digits, 16)
+        .ok()
+        .and_then(char::from_u32)
+        .map(|char| (char, closing + 1))
+        .ok_or_else(|| invalid(closing + 1))
+}
+
+pub fn extract_type(input: &str) -> Option<(Type, usize)> {
+    let word = extract_word(input);
+
+    Type::from(word).map(|t| (t, word.len()))
+}
+
+pub fn extract_identifier(input: &str) -> String {
+    extract_word(input).to_string()
 }
 
 pub fn extract_boolean(input: &str) -> Option<String> {
-    if input.starts_with("true") {
-        Some("true".to_string())
-    } else if input.starts_with("false") {
-        Some("false".to_string())
-    } else {
-        None
+    match extract_word(input) {
+        word @ ("true" | "false") => Some(word.to_string()),
+        _ => None,
     }
 }
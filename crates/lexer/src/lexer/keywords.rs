@@ -1,14 +1,23 @@
 use common::constants::keywords::{
-    DECLARATION_CONSTANT, DECLARATION_FUNCTION, DECLARATION_VARIABLE, STATEMENT_ELSE, STATEMENT_IF,
-    STATEMENT_RETURN, STATEMENT_WHILE,
+    DECLARATION_CONSTANT, DECLARATION_ENUM, DECLARATION_EXTERN, DECLARATION_FUNCTION,
+    DECLARATION_IMPORT, DECLARATION_PUBLIC, DECLARATION_STRUCT, DECLARATION_VARIABLE,
+    EXPRESSION_CAST, STATEMENT_ELSE, STATEMENT_IF, STATEMENT_MATCH, STATEMENT_RETURN,
+    STATEMENT_WHILE,
 };
 
 pub const KEYWORDS: &[&str] = &[
     DECLARATION_CONSTANT,
     DECLARATION_FUNCTION,
     DECLARATION_VARIABLE,
+    DECLARATION_STRUCT,
+    DECLARATION_ENUM,
+    DECLARATION_EXTERN,
+    DECLARATION_PUBLIC,
+    DECLARATION_IMPORT,
     STATEMENT_IF,
     STATEMENT_ELSE,
     STATEMENT_WHILE,
+    STATEMENT_MATCH,
     STATEMENT_RETURN,
+    EXPRESSION_CAST,
 ];
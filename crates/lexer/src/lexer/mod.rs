@@ -1,104 +1,184 @@
 pub mod extractors;
 pub mod keywords;
 pub mod token;
+pub mod trivia;
 
-use std::mem;
-
+use common::{diagnostic::Diagnostic, span::Span};
 use token::{Literal, Token};
+use trivia::LosslessToken;
 
 pub struct Lexer {}
 
+impl Default for Lexer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Lexer {
     pub fn new() -> Self {
         Self {}
     }
 
     pub fn run(&self, input: &str) -> Vec<Token> {
-        let bytes = input.as_bytes();
+        self.try_run(input)
+            .unwrap_or_else(|diagnostic| panic!("{diagnostic}"))
+    }
+
+    pub fn try_run(&self, input: &str) -> Result<Vec<Token>, Diagnostic> {
+        self.try_run_spanned(input).map(|(tokens, _)| tokens)
+    }
+
+    // the tokens of `try_run` and the span of each one of them
+    pub fn try_run_spanned(&self, input: &str) -> Result<(Vec<Token>, Vec<Span>), Diagnostic> {
+        let (mut tokens, mut spans): (Vec<Token>, Vec<Span>) =
+            self.tokenize(input)?.into_iter().unzip();
+
+        tokens.push(Token::EOF);
+        spans.push(Span::new(input.len(), input.len()));
+
+        Ok((tokens, spans))
+    }
+
+    pub fn run_lossless(&self, input: &str) -> Vec<LosslessToken> {
+        self.try_run_lossless(input)
+            .unwrap_or_else(|diagnostic| panic!("{diagnostic}"))
+    }
+
+    // keeps whitespace and comments as trivia, so that
+    // the tokens can be joined back into the exact source
+    pub fn try_run_lossless(&self, input: &str) -> Result<Vec<LosslessToken>, Diagnostic> {
+        let tokens = self.tokenize(input)?;
+
+        Ok(trivia::attach_trivia(input, tokens))
+    }
+
+    fn tokenize(&self, input: &str) -> Result<Vec<(Token, Span)>, Diagnostic> {
         let mut tokens = Vec::new();
         let mut pos = 0;
 
-        while pos < bytes.len() {
-            let char = bytes[pos] as char;
+        while let Some(char) = input[pos..].chars().next() {
+            let start = pos;
 
             if char.is_whitespace() {
-                pos += 1;
-            } else if char == '\n' {
-                pos += 1;
+                pos += char.len_utf8();
             } else if char.is_ascii_digit() {
-                let number = extractors::extract_number(&input[pos..]);
+                let mut number = extractors::extract_number(&input[pos..]);
+                // `t.0.1` accesses elements, a point after an element index is not a decimal one
+                if tokens.last().map(|(token, _)| token) == Some(&Token::Punctuation('.')) {
+                    number.truncate(number.find('.').unwrap_or(number.len()));
+                }
                 pos += number.len();
-                tokens.push(Token::Literal(Literal::Number(number)));
+                check_number(&number, Span::new(start, pos))?;
+                tokens.push((
+                    Token::Literal(Literal::Number(number)),
+                    Span::new(start, pos),
+                ));
             } else if char == '"' {
-                let string = extractors::extract_string(&input[pos..]);
-                pos += string.len() + 2;
-                tokens.push(Token::Literal(Literal::String(string)));
-            } else if char.is_ascii() {
-                let keyword = extractors::extract_keyword(&input[pos..]);
-                if let Some(keyword) = keyword {
-                    pos += keyword.len();
-                    tokens.push(Token::Keyword(keyword));
-                    continue;
-                }
-
+                let (string, len) = extractors::extract_string(&input[pos..])
+                    .map_err(|diagnostic| diagnostic.offset(pos))?;
+                pos += len;
+                tokens.push((
+                    Token::Literal(Literal::String(string)),
+                    Span::new(start, pos),
+                ));
+            } else if char == '\'' {
+                let (char, len) = extractors::extract_char(&input[pos..])
+                    .map_err(|diagnostic| diagnostic.offset(pos))?;
+                pos += len;
+                tokens.push((Token::Literal(Literal::Char(char)), Span::new(start, pos)));
+            } else if extractors::is_raw_string(&input[pos..]) {
+                let (string, len) = extractors::extract_raw_string(&input[pos..])
+                    .map_err(|diagnostic| diagnostic.offset(pos))?;
+                pos += len;
+                tokens.push((
+                    Token::Literal(Literal::String(string)),
+                    Span::new(start, pos),
+                ));
+            } else if extractors::is_identifier_start(char) {
+                let word = extractors::extract_word(&input[pos..]);
+                pos += word.len();
+
+                let token = if let Some(keyword) = extractors::extract_keyword(word) {
+                    Token::Keyword(keyword)
+                } else if let Some((extracted_type, _)) = extractors::extract_type(word) {
+                    Token::Type(extracted_type)
+                } else if let Some(boolean) = extractors::extract_boolean(word) {
+                    Token::Literal(Literal::Boolean(boolean))
+                } else {
+                    Token::Identifier(word.to_string())
+                };
+
+                tokens.push((token, Span::new(start, pos)));
+            } else {
                 let comment = extractors::extract_comment(&input[pos..]);
                 if let Some(comment) = comment {
                     pos += comment.len();
-                    tokens.push(Token::Comment(comment));
+                    tokens.push((Token::Comment(comment), Span::new(start, pos)));
                     continue;
                 }
 
                 let operator = extractors::extract_operator(&input[pos..]);
                 if let Some(operator) = operator {
+                    // a minus after an operand subtracts, anywhere else it negates a number
                     if operator == "-"
-                        && tokens.last().is_some()
-                        && mem::discriminant(tokens.last().unwrap())
-                            != mem::discriminant(&Token::Literal(Literal::Number("".to_string())))
-                        && mem::discriminant(tokens.last().unwrap())
-                            != mem::discriminant(&Token::Identifier("".to_string()))
+                        && tokens.last().is_some_and(|(token, _)| !ends_operand(token))
                     {
                         let number = extractors::extract_number(&input[pos..]);
-                        if number.len() != 0 {
+                        if !number.is_empty() {
                             pos += number.len();
-                            tokens.push(Token::Literal(Literal::Number(number)));
+                            check_number(&number, Span::new(start, pos))?;
+                            tokens.push((
+                                Token::Literal(Literal::Number(number)),
+                                Span::new(start, pos),
+                            ));
                             continue;
                         }
                     }
 
                     pos += operator.len();
-                    tokens.push(Token::Operator(operator));
+                    tokens.push((Token::Operator(operator), Span::new(start, pos)));
                     continue;
                 }
 
                 let punctuation = extractors::extract_punctuation(&input[pos..]);
                 if let Some(punctuation) = punctuation {
                     pos += 1;
-                    tokens.push(Token::Punctuation(punctuation));
-                    continue;
-                }
-
-                let extracted_type = extractors::extract_type(&input[pos..]);
-                if let Some((extracted_type, len)) = extracted_type {
-                    pos += len;
-                    tokens.push(Token::Type(extracted_type));
+                    tokens.push((Token::Punctuation(punctuation), Span::new(start, pos)));
                     continue;
                 }
 
-                let boolean = extractors::extract_boolean(&input[pos..]);
-                if let Some(boolean) = boolean {
-                    pos += boolean.len();
-                    tokens.push(Token::Literal(Literal::Boolean(boolean)));
-                    continue;
-                }
-
-                let identifier = extractors::extract_identifier(&input[pos..]);
-                pos += identifier.len();
-                tokens.push(Token::Identifier(identifier));
+                return Err(Diagnostic::new(
+                    format!("unexpected character: {char:?}"),
+                    Some(Span::new(pos, pos + char.len_utf8())),
+                ));
             }
         }
 
-        tokens.push(Token::EOF);
+        Ok(tokens)
+    }
+}
+
+fn ends_operand(token: &Token) -> bool {
+    matches!(
+        token,
+        Token::Literal(_) | Token::Identifier(_) | Token::Type(_) | Token::Punctuation(')' | ']')
+    )
+}
 
-        tokens
+// numbers with a point are f64 literals, the others integers of up to 64 bits,
+// whether they fit their type is checked once it is known
+fn check_number(number: &str, span: Span) -> Result<(), Diagnostic> {
+    let valid = match number.contains('.') {
+        true => number.parse::<f64>().is_ok(),
+        false => number.parse::<i64>().is_ok() || number.parse::<u64>().is_ok(),
+    };
+
+    match valid {
+        true => Ok(()),
+        false => Err(Diagnostic::new(
+            format!("invalid number literal `{number}`"),
+            Some(span),
+        )),
     }
 }
@@ -1,6 +1,10 @@
-use common::types::Type;
+use common::{
+    dump::{TreeDump, TreeWriter},
+    types::Type,
+};
 
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum Token {
     Keyword(String),
     Identifier(String),
@@ -64,8 +68,28 @@ impl Token {
 }
 
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum Literal {
     String(String),
+    Char(char),
     Number(String),
     Boolean(String),
 }
+
+impl TreeDump for Token {
+    fn dump(&self, writer: &mut TreeWriter) {
+        match self {
+            Self::Keyword(keyword) => writer.leaf(format!("Keyword {keyword}")),
+            Self::Identifier(identifier) => writer.leaf(format!("Identifier {identifier}")),
+            Self::Type(ty) => writer.leaf(format!("Type {ty}")),
+            Self::Literal(Literal::String(string)) => writer.leaf(format!("String {string:?}")),
+            Self::Literal(Literal::Char(char)) => writer.leaf(format!("Char {char:?}")),
+            Self::Literal(Literal::Number(number)) => writer.leaf(format!("Number {number}")),
+            Self::Literal(Literal::Boolean(boolean)) => writer.leaf(format!("Boolean {boolean}")),
+            Self::Operator(operator) => writer.leaf(format!("Operator {operator}")),
+            Self::Punctuation(punctuation) => writer.leaf(format!("Punctuation {punctuation}")),
+            Self::Comment(comment) => writer.leaf(format!("Comment {comment:?}")),
+            Self::EOF => writer.leaf("EOF"),
+        }
+    }
+}
@@ -0,0 +1,176 @@
+use std::mem;
+
+use common::span::Span;
+
+use super::token::Token;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum TriviaKind {
+    Whitespace,
+    Comment,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Trivia {
+    kind: TriviaKind,
+    text: String,
+    span: Span,
+}
+
+impl Trivia {
+    pub fn new(kind: TriviaKind, text: String, span: Span) -> Self {
+        Self { kind, text, span }
+    }
+
+    pub fn kind(&self) -> TriviaKind {
+        self.kind
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn span(&self) -> Span {
+        self.span
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct LosslessToken {
+    token: Token,
+    text: String,
+    span: Span,
+    leading_trivia: Vec<Trivia>,
+    trailing_trivia: Vec<Trivia>,
+}
+
+impl LosslessToken {
+    pub fn new(
+        token: Token,
+        text: String,
+        span: Span,
+        leading_trivia: Vec<Trivia>,
+        trailing_trivia: Vec<Trivia>,
+    ) -> Self {
+        Self {
+            token,
+            text,
+            span,
+            leading_trivia,
+            trailing_trivia,
+        }
+    }
+
+    pub fn token(&self) -> &Token {
+        &self.token
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    pub fn leading_trivia(&self) -> &[Trivia] {
+        &self.leading_trivia
+    }
+
+    pub fn trailing_trivia(&self) -> &[Trivia] {
+        &self.trailing_trivia
+    }
+
+    // the source text of the token together with its trivia
+    pub fn full_text(&self) -> String {
+        self.leading_trivia
+            .iter()
+            .map(Trivia::text)
+            .chain([self.text()])
+            .chain(self.trailing_trivia.iter().map(Trivia::text))
+            .collect()
+    }
+}
+
+// trivia on the same line as a token trails it, everything
+// from the next newline on leads the following token
+pub fn attach_trivia(input: &str, tokens: Vec<(Token, Span)>) -> Vec<LosslessToken> {
+    let mut pending: Vec<Trivia> = vec![];
+    let mut lossless: Vec<LosslessToken> = vec![];
+    let mut pos = 0;
+
+    for (token, span) in tokens {
+        let mut trivia = whitespace_trivia(input, pos, span.start);
+
+        if let Token::Comment(_) = token {
+            trivia.push(Trivia::new(
+                TriviaKind::Comment,
+                input[span.start..span.end].to_string(),
+                span,
+            ));
+        }
+
+        pos = span.end;
+
+        for trivia in trivia {
+            push_trivia(&mut lossless, &mut pending, trivia);
+        }
+
+        if let Token::Comment(_) = token {
+            continue;
+        }
+
+        lossless.push(LosslessToken::new(
+            token,
+            input[span.start..span.end].to_string(),
+            span,
+            mem::take(&mut pending),
+            vec![],
+        ));
+    }
+
+    for trivia in whitespace_trivia(input, pos, input.len()) {
+        push_trivia(&mut lossless, &mut pending, trivia);
+    }
+
+    lossless.push(LosslessToken::new(
+        Token::EOF,
+        String::new(),
+        Span::new(input.len(), input.len()),
+        pending,
+        vec![],
+    ));
+
+    lossless
+}
+
+fn push_trivia(lossless: &mut [LosslessToken], pending: &mut Vec<Trivia>, trivia: Trivia) {
+    match lossless.last_mut() {
+        Some(token) if pending.is_empty() && !trivia.text.contains('\n') => {
+            token.trailing_trivia.push(trivia)
+        }
+        _ => pending.push(trivia),
+    }
+}
+
+// splits the gap between two tokens at its first newline
+fn whitespace_trivia(input: &str, start: usize, end: usize) -> Vec<Trivia> {
+    let gap = &input[start..end];
+
+    let split = match gap.find('\n') {
+        Some(0) | None => vec![(start, end)],
+        Some(newline) => vec![(start, start + newline), (start + newline, end)],
+    };
+
+    split
+        .into_iter()
+        .filter(|(start, end)| start != end)
+        .map(|(start, end)| {
+            Trivia::new(
+                TriviaKind::Whitespace,
+                input[start..end].to_string(),
+                Span::new(start, end),
+            )
+        })
+        .collect()
+}
@@ -0,0 +1,158 @@
+use common::{span::Span, types::Type};
+use lexer::lexer::{
+    extractors,
+    token::{Literal, Token},
+    Lexer,
+};
+
+fn keyword(value: &str) -> Token {
+    Token::Keyword(value.to_string())
+}
+
+fn identifier(value: &str) -> Token {
+    Token::Identifier(value.to_string())
+}
+
+#[test]
+pub fn keywords_end_at_word_boundaries() {
+    assert_eq!(extractors::extract_keyword("if(x)"), Some("if".to_string()));
+    assert_eq!(
+        extractors::extract_keyword("return;"),
+        Some("return".to_string())
+    );
+    assert_eq!(
+        extractors::extract_keyword("while\n"),
+        Some("while".to_string())
+    );
+    assert_eq!(
+        extractors::extract_keyword("else{"),
+        Some("else".to_string())
+    );
+    assert_eq!(extractors::extract_keyword("let"), Some("let".to_string()));
+
+    assert_eq!(extractors::extract_keyword("iffy"), None);
+    assert_eq!(extractors::extract_keyword("returned"), None);
+    assert_eq!(extractors::extract_keyword("let_"), None);
+    assert_eq!(extractors::extract_keyword("_if"), None);
+}
+
+#[test]
+pub fn booleans_and_types_end_at_word_boundaries() {
+    assert_eq!(
+        extractors::extract_boolean("true;"),
+        Some("true".to_string())
+    );
+    assert_eq!(
+        extractors::extract_boolean("false)"),
+        Some("false".to_string())
+    );
+    assert_eq!(extractors::extract_boolean("trueish"), None);
+    assert_eq!(extractors::extract_boolean("false_"), None);
+
+    assert_eq!(extractors::extract_type("i32)"), Some((Type::Int32, 3)));
+    assert_eq!(extractors::extract_type("u8,"), Some((Type::UInt8, 2)));
+    assert_eq!(extractors::extract_type("i32x"), None);
+    assert_eq!(extractors::extract_type("boolean"), None);
+}
+
+#[test]
+pub fn identifiers_follow_unicode_xid() {
+    assert_eq!(extractors::extract_identifier("_"), "_");
+    assert_eq!(
+        extractors::extract_identifier("snake_case = 1"),
+        "snake_case"
+    );
+    assert_eq!(extractors::extract_identifier("_private;"), "_private");
+    assert_eq!(extractors::extract_identifier("x1_y2+"), "x1_y2");
+    assert_eq!(extractors::extract_identifier("größe = 1"), "größe");
+    assert_eq!(extractors::extract_identifier("переменная;"), "переменная");
+    assert_eq!(extractors::extract_identifier("变量)"), "变量");
+
+    assert_eq!(extractors::extract_identifier("1abc"), "");
+    assert_eq!(extractors::extract_identifier("-abc"), "");
+}
+
+#[test]
+pub fn lexer_keywords_without_spaces_work() {
+    let code = "if(x){return;}else{while(y){}}";
+
+    let tokens = Lexer::new().run(code);
+
+    assert_eq!(tokens[0], keyword("if"));
+    assert_eq!(tokens[1], Token::Punctuation('('));
+    assert_eq!(tokens[2], identifier("x"));
+    assert_eq!(tokens[5], keyword("return"));
+    assert_eq!(tokens[6], Token::Punctuation(';'));
+    assert_eq!(tokens[8], keyword("else"));
+    assert_eq!(tokens[10], keyword("while"));
+
+    let code = "return\nx";
+
+    let tokens = Lexer::new().run(code);
+
+    assert_eq!(tokens[0], keyword("return"));
+    assert_eq!(tokens[1], identifier("x"));
+}
+
+#[test]
+pub fn lexer_keyword_prefixed_identifiers_work() {
+    let code = "let iffy = returned + letter + trueish + i32x + whilst;";
+
+    let tokens = Lexer::new().run(code);
+
+    assert_eq!(tokens[0], keyword("let"));
+    assert_eq!(tokens[1], identifier("iffy"));
+    assert_eq!(tokens[3], identifier("returned"));
+    assert_eq!(tokens[5], identifier("letter"));
+    assert_eq!(tokens[7], identifier("trueish"));
+    assert_eq!(tokens[9], identifier("i32x"));
+    assert_eq!(tokens[11], identifier("whilst"));
+    assert_eq!(tokens[12], Token::Punctuation(';'));
+}
+
+#[test]
+pub fn lexer_booleans_and_types_work() {
+    let code = "fun f(a:i32,b:bool) { true&&false }";
+
+    let tokens = Lexer::new().run(code);
+
+    assert_eq!(tokens[5], Token::Type(Type::Int32));
+    assert_eq!(tokens[9], Token::Type(Type::Bool));
+    assert_eq!(
+        tokens[12],
+        Token::Literal(Literal::Boolean("true".to_string()))
+    );
+    assert_eq!(tokens[13], Token::Operator("&&".to_string()));
+    assert_eq!(
+        tokens[14],
+        Token::Literal(Literal::Boolean("false".to_string()))
+    );
+}
+
+#[test]
+pub fn lexer_unicode_identifiers_work() {
+    let code = "let _ = 1;\nlet größe_2 = _;\nlet 变量 = größe_2;";
+
+    let tokens = Lexer::new().run(code);
+
+    assert_eq!(tokens[1], identifier("_"));
+    assert_eq!(tokens[6], identifier("größe_2"));
+    assert_eq!(tokens[8], identifier("_"));
+    assert_eq!(tokens[11], identifier("变量"));
+    assert_eq!(tokens[13], identifier("größe_2"));
+    assert_eq!(tokens.last().unwrap(), &Token::EOF);
+}
+
+#[test]
+pub fn lexer_unexpected_characters_fail() {
+    let code = "let a = 1;\nlet b = a @ 2;";
+
+    let diagnostic = Lexer::new().try_run(code).unwrap_err();
+    assert_eq!(diagnostic.message(), "unexpected character: '@'");
+    assert_eq!(diagnostic.span(), Some(Span::new(21, 22)));
+
+    let code = "let a = 1 + \u{1F600};";
+
+    let diagnostic = Lexer::new().try_run(code).unwrap_err();
+    assert_eq!(diagnostic.span(), Some(Span::new(12, 16)));
+}
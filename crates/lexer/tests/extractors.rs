@@ -1,4 +1,4 @@
-use common::types::Type;
+use common::{span::Span, types::Type};
 use lexer::lexer::extractors;
 
 #[test]
@@ -88,8 +88,99 @@ pub fn comment_extrator_works() {
 pub fn string_extractor_works() {
     let string = r#"let a = "hello, world";"#;
 
-    let s1 = extractors::extract_string(&string[8..]);
+    let (s1, len) = extractors::extract_string(&string[8..]).unwrap();
     assert_eq!(&s1, "hello, world");
+    assert_eq!(len, 14);
+}
+
+#[test]
+pub fn string_extractor_escapes_work() {
+    let string = r#""say \"hi\"\n\t\\ \0 \u{1F600}""#;
+
+    let (s1, len) = extractors::extract_string(string).unwrap();
+    assert_eq!(&s1, "say \"hi\"\n\t\\ \0 \u{1F600}");
+    assert_eq!(len, string.len());
+
+    let string = "\"multi\nline \\\n    continued\"";
+
+    let (s2, _) = extractors::extract_string(string).unwrap();
+    assert_eq!(&s2, "multi\nline continued");
+}
+
+#[test]
+pub fn string_extractor_errors_work() {
+    let unterminated = extractors::extract_string(r#""hello"#);
+    assert!(unterminated.is_err());
+    assert_eq!(unterminated.unwrap_err().span(), Some(Span::new(0, 6)));
+
+    let invalid_escape = extractors::extract_string(r#""a\qb""#);
+    assert!(invalid_escape.is_err());
+    assert_eq!(invalid_escape.unwrap_err().span(), Some(Span::new(2, 4)));
+
+    let invalid_unicode = extractors::extract_string(r#""\u{110000}""#);
+    assert!(invalid_unicode.is_err());
+}
+
+#[test]
+pub fn raw_string_extractor_works() {
+    let string = r##"r"C:\path\n" + 1"##;
+
+    assert!(extractors::is_raw_string(string));
+    let (s1, len) = extractors::extract_raw_string(string).unwrap();
+    assert_eq!(&s1, r"C:\path\n");
+    assert_eq!(len, 12);
+
+    let string = r###"r#"say "hi""#;"###;
+
+    let (s2, len) = extractors::extract_raw_string(string).unwrap();
+    assert_eq!(&s2, r#"say "hi""#);
+    assert_eq!(len, string.len() - 1);
+
+    assert!(!extractors::is_raw_string("return"));
+    assert!(extractors::extract_raw_string(r##"r#"open"##).is_err());
+}
+
+#[test]
+pub fn char_extractor_works() {
+    let (c1, len) = extractors::extract_char("'a' as u8").unwrap();
+    assert_eq!(c1, 'a');
+    assert_eq!(len, 3);
+
+    let (c2, len) = extractors::extract_char(r"'\n'").unwrap();
+    assert_eq!(c2, '\n');
+    assert_eq!(len, 4);
+
+    let (c3, _) = extractors::extract_char(r"'\''").unwrap();
+    assert_eq!(c3, '\'');
+
+    let (c4, len) = extractors::extract_char(r"'\u{1F600}'").unwrap();
+    assert_eq!(c4, '\u{1F600}');
+    assert_eq!(len, 11);
+
+    let (c5, len) = extractors::extract_char("'é';").unwrap();
+    assert_eq!(c5, 'é');
+    assert_eq!(len, 4);
+}
+
+#[test]
+pub fn char_extractor_errors_work() {
+    let empty = extractors::extract_char("''");
+    assert_eq!(empty.unwrap_err().message(), "empty character literal");
+
+    let too_long = extractors::extract_char("'ab'");
+    assert_eq!(
+        too_long.unwrap_err().message(),
+        "character literal must contain exactly one character"
+    );
+
+    let unterminated = extractors::extract_char(r"'\'");
+    assert_eq!(
+        unterminated.unwrap_err().message(),
+        "unterminated character literal"
+    );
+
+    let newline = extractors::extract_char("'a\n';");
+    assert_eq!(newline.unwrap_err().span(), Some(Span::new(0, 2)));
 }
 
 #[test]
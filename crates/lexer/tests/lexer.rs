@@ -1,3 +1,4 @@
+use common::{span::Span, types::Type};
 use lexer::lexer::{
     token::{Literal, Token},
     Lexer,
@@ -48,3 +49,91 @@ let x = true;
     );
     assert_eq!(tokens.last().unwrap(), &Token::EOF);
 }
+
+#[test]
+pub fn lexer_strings_work() {
+    let code = r##"let a = "line\n\"quoted\"";
+let b = r#"raw "string""#;
+"##;
+
+    let tokens = Lexer::new().run(code);
+
+    assert_eq!(
+        tokens[3],
+        Token::Literal(Literal::String("line\n\"quoted\"".to_string()))
+    );
+    assert_eq!(tokens[4], Token::Punctuation(';'));
+    assert_eq!(
+        tokens[8],
+        Token::Literal(Literal::String("raw \"string\"".to_string()))
+    );
+    assert_eq!(tokens[9], Token::Punctuation(';'));
+}
+
+#[test]
+pub fn lexer_string_diagnostics_work() {
+    let code = "let a = \"hello;\nlet b = 1;";
+
+    let diagnostic = Lexer::new().try_run(code).unwrap_err();
+    assert_eq!(diagnostic.message(), "unterminated string literal");
+    assert_eq!(diagnostic.span(), Some(Span::new(8, code.len())));
+
+    let code = "let a = 1;\nlet b = \"\\x\";";
+
+    let diagnostic = Lexer::new().try_run(code).unwrap_err();
+    assert_eq!(diagnostic.span().unwrap().line_col(code), (2, 10));
+}
+
+#[test]
+pub fn lexer_chars_work() {
+    let code = r"let c = '\t' as u32;";
+
+    let tokens = Lexer::new().run(code);
+
+    assert_eq!(tokens[3], Token::Literal(Literal::Char('\t')));
+    assert_eq!(tokens[4], Token::Keyword("as".to_string()));
+    assert_eq!(tokens[5], Token::Type(Type::UInt32));
+    assert_eq!(tokens[6], Token::Punctuation(';'));
+
+    let diagnostic = Lexer::new().try_run("let c = 'ab';").unwrap_err();
+    assert_eq!(diagnostic.span(), Some(Span::new(8, 12)));
+}
+
+#[test]
+pub fn lexer_number_diagnostics_work() {
+    let code = "let a = 1.5.2;";
+
+    let diagnostic = Lexer::new().try_run(code).unwrap_err();
+    assert_eq!(diagnostic.message(), "invalid number literal `1.5.2`");
+    assert_eq!(diagnostic.span(), Some(Span::new(8, 13)));
+
+    let code = "let a = 1;\nlet b = -9223372036854775809;";
+
+    let diagnostic = Lexer::new().try_run(code).unwrap_err();
+    assert_eq!(
+        diagnostic.message(),
+        "invalid number literal `-9223372036854775809`"
+    );
+    assert_eq!(diagnostic.span().unwrap().line_col(code), (2, 9));
+
+    let code = "let a = 3000000000 + 18446744073709551615;";
+
+    assert!(Lexer::new().try_run(code).is_ok());
+    assert!(Lexer::new()
+        .try_run("let a = 18446744073709551616;")
+        .is_err());
+}
+
+#[test]
+pub fn lexer_minus_after_operands_works() {
+    let tokens = Lexer::new().run("xs[0] -1 + f(x) -2 + -3");
+
+    assert_eq!(tokens[4], Token::Operator("-".to_string()));
+    assert_eq!(tokens[5], Token::Literal(Literal::Number("1".to_string())));
+    assert_eq!(tokens[11], Token::Operator("-".to_string()));
+    assert_eq!(tokens[12], Token::Literal(Literal::Number("2".to_string())));
+    assert_eq!(
+        tokens[14],
+        Token::Literal(Literal::Number("-3".to_string()))
+    );
+}
@@ -0,0 +1,79 @@
+use common::span::Span;
+use lexer::lexer::{
+    token::Token,
+    trivia::{Trivia, TriviaKind},
+    Lexer,
+};
+
+#[test]
+pub fn lossless_tokens_round_trip() {
+    let code = "// header\nlet a = 1; // one\n\n  /* block\n */ let b=a;\n";
+
+    let tokens = Lexer::new().run_lossless(code);
+    let text = tokens
+        .iter()
+        .map(|token| token.full_text())
+        .collect::<String>();
+
+    assert_eq!(text, code);
+    assert_eq!(tokens.last().unwrap().token(), &Token::EOF);
+}
+
+#[test]
+pub fn trivia_attachment_works() {
+    let code = "// header\nlet a = 1; // one\n  let b;";
+
+    let tokens = Lexer::new().run_lossless(code);
+
+    // comments on their own line lead the next token
+    assert_eq!(tokens[0].token(), &Token::Keyword("let".to_string()));
+    assert_eq!(
+        tokens[0].leading_trivia(),
+        &[
+            Trivia::new(
+                TriviaKind::Comment,
+                "// header".to_string(),
+                Span::new(0, 9)
+            ),
+            Trivia::new(TriviaKind::Whitespace, "\n".to_string(), Span::new(9, 10)),
+        ]
+    );
+    assert_eq!(
+        tokens[0].trailing_trivia(),
+        &[Trivia::new(
+            TriviaKind::Whitespace,
+            " ".to_string(),
+            Span::new(13, 14)
+        )]
+    );
+
+    // comments on the same line trail the previous token
+    assert_eq!(tokens[4].text(), ";");
+    assert_eq!(tokens[4].span(), Span::new(19, 20));
+    assert_eq!(
+        tokens[4]
+            .trailing_trivia()
+            .iter()
+            .map(Trivia::text)
+            .collect::<Vec<&str>>(),
+        vec![" ", "// one"]
+    );
+    assert_eq!(
+        tokens[5]
+            .leading_trivia()
+            .iter()
+            .map(Trivia::text)
+            .collect::<Vec<&str>>(),
+        vec!["\n  "]
+    );
+}
+
+#[test]
+pub fn lossless_tokens_keep_literal_text() {
+    let code = r#"let s = "a\tb"; let c = '\n';"#;
+
+    let tokens = Lexer::new().run_lossless(code);
+
+    assert_eq!(tokens[3].text(), r#""a\tb""#);
+    assert_eq!(tokens[8].text(), r"'\n'");
+}
@@ -1,26 +1,106 @@
-use std::rc::Rc;
+use std::{fmt, rc::Rc};
 
 use common::types::Type;
 
-use super::unit::ASTUnit;
+use super::{pattern::Pattern, unit::ASTUnit};
 
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum Declaration {
-    // TypeDeclaration, // not implemented yet
+    // `pub` makes functions and types visible to the modules importing theirs
+    TypeDeclaration {
+        identifier: String,
+        fields: Vec<(String, Type)>,
+        public: bool,
+    },
+    EnumDeclaration {
+        identifier: String,
+        variants: Vec<Variant>,
+        public: bool,
+    },
     VariableDeclaration {
         keyword: VariableDeclarationKeyword,
         identifier: String,
+        ty: Option<Type>,
+        expression: Rc<ASTUnit>,
+    },
+    // `let (a, b) = value;`, the pattern cannot fail to match
+    DestructuringDeclaration {
+        keyword: VariableDeclarationKeyword,
+        pattern: Pattern,
+        ty: Option<Type>,
         expression: Rc<ASTUnit>,
     },
+    // `pub extern "C" fun` exports the function with the ABI
     FunctionDeclaration {
         identifier: String,
         parameters: Vec<(String, Type)>,
         return_type: Type,
         expression: Rc<ASTUnit>,
+        public: bool,
+        export: Option<String>,
+    },
+    // `extern "C" fun puts(s: String) -> i32;`, defined outside of the program
+    ExternFunctionDeclaration {
+        abi: Option<String>,
+        identifier: String,
+        parameters: Vec<(String, Type)>,
+        variadic: bool,
+        return_type: Type,
+    },
+    // `import util::strings;`, the path of the module from the source root
+    Import {
+        path: Vec<String>,
     },
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Variant {
+    pub name: String,
+    pub kind: VariantKind,
+    // fields of tuple variants are named by their position
+    pub fields: Vec<(String, Type)>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum VariantKind {
+    Unit,
+    Tuple,
+    Struct,
+}
+
+impl fmt::Display for Variant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            VariantKind::Unit => write!(f, "{}", self.name),
+            VariantKind::Tuple => {
+                let fields = self
+                    .fields
+                    .iter()
+                    .map(|(_, ty)| ty.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ");
+
+                write!(f, "{}({fields})", self.name)
+            }
+            VariantKind::Struct => {
+                let fields = self
+                    .fields
+                    .iter()
+                    .map(|(name, ty)| format!("{name}: {ty}"))
+                    .collect::<Vec<String>>()
+                    .join(", ");
+
+                write!(f, "{} {{ {fields} }}", self.name)
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum VariableDeclarationKeyword {
     Const,
     Let,
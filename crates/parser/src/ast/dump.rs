@@ -0,0 +1,283 @@
+use common::dump::{TreeDump, TreeWriter};
+
+use super::{
+    declaration::{Declaration, VariableDeclarationKeyword},
+    expression::Expression,
+    statement::{LoopStatement, Statement},
+    unit::ASTUnit,
+    AbstractSyntaxTree,
+};
+
+impl TreeDump for AbstractSyntaxTree {
+    fn dump(&self, writer: &mut TreeWriter) {
+        writer.node("AbstractSyntaxTree", |writer| self.root().dump(writer));
+    }
+}
+
+impl TreeDump for ASTUnit {
+    fn dump(&self, writer: &mut TreeWriter) {
+        match self {
+            Self::Declaration(declaration) => declaration.dump(writer),
+            Self::Statement(statement) => statement.dump(writer),
+            Self::Expression(expression) => expression.dump(writer),
+            Self::Block(block) => writer.node("Block", |writer| {
+                for unit in block {
+                    unit.dump(writer);
+                }
+            }),
+            Self::Location(span) => writer.leaf(format!("Location {}..{}", span.start, span.end)),
+        }
+    }
+}
+
+impl TreeDump for Declaration {
+    fn dump(&self, writer: &mut TreeWriter) {
+        match self {
+            Self::TypeDeclaration {
+                identifier,
+                fields,
+                public,
+            } => {
+                let fields = fields
+                    .iter()
+                    .map(|(name, ty)| format!("{name}: {ty}"))
+                    .collect::<Vec<String>>()
+                    .join(", ");
+
+                writer.leaf(format!(
+                    "TypeDeclaration {}{identifier} {{ {fields} }}",
+                    visibility(*public)
+                ));
+            }
+            Self::EnumDeclaration {
+                identifier,
+                variants,
+                public,
+            } => {
+                let variants = variants
+                    .iter()
+                    .map(|variant| variant.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ");
+
+                writer.leaf(format!(
+                    "EnumDeclaration {}{identifier} {{ {variants} }}",
+                    visibility(*public)
+                ));
+            }
+            Self::VariableDeclaration {
+                keyword,
+                identifier,
+                ty,
+                expression,
+            } => {
+                let keyword = match keyword {
+                    VariableDeclarationKeyword::Const => "const",
+                    VariableDeclarationKeyword::Let => "let",
+                };
+                let ty = ty.as_ref().map(|ty| format!(": {ty}")).unwrap_or_default();
+
+                writer.node(
+                    format!("VariableDeclaration {keyword} {identifier}{ty}"),
+                    |writer| expression.dump(writer),
+                );
+            }
+            Self::DestructuringDeclaration {
+                keyword,
+                pattern,
+                ty,
+                expression,
+            } => {
+                let keyword = match keyword {
+                    VariableDeclarationKeyword::Const => "const",
+                    VariableDeclarationKeyword::Let => "let",
+                };
+                let ty = ty.as_ref().map(|ty| format!(": {ty}")).unwrap_or_default();
+
+                writer.node(
+                    format!("DestructuringDeclaration {keyword} {pattern}{ty}"),
+                    |writer| expression.dump(writer),
+                );
+            }
+            Self::FunctionDeclaration {
+                identifier,
+                parameters,
+                return_type,
+                expression,
+                public,
+                export,
+            } => {
+                let parameters = parameters
+                    .iter()
+                    .map(|(name, ty)| format!("{name}: {ty}"))
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                let export = export
+                    .as_ref()
+                    .map(|abi| format!("pub extern {abi:?} "))
+                    .unwrap_or_else(|| visibility(*public).to_string());
+
+                writer.node(
+                    format!(
+                        "FunctionDeclaration {export}{identifier}({parameters}) -> {return_type}"
+                    ),
+                    |writer| expression.dump(writer),
+                );
+            }
+            Self::ExternFunctionDeclaration {
+                abi,
+                identifier,
+                parameters,
+                variadic,
+                return_type,
+            } => {
+                let mut parameters = parameters
+                    .iter()
+                    .map(|(name, ty)| format!("{name}: {ty}"))
+                    .collect::<Vec<String>>();
+                if *variadic {
+                    parameters.push("...".to_string());
+                }
+                let abi = abi
+                    .as_ref()
+                    .map(|abi| format!(" {abi:?}"))
+                    .unwrap_or_default();
+
+                writer.leaf(format!(
+                    "ExternFunctionDeclaration{abi} {identifier}({}) -> {return_type}",
+                    parameters.join(", ")
+                ));
+            }
+            Self::Import { path } => writer.leaf(format!("Import {}", path.join("::"))),
+        }
+    }
+}
+
+impl TreeDump for Statement {
+    fn dump(&self, writer: &mut TreeWriter) {
+        match self {
+            Self::Return(expression) => writer.node("Return", |writer| expression.dump(writer)),
+            Self::ImplicitReturn(expression) => {
+                writer.node("ImplicitReturn", |writer| expression.dump(writer))
+            }
+            Self::ControlFlow {
+                condition,
+                execute,
+                alternative,
+            } => writer.node("ControlFlow", |writer| {
+                writer.node("Condition", |writer| condition.dump(writer));
+                writer.node("Then", |writer| execute.dump(writer));
+
+                if let Some(alternative) = alternative {
+                    writer.node("Else", |writer| alternative.dump(writer));
+                }
+            }),
+            Self::Loop(LoopStatement::While { condition, execute }) => {
+                writer.node("While", |writer| {
+                    writer.node("Condition", |writer| condition.dump(writer));
+                    writer.node("Body", |writer| execute.dump(writer));
+                })
+            }
+            Self::Match { scrutinee, arms } => writer.node("Match", |writer| {
+                scrutinee.dump(writer);
+
+                for arm in arms {
+                    writer.node(format!("Arm {}", arm.pattern), |writer| {
+                        if let Some(guard) = &arm.guard {
+                            writer.node("Guard", |writer| guard.dump(writer));
+                        }
+
+                        arm.body.dump(writer);
+                    });
+                }
+            }),
+        }
+    }
+}
+
+impl TreeDump for Expression {
+    fn dump(&self, writer: &mut TreeWriter) {
+        match self {
+            Self::BinaryExpression {
+                left,
+                right,
+                operation,
+            } => writer.node(
+                format!("BinaryExpression {}", operation.as_str()),
+                |writer| {
+                    left.dump(writer);
+                    right.dump(writer);
+                },
+            ),
+            Self::Literal(literal) => writer.leaf(format!("Literal {literal:?}")),
+            Self::Identifier(identifier) => writer.leaf(format!("Identifier {identifier}")),
+            Self::FunctionInvokation {
+                function_name,
+                parameters,
+            } => writer.node(format!("FunctionInvokation {function_name}"), |writer| {
+                for parameter in parameters {
+                    parameter.dump(writer);
+                }
+            }),
+            Self::Index { target, index } => writer.node("Index", |writer| {
+                target.dump(writer);
+                index.dump(writer);
+            }),
+            Self::Slice { target, start, end } => writer.node("Slice", |writer| {
+                target.dump(writer);
+
+                if let Some(start) = start {
+                    writer.node("Start", |writer| start.dump(writer));
+                }
+                if let Some(end) = end {
+                    writer.node("End", |writer| end.dump(writer));
+                }
+            }),
+            Self::Cast { expression, ty } => {
+                writer.node(format!("Cast {ty}"), |writer| expression.dump(writer))
+            }
+            Self::Struct { identifier, fields } => {
+                writer.node(format!("Struct {identifier}"), |writer| {
+                    for (name, value) in fields {
+                        writer.node(format!("Field {name}"), |writer| value.dump(writer));
+                    }
+                })
+            }
+            Self::FieldAccess { target, field } => {
+                writer.node(format!("FieldAccess {field}"), |writer| target.dump(writer))
+            }
+            Self::Variant {
+                enumeration,
+                variant,
+                fields,
+            } => writer.node(format!("Variant {enumeration}::{variant}"), |writer| {
+                for (name, value) in fields {
+                    writer.node(format!("Field {name}"), |writer| value.dump(writer));
+                }
+            }),
+            Self::AddressOf(place) => writer.node("AddressOf", |writer| place.dump(writer)),
+            Self::Deref(pointer) => writer.node("Deref", |writer| pointer.dump(writer)),
+            Self::Tuple(elements) => writer.node("Tuple", |writer| {
+                for element in elements {
+                    element.dump(writer);
+                }
+            }),
+            Self::Array(elements) => writer.node("Array", |writer| {
+                for element in elements {
+                    element.dump(writer);
+                }
+            }),
+            Self::ArrayRepeat { value, count } => {
+                writer.node(format!("ArrayRepeat {count}"), |writer| value.dump(writer))
+            }
+        }
+    }
+}
+
+fn visibility(public: bool) -> &'static str {
+    if public {
+        "pub "
+    } else {
+        ""
+    }
+}
@@ -1,8 +1,11 @@
 use std::rc::Rc;
 
+use common::types::Type;
+
 use super::{literal::Literal, operation::Operation, unit::ASTUnit};
 
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum Expression {
     BinaryExpression {
         left: Rc<ASTUnit>,
@@ -15,4 +18,43 @@ pub enum Expression {
         function_name: String,
         parameters: Vec<Rc<ASTUnit>>,
     },
+    Index {
+        target: Rc<ASTUnit>,
+        index: Rc<ASTUnit>,
+    },
+    // `target[start..end]`, either bound can be left out
+    Slice {
+        target: Rc<ASTUnit>,
+        start: Option<Rc<ASTUnit>>,
+        end: Option<Rc<ASTUnit>>,
+    },
+    Cast {
+        expression: Rc<ASTUnit>,
+        ty: Type,
+    },
+    Struct {
+        identifier: String,
+        fields: Vec<(String, Rc<ASTUnit>)>,
+    },
+    FieldAccess {
+        target: Rc<ASTUnit>,
+        field: String,
+    },
+    // fields of tuple variants are named by their position
+    Variant {
+        enumeration: String,
+        variant: String,
+        fields: Vec<(String, Rc<ASTUnit>)>,
+    },
+    Tuple(Vec<Rc<ASTUnit>>),
+    // `&place`
+    AddressOf(Rc<ASTUnit>),
+    // `*pointer`
+    Deref(Rc<ASTUnit>),
+    Array(Vec<Rc<ASTUnit>>),
+    // `[value; count]`
+    ArrayRepeat {
+        value: Rc<ASTUnit>,
+        count: u64,
+    },
 }
@@ -1,6 +1,7 @@
 use lexer::lexer;
 
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum Literal {
     String(String),
     Int8(i8),
@@ -21,7 +22,21 @@ impl Literal {
     pub fn from_literal_token(value: &lexer::token::Literal) -> Self {
         match value {
             lexer::token::Literal::String(string) => Self::String(string.clone()),
-            lexer::token::Literal::Number(num_str) => Self::Int32(num_str.parse().unwrap()),
+            lexer::token::Literal::Char(char) => Self::Char(*char),
+            // the lexer only lets through numbers that parse
+            lexer::token::Literal::Number(num_str) if num_str.contains('.') => {
+                Self::Float64(num_str.parse().unwrap())
+            }
+            // integers are i32 ones unless they need more bits
+            lexer::token::Literal::Number(num_str) => {
+                if let Ok(value) = num_str.parse() {
+                    Self::Int32(value)
+                } else if let Ok(value) = num_str.parse() {
+                    Self::Int64(value)
+                } else {
+                    Self::UInt64(num_str.parse().unwrap())
+                }
+            }
             lexer::token::Literal::Boolean(bool) => Self::Bool(bool.parse().unwrap()),
         }
     }
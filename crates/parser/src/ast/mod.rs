@@ -1,13 +1,16 @@
 use unit::ASTUnit;
 
 pub mod declaration;
+pub mod dump;
 pub mod expression;
 pub mod literal;
 pub mod operation;
+pub mod pattern;
 pub mod statement;
 pub mod unit;
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct AbstractSyntaxTree {
     root: ASTUnit,
 }
@@ -1,20 +1,49 @@
+use std::{fmt, str::FromStr};
+
+// the text did not name an operation of the expected kind
 #[derive(Debug, PartialEq, Eq, Clone)]
+pub struct UnknownOperation(pub String);
+
+impl fmt::Display for UnknownOperation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown operation `{}`", self.0)
+    }
+}
+
+impl std::error::Error for UnknownOperation {}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum Operation {
     Algebraic(AlgebraicOperation),
     Logical(LogicalOperation),
     Assignment(AssignmentOperation),
 }
 
+impl FromStr for Operation {
+    type Err = UnknownOperation;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        value
+            .parse()
+            .map(Self::Algebraic)
+            .or_else(|_| value.parse().map(Self::Logical))
+            .or_else(|_| value.parse().map(Self::Assignment))
+    }
+}
+
 impl Operation {
-    pub fn from_str(value: &str) -> Option<Self> {
-        AlgebraicOperation::from_str(value)
-            .map(|alg| Self::Algebraic(alg))
-            .or(LogicalOperation::from_str(value).map(|log| Self::Logical(log)))
-            .or(AssignmentOperation::from_str(value).map(|assign| Self::Assignment(assign)))
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Algebraic(alg) => alg.as_str(),
+            Self::Logical(log) => log.as_str(),
+            Self::Assignment(assign) => assign.as_str(),
+        }
     }
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum AssignmentOperation {
     Assign,
     AdditionAssign,
@@ -23,20 +52,35 @@ pub enum AssignmentOperation {
     DivisionAssign,
 }
 
-impl AssignmentOperation {
-    pub fn from_str(value: &str) -> Option<Self> {
+impl FromStr for AssignmentOperation {
+    type Err = UnknownOperation;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
         match value {
-            "=" => Some(Self::Assign),
-            "+=" => Some(Self::AdditionAssign),
-            "-=" => Some(Self::SubtractionAssign),
-            "/=" => Some(Self::DivisionAssign),
-            "*=" => Some(Self::MultiplicationAssign),
-            _ => None,
+            "=" => Ok(Self::Assign),
+            "+=" => Ok(Self::AdditionAssign),
+            "-=" => Ok(Self::SubtractionAssign),
+            "/=" => Ok(Self::DivisionAssign),
+            "*=" => Ok(Self::MultiplicationAssign),
+            _ => Err(UnknownOperation(value.to_string())),
+        }
+    }
+}
+
+impl AssignmentOperation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Assign => "=",
+            Self::AdditionAssign => "+=",
+            Self::SubtractionAssign => "-=",
+            Self::DivisionAssign => "/=",
+            Self::MultiplicationAssign => "*=",
         }
     }
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum AlgebraicOperation {
     Addition,
     Subtraction,
@@ -44,14 +88,27 @@ pub enum AlgebraicOperation {
     Division,
 }
 
-impl AlgebraicOperation {
-    pub fn from_str(value: &str) -> Option<Self> {
+impl FromStr for AlgebraicOperation {
+    type Err = UnknownOperation;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
         match value {
-            "+" => Some(Self::Addition),
-            "-" => Some(Self::Subtraction),
-            "*" => Some(Self::Multiplication),
-            "/" => Some(Self::Division),
-            _ => None,
+            "+" => Ok(Self::Addition),
+            "-" => Ok(Self::Subtraction),
+            "*" => Ok(Self::Multiplication),
+            "/" => Ok(Self::Division),
+            _ => Err(UnknownOperation(value.to_string())),
+        }
+    }
+}
+
+impl AlgebraicOperation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Addition => "+",
+            Self::Subtraction => "-",
+            Self::Multiplication => "*",
+            Self::Division => "/",
         }
     }
 }
@@ -75,6 +132,7 @@ impl PartialOrd for AlgebraicOperation {
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(u8)]
 pub enum LogicalOperation {
     Equal = 6,
@@ -86,17 +144,33 @@ pub enum LogicalOperation {
     And = 0,
 }
 
-impl LogicalOperation {
-    pub fn from_str(value: &str) -> Option<Self> {
+impl FromStr for LogicalOperation {
+    type Err = UnknownOperation;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
         match value {
-            "==" => Some(Self::Equal),
-            ">=" => Some(Self::GreaterOrEqual),
-            "<=" => Some(Self::LessOrEqual),
-            ">" => Some(Self::Greater),
-            "<" => Some(Self::Less),
-            "||" => Some(Self::Or),
-            "&&" => Some(Self::And),
-            _ => None,
+            "==" => Ok(Self::Equal),
+            ">=" => Ok(Self::GreaterOrEqual),
+            "<=" => Ok(Self::LessOrEqual),
+            ">" => Ok(Self::Greater),
+            "<" => Ok(Self::Less),
+            "||" => Ok(Self::Or),
+            "&&" => Ok(Self::And),
+            _ => Err(UnknownOperation(value.to_string())),
+        }
+    }
+}
+
+impl LogicalOperation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Equal => "==",
+            Self::GreaterOrEqual => ">=",
+            Self::LessOrEqual => "<=",
+            Self::Greater => ">",
+            Self::Less => "<",
+            Self::Or => "||",
+            Self::And => "&&",
         }
     }
 }
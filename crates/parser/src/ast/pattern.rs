@@ -0,0 +1,86 @@
+use std::{fmt, rc::Rc};
+
+use super::{literal::Literal, unit::ASTUnit};
+
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum Pattern {
+    Wildcard,
+    Binding(String),
+    Literal(Literal),
+    // fields of tuple variants are named by their position
+    Variant {
+        enumeration: String,
+        variant: String,
+        fields: Vec<(String, Pattern)>,
+    },
+    Tuple(Vec<Pattern>),
+}
+
+impl Pattern {
+    // matches any value, without looking at more than its shape
+    pub fn is_irrefutable(&self) -> bool {
+        match self {
+            Self::Wildcard | Self::Binding(_) => true,
+            Self::Tuple(elements) => elements.iter().all(Self::is_irrefutable),
+            Self::Literal(_) | Self::Variant { .. } => false,
+        }
+    }
+}
+
+impl fmt::Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Wildcard => write!(f, "_"),
+            Self::Binding(name) => write!(f, "{name}"),
+            Self::Literal(literal) => write!(f, "{literal:?}"),
+            Self::Tuple(elements) if elements.len() == 1 => write!(f, "({},)", elements[0]),
+            Self::Tuple(elements) => {
+                let elements = elements
+                    .iter()
+                    .map(|element| element.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ");
+
+                write!(f, "({elements})")
+            }
+            Self::Variant {
+                enumeration,
+                variant,
+                fields,
+            } => {
+                write!(f, "{enumeration}::{variant}")?;
+
+                if fields.is_empty() {
+                    return Ok(());
+                }
+
+                if fields.iter().all(|(name, _)| name.parse::<usize>().is_ok()) {
+                    let fields = fields
+                        .iter()
+                        .map(|(_, pattern)| pattern.to_string())
+                        .collect::<Vec<String>>()
+                        .join(", ");
+
+                    write!(f, "({fields})")
+                } else {
+                    let fields = fields
+                        .iter()
+                        .map(|(name, pattern)| format!("{name}: {pattern}"))
+                        .collect::<Vec<String>>()
+                        .join(", ");
+
+                    write!(f, " {{ {fields} }}")
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct MatchArm {
+    pub pattern: Pattern,
+    pub guard: Option<Rc<ASTUnit>>,
+    pub body: Rc<ASTUnit>,
+}
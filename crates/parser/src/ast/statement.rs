@@ -1,8 +1,9 @@
 use std::rc::Rc;
 
-use super::unit::ASTUnit;
+use super::{pattern::MatchArm, unit::ASTUnit};
 
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum Statement {
     Return(Rc<ASTUnit>),
     ImplicitReturn(Rc<ASTUnit>),
@@ -12,9 +13,14 @@ pub enum Statement {
         alternative: Option<Rc<ASTUnit>>,
     },
     Loop(LoopStatement),
+    Match {
+        scrutinee: Rc<ASTUnit>,
+        arms: Vec<MatchArm>,
+    },
 }
 
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum LoopStatement {
     While {
         condition: Rc<ASTUnit>,
@@ -1,13 +1,18 @@
 use std::rc::Rc;
 
+use common::span::Span;
+
 use super::{declaration::Declaration, expression::Expression, statement::Statement};
 
 pub type Block = Vec<Rc<ASTUnit>>;
 
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum ASTUnit {
     Declaration(Declaration),
     Statement(Statement),
     Expression(Expression),
     Block(Block),
+    // source position of the unit that follows, only when parsed with spans
+    Location(Span),
 }
@@ -0,0 +1,277 @@
+use common::constants::keywords::{
+    DECLARATION_CONSTANT, DECLARATION_ENUM, DECLARATION_EXTERN, DECLARATION_FUNCTION,
+    DECLARATION_PUBLIC, DECLARATION_STRUCT, DECLARATION_VARIABLE, STATEMENT_ELSE, STATEMENT_IF,
+    STATEMENT_MATCH, STATEMENT_RETURN, STATEMENT_WHILE,
+};
+use lexer::lexer::{
+    token::Token,
+    trivia::{LosslessToken, Trivia, TriviaKind},
+};
+
+use super::{green::GreenNodeBuilder, ConcreteSyntaxTree, SyntaxKind};
+
+// groups the lossless token stream into declarations, statements,
+// blocks and brackets, every token and trivia ends up in the tree
+pub struct CSTBuilder<'a> {
+    tokens: &'a [LosslessToken],
+    pos: usize,
+    builder: GreenNodeBuilder,
+}
+
+impl<'a> CSTBuilder<'a> {
+    pub fn new(tokens: &'a [LosslessToken]) -> Self {
+        Self {
+            tokens,
+            pos: 0,
+            builder: GreenNodeBuilder::new(),
+        }
+    }
+
+    pub fn run(mut self) -> ConcreteSyntaxTree {
+        self.builder.start_node(SyntaxKind::Root);
+
+        while !self.at_end() {
+            if self.at_punctuation('}') {
+                // unbalanced brace, keep it so the source survives
+                self.bump();
+            } else {
+                self.statement();
+            }
+        }
+
+        // EOF carries the trailing trivia of the file
+        if self.pos < self.tokens.len() {
+            self.bump();
+        }
+
+        ConcreteSyntaxTree::new(self.builder.finish())
+    }
+
+    fn statement(&mut self) {
+        match self.current().and_then(Token::as_keyword) {
+            Some(DECLARATION_FUNCTION) => {
+                self.builder.start_node(SyntaxKind::FunctionDeclaration);
+                self.tokens_until(|token| *token == Token::Punctuation('{'));
+                self.block();
+                self.builder.finish_node();
+            }
+            // extern functions have no body, unless they are exported
+            Some(DECLARATION_EXTERN) => self.terminated(SyntaxKind::FunctionDeclaration),
+            Some(DECLARATION_STRUCT) => self.type_declaration(SyntaxKind::StructDeclaration),
+            Some(DECLARATION_ENUM) => self.type_declaration(SyntaxKind::EnumDeclaration),
+            Some(DECLARATION_PUBLIC) => match self
+                .tokens
+                .get(self.pos + 1)
+                .and_then(|token| token.token().as_keyword())
+            {
+                Some(DECLARATION_FUNCTION | DECLARATION_EXTERN) => {
+                    self.builder.start_node(SyntaxKind::FunctionDeclaration);
+                    self.tokens_until(|token| *token == Token::Punctuation('{'));
+                    self.block();
+                    self.builder.finish_node();
+                }
+                Some(DECLARATION_STRUCT) => self.type_declaration(SyntaxKind::StructDeclaration),
+                Some(DECLARATION_ENUM) => self.type_declaration(SyntaxKind::EnumDeclaration),
+                _ => self.terminated(SyntaxKind::ExpressionStatement),
+            },
+            Some(DECLARATION_VARIABLE | DECLARATION_CONSTANT) => {
+                self.terminated(SyntaxKind::VariableDeclaration);
+            }
+            Some(STATEMENT_RETURN) => {
+                self.terminated(SyntaxKind::ReturnStatement);
+            }
+            Some(STATEMENT_IF) => self.if_statement(),
+            Some(STATEMENT_MATCH) => self.match_expression(),
+            Some(STATEMENT_WHILE) => {
+                self.builder.start_node(SyntaxKind::WhileStatement);
+                self.tokens_until(|token| *token == Token::Punctuation('{'));
+                self.block();
+                self.builder.finish_node();
+            }
+            _ if self.at_punctuation('{') => self.block(),
+            _ => self.terminated(SyntaxKind::ExpressionStatement),
+        }
+    }
+
+    // a statement running till the next top-level ";", or the end of the block
+    fn terminated(&mut self, kind: SyntaxKind) {
+        self.builder.start_node(kind);
+        self.tokens_until(|token| *token == Token::Punctuation(';'));
+
+        if self.at_punctuation(';') {
+            self.bump();
+        }
+
+        self.builder.finish_node();
+    }
+
+    // the fields or variants of a struct or an enum
+    fn type_declaration(&mut self, kind: SyntaxKind) {
+        self.builder.start_node(kind);
+        self.tokens_until(|token| *token == Token::Punctuation('{'));
+        self.list();
+        self.builder.finish_node();
+    }
+
+    fn match_expression(&mut self) {
+        self.builder.start_node(SyntaxKind::MatchExpression);
+        self.bump();
+        self.tokens_until(|token| *token == Token::Punctuation('{'));
+        self.list();
+        self.builder.finish_node();
+    }
+
+    fn if_statement(&mut self) {
+        self.builder.start_node(SyntaxKind::IfStatement);
+        self.bump();
+        self.tokens_until(|token| *token == Token::Punctuation('{'));
+        self.block();
+
+        if self.current().and_then(Token::as_keyword) == Some(STATEMENT_ELSE) {
+            self.builder.start_node(SyntaxKind::ElseClause);
+            self.bump();
+
+            if self.current().and_then(Token::as_keyword) == Some(STATEMENT_IF) {
+                self.if_statement();
+            } else {
+                self.block();
+            }
+
+            self.builder.finish_node();
+        }
+
+        self.builder.finish_node();
+    }
+
+    fn block(&mut self) {
+        if !self.at_punctuation('{') {
+            return;
+        }
+
+        self.builder.start_node(SyntaxKind::Block);
+        self.bump();
+
+        while !self.at_end() && !self.at_punctuation('}') {
+            self.statement();
+        }
+
+        if self.at_punctuation('}') {
+            self.bump();
+        }
+
+        self.builder.finish_node();
+    }
+
+    fn list(&mut self) {
+        if !self.at_punctuation('{') {
+            return;
+        }
+
+        self.builder.start_node(SyntaxKind::List);
+        self.bump();
+
+        while !self.at_end() && !self.at_punctuation('}') {
+            // guards of match arms
+            if self.current().and_then(Token::as_keyword) == Some(STATEMENT_IF) {
+                self.bump();
+            } else {
+                self.expression_token();
+            }
+        }
+
+        if self.at_punctuation('}') {
+            self.bump();
+        }
+
+        self.builder.finish_node();
+    }
+
+    fn group(&mut self, kind: SyntaxKind, close: char) {
+        self.builder.start_node(kind);
+        self.bump();
+
+        while !self.at_end() && !self.at_punctuation(close) && !self.at_punctuation('}') {
+            self.expression_token();
+        }
+
+        if self.at_punctuation(close) {
+            self.bump();
+        }
+
+        self.builder.finish_node();
+    }
+
+    fn tokens_until(&mut self, stop: impl Fn(&Token) -> bool) {
+        while let Some(token) = self.current() {
+            if stop(token) || matches!(token, Token::EOF | Token::Punctuation('}')) {
+                break;
+            }
+
+            self.expression_token();
+        }
+    }
+
+    fn expression_token(&mut self) {
+        match self.current() {
+            Some(Token::Punctuation('(')) => self.group(SyntaxKind::Parenthesized, ')'),
+            Some(Token::Punctuation('[')) => self.group(SyntaxKind::Bracketed, ']'),
+            // braces right after a name hold the fields of a struct literal or pattern
+            Some(Token::Punctuation('{'))
+                if self.pos > 0
+                    && matches!(self.tokens[self.pos - 1].token(), Token::Identifier(_)) =>
+            {
+                self.group(SyntaxKind::Braced, '}')
+            }
+            Some(Token::Punctuation('{')) => self.block(),
+            Some(Token::Keyword(keyword)) if keyword == STATEMENT_IF => self.if_statement(),
+            Some(Token::Keyword(keyword)) if keyword == STATEMENT_MATCH => self.match_expression(),
+            _ => self.bump(),
+        }
+    }
+
+    fn bump(&mut self) {
+        let token = &self.tokens[self.pos];
+
+        self.trivia(token.leading_trivia());
+        self.builder.token(kind_of(token.token()), token.text());
+        self.trivia(token.trailing_trivia());
+
+        self.pos += 1;
+    }
+
+    fn trivia(&mut self, trivia: &[Trivia]) {
+        for trivia in trivia {
+            let kind = match trivia.kind() {
+                TriviaKind::Whitespace => SyntaxKind::Whitespace,
+                TriviaKind::Comment => SyntaxKind::Comment,
+            };
+
+            self.builder.token(kind, trivia.text());
+        }
+    }
+
+    fn current(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(LosslessToken::token)
+    }
+
+    fn at_punctuation(&self, punctuation: char) -> bool {
+        self.current() == Some(&Token::Punctuation(punctuation))
+    }
+
+    fn at_end(&self) -> bool {
+        matches!(self.current(), None | Some(Token::EOF))
+    }
+}
+
+fn kind_of(token: &Token) -> SyntaxKind {
+    match token {
+        Token::Keyword(_) => SyntaxKind::Keyword,
+        Token::Identifier(_) => SyntaxKind::Identifier,
+        Token::Type(_) => SyntaxKind::Type,
+        Token::Literal(_) => SyntaxKind::Literal,
+        Token::Operator(_) => SyntaxKind::Operator,
+        Token::Punctuation(_) => SyntaxKind::Punctuation,
+        Token::Comment(_) => SyntaxKind::Comment,
+        Token::EOF => SyntaxKind::EOF,
+    }
+}
@@ -0,0 +1,118 @@
+use std::rc::Rc;
+
+use super::SyntaxKind;
+
+// immutable, position independent tree, identical
+// subtrees can be shared between trees
+#[derive(Debug, PartialEq, Eq)]
+pub struct GreenNode {
+    kind: SyntaxKind,
+    width: usize,
+    children: Vec<GreenElement>,
+}
+
+impl GreenNode {
+    pub fn new(kind: SyntaxKind, children: Vec<GreenElement>) -> Self {
+        let width = children.iter().map(GreenElement::width).sum();
+
+        Self {
+            kind,
+            width,
+            children,
+        }
+    }
+
+    pub fn kind(&self) -> SyntaxKind {
+        self.kind
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn children(&self) -> &[GreenElement] {
+        &self.children
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct GreenToken {
+    kind: SyntaxKind,
+    text: String,
+}
+
+impl GreenToken {
+    pub fn new(kind: SyntaxKind, text: String) -> Self {
+        Self { kind, text }
+    }
+
+    pub fn kind(&self) -> SyntaxKind {
+        self.kind
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn width(&self) -> usize {
+        self.text.len()
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum GreenElement {
+    Node(Rc<GreenNode>),
+    Token(Rc<GreenToken>),
+}
+
+impl GreenElement {
+    pub fn kind(&self) -> SyntaxKind {
+        match self {
+            Self::Node(node) => node.kind(),
+            Self::Token(token) => token.kind(),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        match self {
+            Self::Node(node) => node.width(),
+            Self::Token(token) => token.width(),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct GreenNodeBuilder {
+    parents: Vec<(SyntaxKind, Vec<GreenElement>)>,
+}
+
+impl GreenNodeBuilder {
+    pub fn new() -> Self {
+        Self { parents: vec![] }
+    }
+
+    pub fn start_node(&mut self, kind: SyntaxKind) {
+        self.parents.push((kind, vec![]));
+    }
+
+    pub fn token(&mut self, kind: SyntaxKind, text: &str) {
+        let token = GreenElement::Token(Rc::new(GreenToken::new(kind, text.to_string())));
+
+        self.parents.last_mut().unwrap().1.push(token);
+    }
+
+    pub fn finish_node(&mut self) {
+        let (kind, children) = self.parents.pop().unwrap();
+        let node = GreenElement::Node(Rc::new(GreenNode::new(kind, children)));
+
+        self.parents.last_mut().unwrap().1.push(node);
+    }
+
+    pub fn finish(mut self) -> Rc<GreenNode> {
+        let (kind, children) = self.parents.pop().unwrap();
+
+        assert!(self.parents.is_empty(), "unfinished nodes left in builder");
+
+        Rc::new(GreenNode::new(kind, children))
+    }
+}
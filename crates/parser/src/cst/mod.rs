@@ -0,0 +1,68 @@
+use green::GreenNode;
+use red::SyntaxNode;
+use std::rc::Rc;
+
+pub mod builder;
+pub mod green;
+pub mod red;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum SyntaxKind {
+    // tokens
+    Keyword,
+    Identifier,
+    Type,
+    Literal,
+    Operator,
+    Punctuation,
+    Whitespace,
+    Comment,
+    EOF,
+
+    // nodes
+    Root,
+    FunctionDeclaration,
+    VariableDeclaration,
+    StructDeclaration,
+    EnumDeclaration,
+    IfStatement,
+    ElseClause,
+    WhileStatement,
+    ReturnStatement,
+    ExpressionStatement,
+    MatchExpression,
+    Block,
+    // comma separated fields, variants or match arms in braces
+    List,
+    Parenthesized,
+    Bracketed,
+    // the fields of a struct literal or pattern
+    Braced,
+}
+
+impl SyntaxKind {
+    pub fn is_trivia(&self) -> bool {
+        matches!(self, Self::Whitespace | Self::Comment)
+    }
+}
+
+#[derive(Debug)]
+pub struct ConcreteSyntaxTree {
+    root: SyntaxNode,
+}
+
+impl ConcreteSyntaxTree {
+    pub fn new(green: Rc<GreenNode>) -> Self {
+        Self {
+            root: SyntaxNode::new_root(green),
+        }
+    }
+
+    pub fn root(&self) -> &SyntaxNode {
+        &self.root
+    }
+
+    pub fn text(&self) -> String {
+        self.root.text()
+    }
+}
@@ -1,93 +1,277 @@
-use std::{mem, rc::Rc};
+use std::{
+    cell::RefCell,
+    mem,
+    ops::{Bound, Deref, RangeBounds},
+    rc::Rc,
+};
 
 use ast::{
-    declaration::{Declaration, VariableDeclarationKeyword},
+    declaration::{Declaration, VariableDeclarationKeyword, Variant, VariantKind},
     expression::Expression,
     literal::Literal,
     operation::Operation,
+    pattern::{MatchArm, Pattern},
     statement::{LoopStatement, Statement},
     unit::ASTUnit,
     AbstractSyntaxTree,
 };
 use common::{
-    constants::keywords::{
-        DECLARATION_CONSTANT, DECLARATION_FUNCTION, DECLARATION_VARIABLE, STATEMENT_ELSE,
-        STATEMENT_IF, STATEMENT_RETURN, STATEMENT_WHILE,
+    constants::{
+        builtins::ABI_C,
+        keywords::{
+            DECLARATION_CONSTANT, DECLARATION_ENUM, DECLARATION_EXTERN, DECLARATION_FUNCTION,
+            DECLARATION_IMPORT, DECLARATION_PUBLIC, DECLARATION_STRUCT, DECLARATION_VARIABLE,
+            EXPRESSION_CAST, STATEMENT_ELSE, STATEMENT_IF, STATEMENT_MATCH, STATEMENT_RETURN,
+            STATEMENT_WHILE,
+        },
     },
+    diagnostic::Diagnostic,
+    span::Span,
     types::Type,
     util::traversal,
 };
-use lexer::lexer::token::Token;
+use cst::{builder::CSTBuilder, ConcreteSyntaxTree};
+use lexer::lexer::{
+    token::{self, Token},
+    trivia::LosslessToken,
+};
 
 pub mod ast;
+pub mod cst;
 
 pub enum Keyword {
     FunctionDeclaration,
+    ExternFunctionDeclaration,
+    Public,
+    Import,
+    TypeDeclaration,
+    EnumDeclaration,
     VariableDeclaration(VariableDeclarationKeyword),
     ControlFlowIf,
     ControlFlowElse,
     Return,
     While,
+    Match,
+}
+
+pub enum UnaryOperator {
+    AddressOf,
+    Deref,
 }
 
 pub enum RecognizableStructure {
     Block((usize, usize)),
     FunctionInvokation((usize, usize)),
+    Index((usize, usize)),
+    Tuple((usize, usize)),
+    Array((usize, usize)),
+    Struct((usize, usize)),
+    Variant((usize, usize)),
     Statement((usize, usize)),
 }
 
-pub struct Parser {}
+// a part of the tokens being parsed, knowing where it starts in them
+// so that the spans of its tokens can be found
+#[derive(Clone, Copy, Default)]
+struct TokenSlice<'a> {
+    tokens: &'a [Token],
+    start: usize,
+}
+
+impl<'a> TokenSlice<'a> {
+    fn new(tokens: &'a [Token]) -> Self {
+        Self { tokens, start: 0 }
+    }
+
+    fn slice(&self, range: impl RangeBounds<usize>) -> Self {
+        self.get_slice(range).expect("range out of the tokens")
+    }
+
+    fn get_slice(&self, range: impl RangeBounds<usize>) -> Option<Self> {
+        let start = match range.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start + 1,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&end) => end + 1,
+            Bound::Excluded(&end) => end,
+            Bound::Unbounded => self.tokens.len(),
+        };
+
+        Some(Self {
+            tokens: self.tokens.get(start..end)?,
+            start: self.start + start,
+        })
+    }
+}
+
+impl Deref for TokenSlice<'_> {
+    type Target = [Token];
+
+    fn deref(&self) -> &Self::Target {
+        self.tokens
+    }
+}
+
+pub struct Parser {
+    // spans of the tokens given to `run_spanned`
+    spans: RefCell<Option<Vec<Span>>>,
+}
+
+impl Default for Parser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl Parser {
     pub fn new() -> Self {
-        Self {}
+        Self {
+            spans: RefCell::new(None),
+        }
     }
 
     pub fn run(&self, input: &[Token]) -> AbstractSyntaxTree {
-        AbstractSyntaxTree::new(self.run_internal(input).0)
+        self.try_run(input)
+            .unwrap_or_else(|diagnostic| panic!("{diagnostic}"))
+    }
+
+    pub fn try_run(&self, input: &[Token]) -> Result<AbstractSyntaxTree, Diagnostic> {
+        let (root, _) = self.run_internal(TokenSlice::new(input))?;
+
+        Ok(AbstractSyntaxTree::new(root))
     }
 
-    fn run_internal(&self, tokens: &[Token]) -> (ASTUnit, usize) {
+    // like `run`, with a location in front of every declaration and statement
+    pub fn run_spanned(&self, input: &[Token], spans: &[Span]) -> AbstractSyntaxTree {
+        self.try_run_spanned(input, spans)
+            .unwrap_or_else(|diagnostic| panic!("{diagnostic}"))
+    }
+
+    // like `run_spanned`, the diagnostics point at the tokens they are about
+    pub fn try_run_spanned(
+        &self,
+        input: &[Token],
+        spans: &[Span],
+    ) -> Result<AbstractSyntaxTree, Diagnostic> {
+        *self.spans.borrow_mut() = Some(spans.to_vec());
+
+        let ast = self.try_run(input);
+        *self.spans.borrow_mut() = None;
+
+        ast
+    }
+
+    fn span_of(&self, tokens: TokenSlice, pos: usize) -> Option<Span> {
+        self.spans
+            .borrow()
+            .as_ref()?
+            .get(tokens.start + pos)
+            .copied()
+    }
+
+    // a diagnostic at the token at `pos`, or at the last one when the tokens end before it
+    fn error(&self, tokens: TokenSlice, pos: usize, message: String) -> Diagnostic {
+        let pos = pos.min(tokens.len().saturating_sub(1));
+
+        Diagnostic::new(message, self.span_of(tokens, pos))
+    }
+
+    // the size of the braces at `pos`, which hold `what`
+    fn braces(&self, tokens: TokenSlice, pos: usize, what: &str) -> Result<usize, Diagnostic> {
+        if tokens.get(pos) != Some(&Token::Punctuation('{')) {
+            return Err(self.error(
+                tokens,
+                pos,
+                format!("expected {what}, got: {:?}", tokens.get(pos)),
+            ));
+        }
+
+        Ok(self.closing(tokens, pos, '{')? + 1)
+    }
+
+    // the offset from `pos` of the bracket closing the one at `pos`
+    fn closing(&self, tokens: TokenSlice, pos: usize, open: char) -> Result<usize, Diagnostic> {
+        let close = match open {
+            '(' => ')',
+            '[' => ']',
+            _ => '}',
+        };
+
+        traversal::traverse_till_root_par(
+            tokens.get(pos..).unwrap_or_default(),
+            (Token::Punctuation(open), Token::Punctuation(close)),
+        )
+        .ok_or_else(|| self.error(tokens, pos, format!("unclosed `{open}`")))
+    }
+
+    pub fn run_lossless(&self, input: &[LosslessToken]) -> ConcreteSyntaxTree {
+        CSTBuilder::new(input).run()
+    }
+
+    fn run_internal(&self, tokens: TokenSlice) -> Result<(ASTUnit, usize), Diagnostic> {
         let mut pos = 0;
-        let (tokens, offset) = if tokens.first().is_some()
+        let (tokens, offset) = if !tokens.is_empty()
             && tokens.first().unwrap() == &Token::Punctuation('{')
             && tokens.last().is_some()
             && tokens.last().unwrap() == &Token::Punctuation('}')
         {
-            (&tokens[1..(tokens.len() - 1)], 2)
+            (tokens.slice(1..(tokens.len() - 1)), 2)
         } else {
             (tokens, 0)
         };
 
         let mut units = vec![];
+        // set by `pub`, for the declaration after it
+        let mut public = false;
 
         while pos < tokens.len() {
+            let start = pos;
             let token = &tokens[pos];
+            let units_before = units.len();
 
             match token {
-                Token::Identifier(_) | Token::Literal(_) => {
-                    let (expression, size) = self.parse_expression(&tokens[pos..]);
+                token if self.starts_expression(token) => {
+                    let (expression, size) = self.parse_expression(tokens.slice(pos..))?;
                     pos += size;
 
-                    let unit = if pos >= tokens.len() || tokens[pos] != Token::Punctuation(';') {
+                    let unit = if pos >= tokens.len() || tokens[pos] == Token::EOF {
                         // if the expression is in the end of the code block
                         ASTUnit::Statement(Statement::ImplicitReturn(Rc::new(expression)))
-                    } else {
+                    } else if tokens[pos] == Token::Punctuation(';') {
                         expression
+                    } else {
+                        return Err(self.error(
+                            tokens,
+                            pos,
+                            format!("expected `;` after expression, got: {:?}", tokens[pos]),
+                        ));
                     };
 
                     units.push(Rc::new(unit));
                 }
                 Token::Keyword(keyword) => {
-                    let keyword = self.parse_keyword(&keyword).unwrap();
+                    let keyword = self.parse_keyword(keyword).unwrap();
 
                     match keyword {
                         Keyword::Return => {
                             pos += 1;
 
-                            let expression = &tokens[pos..];
+                            let (expression, size) = self.parse_expression(tokens.slice(pos..))?;
 
-                            let (expression, size) = self.parse_expression(expression);
+                            if let Some(token) = tokens
+                                .get(pos + size)
+                                .filter(|token| **token != Token::Punctuation(';'))
+                            {
+                                return Err(self.error(
+                                    tokens,
+                                    pos + size,
+                                    format!(
+                                        "expected `;` after the returned value, got: {token:?}"
+                                    ),
+                                ));
+                            }
 
                             // expression size + ";"
                             pos += size + 1;
@@ -99,11 +283,63 @@ impl Parser {
                         Keyword::VariableDeclaration(keyword) => {
                             pos += 1;
 
-                            let identifier = match &tokens[pos] {
-                                Token::Identifier(identifier) => identifier,
-                                other => panic!("expected variable identifier, got: {other:?}"),
+                            // a variable, or a tuple pattern destructuring the value
+                            let (identifier, pattern) = match tokens.get(pos) {
+                                Some(Token::Identifier(identifier)) => (identifier.clone(), None),
+                                Some(Token::Punctuation('(')) => {
+                                    let end = self.closing(tokens, pos, '(')?;
+                                    let pattern =
+                                        self.parse_pattern(tokens.slice(pos..=(pos + end)))?;
+
+                                    // the pattern ends where an identifier would
+                                    pos += end;
+
+                                    (String::new(), Some(pattern))
+                                }
+                                other => {
+                                    return Err(self.error(
+                                        tokens,
+                                        pos,
+                                        format!("expected variable identifier, got: {other:?}"),
+                                    ))
+                                }
+                            };
+
+                            // optional ": type"
+                            let ty = if tokens.get(pos + 1) == Some(&Token::Punctuation(':')) {
+                                match tokens
+                                    .get_slice((pos + 2)..)
+                                    .and_then(|ty| self.parse_type(ty))
+                                {
+                                    Some((ty, size)) => {
+                                        pos += 1 + size;
+                                        Some(ty)
+                                    }
+                                    None => {
+                                        return Err(self.error(
+                                            tokens,
+                                            pos + 2,
+                                            format!(
+                                                "expected variable type, got: {:?}",
+                                                tokens.get(pos + 2)
+                                            ),
+                                        ))
+                                    }
+                                }
+                            } else {
+                                None
+                            };
+
+                            if tokens.get(pos + 1) != Some(&Token::Operator("=".to_string())) {
+                                return Err(self.error(
+                                    tokens,
+                                    pos + 1,
+                                    format!(
+                                        "expected `=` after the variable, got: {:?}",
+                                        tokens.get(pos + 1)
+                                    ),
+                                ));
                             }
-                            .clone();
 
                             // ident + "="
                             pos += 2;
@@ -113,137 +349,269 @@ impl Parser {
                             while expression_end < tokens.len()
                                 && tokens[expression_end] != Token::Punctuation(';')
                             {
-                                expression_end +=
-                                    if tokens[expression_end] == Token::Punctuation('{') {
-                                        traversal::traverse_till_root_par(
-                                            &tokens[expression_end..],
-                                            (Token::Punctuation('{'), Token::Punctuation('}')),
-                                        )
-                                        .map(|pos| pos + 1)
-                                        .unwrap()
-                                    } else {
-                                        1
-                                    };
+                                // blocks and repeat literals contain semicolons of their own
+                                expression_end += match tokens[expression_end] {
+                                    Token::Punctuation(open @ ('{' | '[')) => {
+                                        self.closing(tokens, expression_end, open)? + 1
+                                    }
+                                    _ => 1,
+                                };
                             }
 
-                            let expression = &tokens[pos..expression_end];
-
-                            let (expression, size) = self.parse_expression(expression);
+                            let (expression, size) =
+                                self.parse_expression(tokens.slice(pos..expression_end))?;
 
                             pos += size;
 
-                            units.push(Rc::new(ASTUnit::Declaration(
-                                Declaration::VariableDeclaration {
+                            if pos != expression_end {
+                                return Err(self.error(
+                                    tokens,
+                                    pos,
+                                    format!(
+                                        "expected `;` after the value of the variable, got: {:?}",
+                                        tokens[pos]
+                                    ),
+                                ));
+                            }
+
+                            let declaration = match pattern {
+                                Some(pattern) => Declaration::DestructuringDeclaration {
+                                    keyword,
+                                    pattern,
+                                    ty,
+                                    expression: Rc::new(expression),
+                                },
+                                None => Declaration::VariableDeclaration {
                                     keyword,
                                     identifier,
+                                    ty,
                                     expression: Rc::new(expression),
                                 },
-                            )));
+                            };
+
+                            units.push(Rc::new(ASTUnit::Declaration(declaration)));
                         }
                         Keyword::FunctionDeclaration => {
                             // fun keyword
                             pos += 1;
 
-                            let identifier = match tokens[pos] {
-                                Token::Identifier(ref ident) => ident.clone(),
-                                _ => panic!("expected function identifier"),
-                            };
-                            // ident
+                            let (identifier, parameters, variadic, return_type, size) =
+                                self.parse_signature(tokens.slice(pos..))?;
+                            pos += size;
+
+                            if variadic {
+                                return Err(self.error(
+                                    tokens,
+                                    start,
+                                    "only extern functions can be variadic".to_string(),
+                                ));
+                            }
+
+                            let block_end_offset =
+                                self.braces(tokens, pos, "the body of the function")?;
+
+                            let (expression, _) =
+                                self.run_internal(tokens.slice(pos..(pos + block_end_offset)))?;
+
+                            pos += block_end_offset;
+
+                            units.push(Rc::new(ASTUnit::Declaration(
+                                Declaration::FunctionDeclaration {
+                                    identifier,
+                                    parameters,
+                                    return_type,
+                                    expression: Rc::new(expression),
+                                    public: mem::take(&mut public),
+                                    export: None,
+                                },
+                            )));
+                        }
+                        Keyword::ExternFunctionDeclaration => {
+                            let (declaration, size) =
+                                self.parse_extern(tokens.slice(pos..), false)?;
+                            pos += size;
+
+                            units.push(Rc::new(ASTUnit::Declaration(declaration)));
+                        }
+                        Keyword::Public => {
+                            // pub keyword
                             pos += 1;
 
-                            let args_end_offset = traversal::traverse_till_root_par(
-                                &tokens[pos..],
-                                (Token::Punctuation('('), Token::Punctuation(')')),
-                            )
-                            .unwrap();
-                            // (
+                            match tokens.get(pos).and_then(Token::as_keyword) {
+                                Some(DECLARATION_EXTERN) => {
+                                    let (declaration, size) =
+                                        self.parse_extern(tokens.slice(pos..), true)?;
+                                    pos += size;
+
+                                    units.push(Rc::new(ASTUnit::Declaration(declaration)));
+                                }
+                                // the declaration is parsed next
+                                Some(
+                                    DECLARATION_FUNCTION | DECLARATION_STRUCT | DECLARATION_ENUM,
+                                ) => public = true,
+                                _ => {
+                                    return Err(self.error(
+                                        tokens,
+                                        pos,
+                                        format!(
+                                            "expected `fun`, `struct`, `enum` or `extern fun` after `pub`, got: {:?}",
+                                            tokens.get(pos)
+                                        ),
+                                    ))
+                                }
+                            }
+                        }
+                        Keyword::Import => {
+                            // import keyword
                             pos += 1;
 
-                            let parameters = if tokens[pos] == Token::Punctuation(')') {
-                                vec![]
-                            } else {
-                                tokens[pos..(pos + args_end_offset - 1)]
-                                    .split(|tok| tok.eq(&Token::Punctuation(',')))
-                                    .map(|param| (param[0].clone(), param[2].clone()))
-                                    .map(|(ident, ty)| {
-                                        (
-                                            match ident {
-                                                Token::Identifier(ident) => ident,
-                                                _ => panic!("expected identifier"),
-                                            },
-                                            match ty {
-                                                Token::Type(ty) => ty,
-                                                _ => panic!("expected type"),
-                                            },
-                                        )
-                                    })
-                                    .collect()
+                            // "a" "::" "b" up to ";"
+                            let mut path = vec![];
+                            loop {
+                                match &tokens[pos..] {
+                                    [Token::Identifier(module), Token::Punctuation(':'), Token::Punctuation(':'), ..] =>
+                                    {
+                                        path.push(module.clone());
+                                        pos += 3;
+                                    }
+                                    [Token::Identifier(module), Token::Punctuation(';'), ..] => {
+                                        path.push(module.clone());
+                                        pos += 2;
+                                        break;
+                                    }
+                                    other => {
+                                        return Err(self.error(
+                                            tokens,
+                                            pos,
+                                            format!(
+                                                "expected a module path after `import`, got: {:?}",
+                                                other.first()
+                                            ),
+                                        ))
+                                    }
+                                }
+                            }
+
+                            units.push(Rc::new(ASTUnit::Declaration(Declaration::Import { path })));
+                        }
+                        Keyword::TypeDeclaration => {
+                            // struct keyword
+                            pos += 1;
+
+                            let identifier = match tokens.get(pos) {
+                                Some(Token::Identifier(ident)) => ident.clone(),
+                                _ => {
+                                    return Err(self.error(
+                                        tokens,
+                                        pos,
+                                        "expected struct identifier".to_string(),
+                                    ))
+                                }
                             };
+                            // ident
+                            pos += 1;
 
-                            pos += args_end_offset;
+                            let fields_size =
+                                self.braces(tokens, pos, "the fields of the struct")?;
 
-                            // pos + "->".len()
-                            let return_type = if tokens[pos] == Token::Operator("-".to_string())
-                                && tokens[pos + 1] == Token::Operator(">".to_string())
-                            {
-                                // "->" + "type"
-                                match tokens[pos + 2] {
-                                    Token::Type(ref ty) => {
-                                        pos += 2 + 1;
-                                        ty.clone()
-                                    }
-                                    _ => panic!("expected return type"),
+                            let fields = self
+                                .split_arguments(tokens.slice((pos + 1)..(pos + fields_size - 1)))
+                                .into_iter()
+                                // a trailing comma leaves an empty field
+                                .filter(|field| !field.is_empty())
+                                .map(|field| self.parse_field(field))
+                                .collect::<Result<_, Diagnostic>>()?;
+
+                            // "{" ... "}"
+                            pos += fields_size;
+
+                            units.push(Rc::new(ASTUnit::Declaration(
+                                Declaration::TypeDeclaration {
+                                    identifier,
+                                    fields,
+                                    public: mem::take(&mut public),
+                                },
+                            )));
+                        }
+                        Keyword::EnumDeclaration => {
+                            // enum keyword
+                            pos += 1;
+
+                            let identifier = match tokens.get(pos) {
+                                Some(Token::Identifier(ident)) => ident.clone(),
+                                _ => {
+                                    return Err(self.error(
+                                        tokens,
+                                        pos,
+                                        "expected enum identifier".to_string(),
+                                    ))
                                 }
-                            } else {
-                                Type::Void
                             };
+                            // ident
+                            pos += 1;
 
-                            let block_end_offset = traversal::traverse_till_root_par(
-                                &tokens[pos..],
-                                (Token::Punctuation('{'), Token::Punctuation('}')),
-                            )
-                            .map(|pos| pos + 1)
-                            .unwrap();
+                            let variants_size =
+                                self.braces(tokens, pos, "the variants of the enum")?;
 
-                            let (expression, _) =
-                                self.run_internal(&tokens[pos..(pos + block_end_offset)]);
+                            let variants = self
+                                .split_arguments(tokens.slice((pos + 1)..(pos + variants_size - 1)))
+                                .into_iter()
+                                // a trailing comma leaves an empty variant
+                                .filter(|variant| !variant.is_empty())
+                                .map(|variant| self.parse_variant(variant))
+                                .collect::<Result<_, Diagnostic>>()?;
 
-                            pos += block_end_offset;
+                            // "{" ... "}"
+                            pos += variants_size;
 
                             units.push(Rc::new(ASTUnit::Declaration(
-                                Declaration::FunctionDeclaration {
+                                Declaration::EnumDeclaration {
                                     identifier,
-                                    parameters,
-                                    return_type,
-                                    expression: Rc::new(expression),
+                                    variants,
+                                    public: mem::take(&mut public),
                                 },
                             )));
                         }
+                        Keyword::Match => {
+                            pos += 1;
+                            let (statement, size) = self.parse_match(tokens.slice(pos..))?;
+                            pos += size;
+
+                            // like an expression, a match in the end of the code block is its value
+                            let unit = if pos >= tokens.len() {
+                                ASTUnit::Statement(Statement::ImplicitReturn(Rc::new(statement)))
+                            } else {
+                                statement
+                            };
+
+                            units.push(Rc::new(unit));
+                        }
                         Keyword::While => {
                             pos += 1;
 
-                            let condition = &tokens[pos..];
-                            let (condition, size) = self.parse_expression(condition);
+                            // the condition runs till the brace opening the body
+                            let size = self
+                                .find_top_level(&tokens[pos..], |token| {
+                                    token == &Token::Punctuation('{')
+                                })
+                                .ok_or_else(|| {
+                                    self.error(
+                                        tokens,
+                                        start,
+                                        "expected the body of the while loop".to_string(),
+                                    )
+                                })?;
+                            let (condition, _) =
+                                self.parse_expression(tokens.slice(pos..(pos + size)))?;
 
                             pos += size;
 
-                            let block = &tokens[pos..(pos
-                                + traversal::traverse_till_root_par(
-                                    &tokens[pos..],
-                                    (Token::Punctuation('{'), Token::Punctuation('}')),
-                                )
-                                .map(|pos| pos + 1)
-                                .unwrap_or(
-                                    tokens
-                                        .iter()
-                                        .position(|tok| tok == &Token::Punctuation('}'))
-                                        .map(|pos| pos + 1)
-                                        .unwrap(),
-                                ))];
+                            let block =
+                                tokens.slice(pos..(pos + self.closing(tokens, pos, '{')? + 1));
 
                             pos += block.len();
 
-                            let (block, _) = self.run_internal(block);
+                            let (block, _) = self.run_internal(block)?;
 
                             units.push(Rc::new(ASTUnit::Statement(Statement::Loop(
                                 LoopStatement::While {
@@ -254,23 +622,24 @@ impl Parser {
                         }
                         Keyword::ControlFlowIf => {
                             pos += 1;
-                            let (control_flow, size) = self.parse_control_flow(&tokens[pos..]);
+                            let (control_flow, size) =
+                                self.parse_control_flow(tokens.slice(pos..))?;
                             pos += size;
                             units.push(Rc::new(control_flow));
                         }
-                        Keyword::ControlFlowElse => unreachable!(),
+                        Keyword::ControlFlowElse => {
+                            return Err(self.error(
+                                tokens,
+                                pos,
+                                "`else` without an `if` before it".to_string(),
+                            ))
+                        }
                     };
                 }
                 Token::Punctuation('{') => {
                     let (unit, size) = self.parse_expression(
-                        &tokens[pos..(pos
-                            + traversal::traverse_till_root_par(
-                                &tokens[pos..],
-                                (Token::Punctuation('{'), Token::Punctuation('}')),
-                            )
-                            .map(|pos| pos + 1)
-                            .unwrap())],
-                    );
+                        tokens.slice(pos..(pos + self.closing(tokens, pos, '{')? + 1)),
+                    )?;
 
                     pos += size;
 
@@ -278,48 +647,35 @@ impl Parser {
                 }
                 _ => pos += 1,
             }
+
+            if units.len() > units_before {
+                if let Some(span) = self.span_of(tokens, start) {
+                    units.insert(units_before, Rc::new(ASTUnit::Location(span)));
+                }
+            }
         }
 
-        (ASTUnit::Block(units), pos + offset)
+        Ok((ASTUnit::Block(units), pos + offset))
     }
 
-    fn parse_control_flow(&self, tokens: &[Token]) -> (ASTUnit, usize) {
+    fn parse_control_flow(&self, tokens: TokenSlice) -> Result<(ASTUnit, usize), Diagnostic> {
         let mut offset = 0;
 
         let mut end = 0;
-        let mut condition_end = 0;
 
         while end < tokens.len() {
             if tokens[end] == Token::Keyword("else".to_string())
-                && tokens[end + 1] != Token::Keyword("if".to_string())
+                && tokens.get(end + 1) != Some(&Token::Keyword("if".to_string()))
             {
                 break;
             }
 
-            let prev_end = end;
-
             end += traversal::traverse_till_root_par(
                 &tokens[end..],
                 (Token::Punctuation('{'), Token::Punctuation('}')),
             )
             .map(|pos| pos + 1)
             .unwrap_or(tokens.len() - end);
-
-            if end < tokens.len()
-                && tokens[end] == Token::Keyword("else".to_string())
-                && condition_end == 0
-            {
-                condition_end = tokens[prev_end..]
-                    .iter()
-                    .position(|token| {
-                        token
-                            .as_punctuation()
-                            .map(|token| token == '{')
-                            .unwrap_or(false)
-                    })
-                    .map(|pos| prev_end + pos)
-                    .unwrap_or(end);
-            }
         }
 
         if end < tokens.len() && tokens[end] == Token::Keyword("else".to_string()) {
@@ -331,20 +687,32 @@ impl Parser {
             .unwrap_or(tokens.len() - end);
         }
 
-        let (condition, size) = self.parse_expression(&tokens[..condition_end]);
+        // the condition runs till the brace opening the body
+        let condition_end = self
+            .find_top_level(&tokens, |token| token == &Token::Punctuation('{'))
+            .ok_or_else(|| {
+                self.error(
+                    tokens,
+                    0,
+                    "expected the body of the if statement".to_string(),
+                )
+            })?;
+        let (condition, size) = self.parse_expression(tokens.slice(..condition_end))?;
 
         offset += size;
 
-        let block = &tokens[offset
-            ..traversal::traverse_till_root_par(
-                &tokens[offset..],
-                (Token::Punctuation('{'), Token::Punctuation('}')),
-            )
-            .map(|pos| offset + pos + 1)
-            .unwrap_or(tokens.len())
-            .min(end)];
+        let block = tokens.slice(
+            offset
+                ..traversal::traverse_till_root_par(
+                    &tokens[offset..],
+                    (Token::Punctuation('{'), Token::Punctuation('}')),
+                )
+                .map(|pos| offset + pos + 1)
+                .unwrap_or(tokens.len())
+                .min(end),
+        );
 
-        let (block, size) = self.run_internal(block);
+        let (block, size) = self.run_internal(block)?;
 
         offset += size;
 
@@ -352,7 +720,7 @@ impl Parser {
             if tokens[offset] == Token::Keyword("else".to_string()) {
                 offset += 1;
 
-                let (alternative, size) = self.run_internal(&tokens[offset..end]);
+                let (alternative, size) = self.run_internal(tokens.slice(offset..end))?;
 
                 offset += size;
                 Some(Rc::new(alternative))
@@ -363,91 +731,486 @@ impl Parser {
             None
         };
 
-        (
+        Ok((
             ASTUnit::Statement(Statement::ControlFlow {
                 condition: Rc::new(condition),
                 execute: Rc::new(block),
                 alternative,
             }),
             offset,
-        )
+        ))
     }
 
-    fn parse_expression(&self, expression: &[Token]) -> (ASTUnit, usize) {
-        if expression.len() == 0 {
-            return (
+    // everything after the match keyword, up to the closing brace of the arms
+    fn parse_match(&self, tokens: TokenSlice) -> Result<(ASTUnit, usize), Diagnostic> {
+        let arms_start = self
+            .find_top_level(&tokens, |token| token == &Token::Punctuation('{'))
+            .ok_or_else(|| self.error(tokens, 0, "expected match arms".to_string()))?;
+        let arms_end = arms_start + self.closing(tokens, arms_start, '{')?;
+
+        let (scrutinee, _) = self.parse_expression(tokens.slice(..arms_start))?;
+
+        let arms_tokens = tokens.slice((arms_start + 1)..arms_end);
+        let mut arms = vec![];
+        let mut pos = 0;
+
+        while pos < arms_tokens.len() {
+            let rest = arms_tokens.slice(pos..);
+
+            let arrow = self
+                .find_top_level(&rest, |token| token == &Token::Operator("=".to_string()))
+                .filter(|&arrow| rest.get(arrow + 1) == Some(&Token::Operator(">".to_string())))
+                .ok_or_else(|| {
+                    self.error(rest, 0, "expected `=>` after match pattern".to_string())
+                })?;
+
+            let guard_start = self.find_top_level(&rest[..arrow], |token| {
+                token == &Token::Keyword(STATEMENT_IF.to_string())
+            });
+
+            let pattern = self.parse_pattern(rest.slice(..guard_start.unwrap_or(arrow)))?;
+            let guard = guard_start
+                .map(|start| self.parse_expression(rest.slice((start + 1)..arrow)))
+                .transpose()?
+                .map(|(guard, _)| Rc::new(guard));
+
+            // pattern + "=>"
+            let body_start = arrow + 2;
+
+            let (body, body_end) = if rest.get(body_start) == Some(&Token::Punctuation('{')) {
+                let body_end = body_start + self.closing(rest, body_start, '{')? + 1;
+
+                (
+                    self.run_internal(rest.slice(body_start..body_end))?.0,
+                    body_end,
+                )
+            } else {
+                let body_end = body_start
+                    + self
+                        .find_top_level(rest.get(body_start..).unwrap_or_default(), |token| {
+                            token == &Token::Punctuation(',')
+                        })
+                        .unwrap_or(rest.len().saturating_sub(body_start));
+
+                (
+                    self.parse_expression(rest.slice(body_start..body_end))?.0,
+                    body_end,
+                )
+            };
+
+            arms.push(MatchArm {
+                pattern,
+                guard,
+                body: Rc::new(body),
+            });
+
+            pos += body_end;
+
+            // the comma after an arm is optional after a block and after the last arm
+            if arms_tokens.get(pos) == Some(&Token::Punctuation(',')) {
+                pos += 1;
+            }
+        }
+
+        Ok((
+            ASTUnit::Statement(Statement::Match {
+                scrutinee: Rc::new(scrutinee),
+                arms,
+            }),
+            arms_end + 1,
+        ))
+    }
+
+    fn parse_pattern(&self, tokens: TokenSlice) -> Result<Pattern, Diagnostic> {
+        let pattern = match &*tokens {
+            [Token::Identifier(name)] if name == "_" => Pattern::Wildcard,
+            [Token::Identifier(name)] => Pattern::Binding(name.clone()),
+            [Token::Literal(literal)] => Pattern::Literal(Literal::from_literal_token(literal)),
+            [Token::Punctuation('('), .., Token::Punctuation(')')] => Pattern::Tuple(
+                self.split_arguments(tokens.slice(1..(tokens.len() - 1)))
+                    .into_iter()
+                    // a trailing comma leaves an empty element
+                    .filter(|element| !element.is_empty())
+                    .map(|element| self.parse_pattern(element))
+                    .collect::<Result<_, Diagnostic>>()?,
+            ),
+            [Token::Identifier(_), Token::Punctuation(':'), Token::Punctuation(':'), Token::Identifier(_), ..] =>
+            {
+                let (mut segments, path) = self.parse_path(&tokens);
+                let variant = segments.pop().unwrap();
+                let enumeration = segments.join("::");
+
+                let rest = tokens.slice(path..);
+                let fields = match rest.first() {
+                    None => vec![],
+                    Some(Token::Punctuation('(')) => self
+                        .split_arguments(rest.slice(1..(rest.len() - 1)))
+                        .into_iter()
+                        .filter(|field| !field.is_empty())
+                        .enumerate()
+                        .map(|(idx, field)| Ok((idx.to_string(), self.parse_pattern(field)?)))
+                        .collect::<Result<_, Diagnostic>>()?,
+                    Some(Token::Punctuation('{')) => self
+                        .split_arguments(rest.slice(1..(rest.len() - 1)))
+                        .into_iter()
+                        .filter(|field| !field.is_empty())
+                        .map(|field| match &*field {
+                            // "name" is a shorthand for "name: name"
+                            [Token::Identifier(name)] => {
+                                Ok((name.clone(), Pattern::Binding(name.clone())))
+                            }
+                            [Token::Identifier(name), Token::Punctuation(':'), ..] => {
+                                Ok((name.clone(), self.parse_pattern(field.slice(2..))?))
+                            }
+                            other => Err(self.error(
+                                field,
+                                0,
+                                format!("expected field pattern, got: {other:?}"),
+                            )),
+                        })
+                        .collect::<Result<_, Diagnostic>>()?,
+                    other => {
+                        return Err(self.error(
+                            rest,
+                            0,
+                            format!("expected variant fields, got: {other:?}"),
+                        ))
+                    }
+                };
+
+                Pattern::Variant {
+                    enumeration,
+                    variant,
+                    fields,
+                }
+            }
+            other => {
+                return Err(self.error(tokens, 0, format!("expected pattern, got: {other:?}")))
+            }
+        };
+
+        Ok(pattern)
+    }
+
+    fn parse_variant(&self, tokens: TokenSlice) -> Result<Variant, Diagnostic> {
+        let name = match &tokens[0] {
+            Token::Identifier(name) => name.clone(),
+            other => {
+                return Err(self.error(
+                    tokens,
+                    0,
+                    format!("expected variant identifier, got: {other:?}"),
+                ))
+            }
+        };
+
+        let (kind, fields) = match tokens.get(1) {
+            None => (VariantKind::Unit, vec![]),
+            Some(Token::Punctuation('(')) => (
+                VariantKind::Tuple,
+                self.split_arguments(tokens.slice(2..(tokens.len() - 1)))
+                    .into_iter()
+                    .filter(|field| !field.is_empty())
+                    .enumerate()
+                    .map(|(idx, field)| match self.parse_type(field) {
+                        Some((ty, _)) => Ok((idx.to_string(), ty)),
+                        None => Err(self.error(field, 0, "expected field type".to_string())),
+                    })
+                    .collect::<Result<_, Diagnostic>>()?,
+            ),
+            Some(Token::Punctuation('{')) => (
+                VariantKind::Struct,
+                self.split_arguments(tokens.slice(2..(tokens.len() - 1)))
+                    .into_iter()
+                    .filter(|field| !field.is_empty())
+                    .map(|field| self.parse_field(field))
+                    .collect::<Result<_, Diagnostic>>()?,
+            ),
+            Some(other) => {
+                return Err(self.error(
+                    tokens,
+                    1,
+                    format!("expected variant fields, got: {other:?}"),
+                ))
+            }
+        };
+
+        Ok(Variant { name, kind, fields })
+    }
+
+    // "name" + ":" + type
+    fn parse_field(&self, field: TokenSlice) -> Result<(String, Type), Diagnostic> {
+        let name = match &field[0] {
+            Token::Identifier(name) => name.clone(),
+            _ => return Err(self.error(field, 0, "expected field identifier".to_string())),
+        };
+
+        match field.get_slice(2..).and_then(|ty| self.parse_type(ty)) {
+            Some((ty, _)) => Ok((name, ty)),
+            None => Err(self.error(field, 2, "expected field type".to_string())),
+        }
+    }
+
+    fn parse_expression(&self, expression: TokenSlice) -> Result<(ASTUnit, usize), Diagnostic> {
+        if expression.is_empty() {
+            return Ok((
                 ASTUnit::Expression(Expression::Literal(Literal::Int32(0))),
                 0,
-            );
+            ));
         }
 
         let mut size = 0;
 
+        // parentheses around a comma hold a tuple instead
         let expression = if expression[0] == Token::Punctuation('(')
-            && expression.last().unwrap() == &Token::Punctuation(')')
+            && traversal::traverse_till_root_par(
+                &expression,
+                (Token::Punctuation('('), Token::Punctuation(')')),
+            ) == Some(expression.len() - 1)
+            && self
+                .find_top_level(&expression[1..(expression.len() - 1)], |token| {
+                    token == &Token::Punctuation(',')
+                })
+                .is_none()
         {
             size += 2;
-            &expression[1..(expression.len() - 1)]
+            expression.slice(1..(expression.len() - 1))
         } else {
             expression
         };
 
-        if let Some(structure) = self.recognize_structure(expression) {
+        if let Some(structure) = self.recognize_structure(&expression) {
             match structure {
                 RecognizableStructure::Block((start, end)) => {
-                    let (unit, size) = self.run_internal(&expression[(start + 1)..(end - 1)]);
-                    return (unit, size + 2);
+                    let (unit, size) =
+                        self.run_internal(expression.slice((start + 1)..(end - 1)))?;
+                    return Ok((unit, size + 2));
                 }
                 RecognizableStructure::FunctionInvokation((start, end)) => {
                     let identifier = expression[start].as_identifier().unwrap().to_string();
-                    let params: Vec<Rc<ASTUnit>> = expression[(start + 1)..end]
-                        .split(|tok| tok == &Token::Punctuation(','))
-                        .map(|expr| self.parse_expression(expr))
-                        .map(|(expr, _)| Rc::new(expr))
-                        .collect();
+                    // identifier + "(" ... ")"
+                    let arguments = expression.slice((start + 2)..(end - 1));
+                    let params: Vec<Rc<ASTUnit>> = if arguments.is_empty() {
+                        vec![]
+                    } else {
+                        self.split_arguments(arguments)
+                            .into_iter()
+                            .map(|expr| Ok(Rc::new(self.parse_expression(expr)?.0)))
+                            .collect::<Result<_, Diagnostic>>()?
+                    };
 
-                    return (
+                    return Ok((
                         ASTUnit::Expression(Expression::FunctionInvokation {
                             function_name: identifier,
                             parameters: params,
                         }),
-                        end - start,
-                    );
+                        size + end - start,
+                    ));
+                }
+                RecognizableStructure::Index((start, end)) => {
+                    let (target, _) =
+                        self.parse_expression(expression.slice(start..(start + 1)))?;
+                    // identifier + "[" ... "]"
+                    let index =
+                        self.parse_index(target, expression.slice((start + 2)..(end - 1)))?;
+
+                    return Ok((ASTUnit::Expression(index), size + end - start));
+                }
+
+                RecognizableStructure::Tuple((start, end)) => {
+                    // "(" ... ")"
+                    let elements = self
+                        .split_arguments(expression.slice((start + 1)..(end - 1)))
+                        .into_iter()
+                        // a trailing comma leaves an empty element
+                        .filter(|element| !element.is_empty())
+                        .map(|element| Ok(Rc::new(self.parse_expression(element)?.0)))
+                        .collect::<Result<_, Diagnostic>>()?;
+
+                    return Ok((
+                        ASTUnit::Expression(Expression::Tuple(elements)),
+                        size + end - start,
+                    ));
+                }
+                RecognizableStructure::Array((start, end)) => {
+                    // "[" ... "]"
+                    let elements = expression.slice((start + 1)..(end - 1));
+
+                    let unit = match self
+                        .find_top_level(&elements, |token| token == &Token::Punctuation(';'))
+                    {
+                        Some(semicolon) => {
+                            let (value, _) = self.parse_expression(elements.slice(..semicolon))?;
+                            let count = match elements.get(semicolon + 1) {
+                                Some(Token::Literal(token::Literal::Number(number))) => {
+                                    number.parse().map_err(|_| {
+                                        self.error(
+                                            elements,
+                                            semicolon + 1,
+                                            format!("expected array length, got: {number}"),
+                                        )
+                                    })?
+                                }
+                                other => {
+                                    return Err(self.error(
+                                        elements,
+                                        semicolon + 1,
+                                        format!("expected array length, got: {other:?}"),
+                                    ))
+                                }
+                            };
+
+                            Expression::ArrayRepeat {
+                                value: Rc::new(value),
+                                count,
+                            }
+                        }
+                        None => Expression::Array(
+                            self.split_arguments(elements)
+                                .into_iter()
+                                // a trailing comma leaves an empty element
+                                .filter(|element| !element.is_empty())
+                                .map(|element| Ok(Rc::new(self.parse_expression(element)?.0)))
+                                .collect::<Result<_, Diagnostic>>()?,
+                        ),
+                    };
+
+                    return Ok((ASTUnit::Expression(unit), size + end - start));
+                }
+                RecognizableStructure::Struct((start, end)) => {
+                    let identifier = expression[start].as_identifier().unwrap().to_string();
+                    // identifier + "{" ... "}"
+                    let fields = self
+                        .split_arguments(expression.slice((start + 2)..(end - 1)))
+                        .into_iter()
+                        .filter(|field| !field.is_empty())
+                        .map(|field| {
+                            let name = match &field[0] {
+                                Token::Identifier(name) => name.clone(),
+                                other => {
+                                    return Err(self.error(
+                                        field,
+                                        0,
+                                        format!("expected field identifier, got: {other:?}"),
+                                    ))
+                                }
+                            };
+                            // name + ":"
+                            let (value, _) =
+                                self.parse_expression(field.get_slice(2..).unwrap_or_default())?;
+
+                            Ok((name, Rc::new(value)))
+                        })
+                        .collect::<Result<_, Diagnostic>>()?;
+
+                    return Ok((
+                        ASTUnit::Expression(Expression::Struct { identifier, fields }),
+                        size + end - start,
+                    ));
                 }
+                RecognizableStructure::Variant((start, end)) => {
+                    // the last segment names the variant, the ones before it the enumeration
+                    let (mut segments, path) = self.parse_path(&expression[start..]);
+                    let variant = segments.pop().unwrap();
+                    let enumeration = segments.join("::");
+
+                    // variant + "(" ... ")" or variant + "{" ... "}"
+                    let fields = match expression.get(start + path) {
+                        Some(Token::Punctuation('(')) => self
+                            .split_arguments(expression.slice((start + path + 1)..(end - 1)))
+                            .into_iter()
+                            .filter(|field| !field.is_empty())
+                            .enumerate()
+                            .map(|(idx, field)| {
+                                Ok((idx.to_string(), Rc::new(self.parse_expression(field)?.0)))
+                            })
+                            .collect::<Result<_, Diagnostic>>()?,
+                        Some(Token::Punctuation('{')) => self
+                            .split_arguments(expression.slice((start + path + 1)..(end - 1)))
+                            .into_iter()
+                            .filter(|field| !field.is_empty())
+                            .map(|field| {
+                                let name = match &field[0] {
+                                    Token::Identifier(name) => name.clone(),
+                                    other => {
+                                        return Err(self.error(
+                                            field,
+                                            0,
+                                            format!("expected field identifier, got: {other:?}"),
+                                        ))
+                                    }
+                                };
+                                // name + ":"
+                                let (value, _) = self
+                                    .parse_expression(field.get_slice(2..).unwrap_or_default())?;
+
+                                Ok((name, Rc::new(value)))
+                            })
+                            .collect::<Result<_, Diagnostic>>()?,
+                        _ => vec![],
+                    };
 
+                    return Ok((
+                        ASTUnit::Expression(Expression::Variant {
+                            enumeration,
+                            variant,
+                            fields,
+                        }),
+                        size + end - start,
+                    ));
+                }
                 RecognizableStructure::Statement((start, end)) => {
-                    let (unit, size) = self.run_internal(&expression[start..end]);
-                    return (unit, size);
+                    return self.run_internal(expression.slice(start..end));
                 }
             }
         }
 
         let mut parentheses_count = 0;
+        let mut brackets_count = 0;
         let mut braces_count = 0;
         let mut semicolon_count = 0;
 
         let mut lowest_precedence: Option<(usize, Operation)> = None;
+        let mut cast: Option<usize> = None;
+        let mut field_access: Option<usize> = None;
+        let mut index_access: Option<usize> = None;
 
         for idx in 0..(expression.len() - 1) {
-            if (mem::discriminant(&expression[idx])
-                == mem::discriminant(&Token::Literal(lexer::lexer::token::Literal::Number(
-                    "0".to_string(),
-                )))
-                || mem::discriminant(&expression[idx])
-                    == mem::discriminant(&Token::Identifier("".to_string())))
-                && mem::discriminant(&expression[idx + 1])
-                    != mem::discriminant(&Token::Operator("+".to_string()))
+            // an operand that is not followed by an operator, a cast,
+            // a field access, an invokation or an index ends the expression
+            if matches!(expression[idx], Token::Literal(_) | Token::Identifier(_))
+                && !self.continues_expression(Some(&expression[idx + 1]))
+                && !matches!(
+                    expression[idx + 1],
+                    Token::Punctuation('(') | Token::Punctuation('[') | Token::Punctuation(':')
+                )
+                && parentheses_count == 0
+                && brackets_count == 0
+                && braces_count == 0
             {
                 break;
             }
 
             match &expression[idx] {
                 Token::Operator(op) => {
-                    if parentheses_count != 0 || braces_count != 0 || semicolon_count != 0 {
+                    if parentheses_count != 0
+                        || brackets_count != 0
+                        || braces_count != 0
+                        || semicolon_count != 0
+                    {
+                        continue;
+                    }
+
+                    // a dereference or an address without a left operand is a prefix one
+                    if (op == "*" || op == "&")
+                        && (idx == 0 || matches!(expression[idx - 1], Token::Operator(_)))
+                    {
                         continue;
                     }
 
-                    let operation = Operation::from_str(op).unwrap();
+                    let operation = op.parse::<Operation>().map_err(|_| {
+                        self.error(expression, idx, format!("unknown operator `{op}`"))
+                    })?;
                     if lowest_precedence.is_none()
                         || lowest_precedence.as_ref().unwrap().1.gt(&operation)
                     {
@@ -460,14 +1223,61 @@ impl Parser {
                 Token::Punctuation(')') => {
                     parentheses_count -= 1;
                 }
+                Token::Punctuation('[') => {
+                    // an index of a postfix expression, not an array literal
+                    if idx > 0
+                        && matches!(
+                            expression[idx - 1],
+                            Token::Identifier(_)
+                                | Token::Literal(_)
+                                | Token::Punctuation(')' | ']' | '}')
+                        )
+                        && parentheses_count == 0
+                        && brackets_count == 0
+                        && braces_count == 0
+                        && semicolon_count == 0
+                    {
+                        // the last access is the outermost one
+                        index_access = Some(idx);
+                    }
+
+                    brackets_count += 1;
+                }
+                Token::Punctuation(']') => {
+                    brackets_count -= 1;
+                }
                 Token::Punctuation('{') => {
                     braces_count += 1;
                 }
                 Token::Punctuation('}') => {
                     braces_count -= 1;
                 }
+                Token::Keyword(keyword) if keyword == EXPRESSION_CAST => {
+                    if parentheses_count != 0
+                        || brackets_count != 0
+                        || braces_count != 0
+                        || semicolon_count != 0
+                    {
+                        continue;
+                    }
+
+                    // casts are left-associative, the last one is applied first
+                    cast = Some(idx);
+                }
+                Token::Punctuation('.') => {
+                    if parentheses_count != 0
+                        || brackets_count != 0
+                        || braces_count != 0
+                        || semicolon_count != 0
+                    {
+                        continue;
+                    }
+
+                    // the last access is the outermost one
+                    field_access = Some(idx);
+                }
                 Token::Punctuation(';') => {
-                    if parentheses_count != 0 || braces_count != 0 {
+                    if parentheses_count != 0 || brackets_count != 0 || braces_count != 0 {
                         continue;
                     }
                     semicolon_count += 1;
@@ -477,38 +1287,152 @@ impl Parser {
         }
 
         if let Some((idx, lowest)) = lowest_precedence {
-            let (left, right) = expression.split_at(idx);
+            let left = expression.slice(..idx);
             // ignore lowest operator
-            let right = &right[1..];
+            let right = expression.slice((idx + 1)..);
 
-            let (left, left_size) = self.parse_expression(left);
-            let (right, right_size) = self.parse_expression(right);
+            let (left, left_size) = self.parse_expression(left)?;
+            let (right, right_size) = self.parse_expression(right)?;
 
             // left + right + operator
             size += left_size + right_size + 1;
 
-            (
+            Ok((
                 ASTUnit::Expression(Expression::BinaryExpression {
                     left: Rc::new(left),
                     right: Rc::new(right),
                     operation: lowest,
                 }),
                 size,
-            )
+            ))
+        } else if let Some(idx) = cast {
+            let ty = match expression.get(idx + 1) {
+                Some(Token::Type(ty)) => ty.clone(),
+                other => {
+                    return Err(self.error(
+                        expression,
+                        idx + 1,
+                        format!("expected cast type, got: {other:?}"),
+                    ))
+                }
+            };
+
+            let (operand, operand_size) = self.parse_expression(expression.slice(..idx))?;
+
+            // operand + "as" + type
+            size += operand_size + 2;
+
+            Ok((
+                ASTUnit::Expression(Expression::Cast {
+                    expression: Rc::new(operand),
+                    ty,
+                }),
+                size,
+            ))
+        } else if let Some(unary) = self.parse_unary(&expression) {
+            let (operand, operand_size) = self.parse_expression(expression.slice(1..))?;
+
+            // operator + operand
+            size += operand_size + 1;
+
+            Ok((
+                ASTUnit::Expression(match unary {
+                    UnaryOperator::AddressOf => Expression::AddressOf(Rc::new(operand)),
+                    UnaryOperator::Deref => Expression::Deref(Rc::new(operand)),
+                }),
+                size,
+            ))
+        } else if let Some(idx) = index_access.filter(|idx| Some(*idx) > field_access) {
+            let end = idx + self.closing(expression, idx, '[')?;
+
+            let (target, target_size) = self.parse_expression(expression.slice(..idx))?;
+            let index = self.parse_index(target, expression.slice((idx + 1)..end))?;
+
+            // target + "[" ... "]"
+            size += target_size + end - idx + 1;
+
+            Ok((ASTUnit::Expression(index), size))
+        } else if let Some(idx) = field_access {
+            // `target.name(arguments)` calls `name` with the target in front
+            if expression.get(idx + 2) == Some(&Token::Punctuation('(')) {
+                let function_name = match &expression[idx + 1] {
+                    Token::Identifier(name) => name.clone(),
+                    other => {
+                        return Err(self.error(
+                            expression,
+                            idx + 1,
+                            format!("expected method identifier, got: {other:?}"),
+                        ))
+                    }
+                };
+
+                let end = idx + 2 + self.closing(expression, idx + 2, '(')?;
+
+                let (target, target_size) = self.parse_expression(expression.slice(..idx))?;
+
+                let arguments = expression.slice((idx + 3)..end);
+                let mut parameters = vec![Rc::new(target)];
+
+                if !arguments.is_empty() {
+                    for argument in self.split_arguments(arguments) {
+                        parameters.push(Rc::new(self.parse_expression(argument)?.0));
+                    }
+                }
+
+                // target + "." + name + "(" ... ")"
+                size += target_size + end - idx + 1;
+
+                return Ok((
+                    ASTUnit::Expression(Expression::FunctionInvokation {
+                        function_name,
+                        parameters,
+                    }),
+                    size,
+                ));
+            }
+
+            let field = match expression.get(idx + 1) {
+                Some(Token::Identifier(field)) => field.clone(),
+                // tuple elements
+                Some(Token::Literal(token::Literal::Number(number))) => number.clone(),
+                other => {
+                    return Err(self.error(
+                        expression,
+                        idx + 1,
+                        format!("expected field identifier, got: {other:?}"),
+                    ))
+                }
+            };
+
+            let (target, target_size) = self.parse_expression(expression.slice(..idx))?;
+
+            // target + "." + field
+            size += target_size + 2;
+
+            Ok((
+                ASTUnit::Expression(Expression::FieldAccess {
+                    target: Rc::new(target),
+                    field,
+                }),
+                size,
+            ))
         } else {
             let literal_or_ident = expression
                 .iter()
-                .position(|tok| match tok {
-                    Token::Literal(_) | Token::Identifier(_) => true,
-                    _ => false,
-                })
-                .unwrap();
+                .position(|tok| matches!(tok, Token::Literal(_) | Token::Identifier(_)))
+                .ok_or_else(|| {
+                    self.error(
+                        expression,
+                        0,
+                        format!("expected expression, got: {:?}", &*expression),
+                    )
+                })?;
 
             size += literal_or_ident + 1;
 
             let literal_or_ident = &expression[literal_or_ident];
 
-            (
+            Ok((
                 ASTUnit::Expression(match literal_or_ident {
                     Token::Literal(literal) => {
                         Expression::Literal(Literal::from_literal_token(literal))
@@ -517,8 +1441,50 @@ impl Parser {
                     _ => unreachable!(),
                 }),
                 size,
-            )
+            ))
+        }
+    }
+
+    // what is between the brackets of `target[...]`, an index or a range of a slice
+    fn parse_index(&self, target: ASTUnit, inside: TokenSlice) -> Result<Expression, Diagnostic> {
+        // the first of two dots in a row, other ones access fields
+        let mut range = None;
+        let mut from = 0;
+
+        while let Some(dot) =
+            self.find_top_level(&inside[from..], |token| token == &Token::Punctuation('.'))
+        {
+            if inside.get(from + dot + 1) == Some(&Token::Punctuation('.')) {
+                range = Some(from + dot);
+                break;
+            }
+
+            from += dot + 1;
         }
+
+        let Some(dot) = range else {
+            let (index, _) = self.parse_expression(inside)?;
+
+            return Ok(Expression::Index {
+                target: Rc::new(target),
+                index: Rc::new(index),
+            });
+        };
+
+        // both bounds can be left out, the slice starts at 0 and ends at the length
+        let bound = |tokens: TokenSlice| -> Result<Option<Rc<ASTUnit>>, Diagnostic> {
+            if tokens.is_empty() {
+                return Ok(None);
+            }
+
+            Ok(Some(Rc::new(self.parse_expression(tokens)?.0)))
+        };
+
+        Ok(Expression::Slice {
+            target: Rc::new(target),
+            start: bound(inside.slice(..dot))?,
+            end: bound(inside.slice((dot + 2)..))?,
+        })
     }
 
     fn recognize_structure(&self, input: &[Token]) -> Option<RecognizableStructure> {
@@ -527,33 +1493,131 @@ impl Parser {
                 input,
                 (Token::Punctuation('{'), Token::Punctuation('}')),
             )
-            .map(|pos| pos + 1);
-
-            if end.is_none() {
-                return None;
-            }
-
-            let end = end.unwrap();
+            .map(|pos| pos + 1)?;
 
             Some(RecognizableStructure::Block((0, end)))
         } else if mem::discriminant(&input[0])
             == mem::discriminant(&Token::Identifier("".to_string()))
             && input.len() > 1
-            && &input[1] == &Token::Punctuation('(')
+            && input[1] == Token::Punctuation('(')
         {
             let end = traversal::traverse_till_root_par(
                 input,
                 (Token::Punctuation('('), Token::Punctuation(')')),
             )
-            .map(|pos| pos + 1);
+            .map(|pos| pos + 1)?;
 
-            if end.is_none() {
+            // the invokation is only an operand of a larger expression
+            if self.continues_expression(input.get(end)) {
                 return None;
             }
 
-            let end = end.unwrap();
-
             Some(RecognizableStructure::FunctionInvokation((0, end)))
+        } else if matches!(input[0], Token::Identifier(_) | Token::Literal(_))
+            && input.len() > 1
+            && input[1] == Token::Punctuation('[')
+        {
+            let end = traversal::traverse_till_root_par(
+                input,
+                (Token::Punctuation('['), Token::Punctuation(']')),
+            )
+            .map(|pos| pos + 1)?;
+
+            if self.continues_expression(input.get(end)) {
+                return None;
+            }
+
+            Some(RecognizableStructure::Index((0, end)))
+        } else if input[0] == Token::Punctuation('(') {
+            let end = traversal::traverse_till_root_par(
+                input,
+                (Token::Punctuation('('), Token::Punctuation(')')),
+            )
+            .map(|pos| pos + 1)?;
+
+            // only parentheses around a comma make a tuple
+            if self.continues_expression(input.get(end))
+                || self
+                    .find_top_level(&input[1..(end - 1)], |token| {
+                        token == &Token::Punctuation(',')
+                    })
+                    .is_none()
+            {
+                return None;
+            }
+
+            Some(RecognizableStructure::Tuple((0, end)))
+        } else if input[0] == Token::Punctuation('[') {
+            let end = traversal::traverse_till_root_par(
+                input,
+                (Token::Punctuation('['), Token::Punctuation(']')),
+            )
+            .map(|pos| pos + 1)?;
+
+            if self.continues_expression(input.get(end)) {
+                return None;
+            }
+
+            Some(RecognizableStructure::Array((0, end)))
+        } else if matches!(input[0], Token::Identifier(_))
+            && input.get(1) == Some(&Token::Punctuation('{'))
+            && matches!(input.get(2), Some(Token::Identifier(_)))
+            && input.get(3) == Some(&Token::Punctuation(':'))
+        {
+            // blocks never start with "ident:", so this can't be a condition followed by a body
+            let end = traversal::traverse_till_root_par(
+                input,
+                (Token::Punctuation('{'), Token::Punctuation('}')),
+            )
+            .map(|pos| pos + 1)?;
+
+            if self.continues_expression(input.get(end)) {
+                return None;
+            }
+
+            Some(RecognizableStructure::Struct((0, end)))
+        } else if matches!(input[0], Token::Identifier(_))
+            && input.get(1) == Some(&Token::Punctuation(':'))
+            && input.get(2) == Some(&Token::Punctuation(':'))
+            && matches!(input.get(3), Some(Token::Identifier(_)))
+        {
+            let (_, path) = self.parse_path(input);
+
+            let end = match input.get(path) {
+                Some(Token::Punctuation('(')) => traversal::traverse_till_root_par(
+                    &input[path..],
+                    (Token::Punctuation('('), Token::Punctuation(')')),
+                )
+                .map(|pos| path + pos + 1)?,
+                // same as struct literals, "{" only opens fields when followed by "ident:"
+                Some(Token::Punctuation('{'))
+                    if matches!(input.get(path + 1), Some(Token::Identifier(_)))
+                        && input.get(path + 2) == Some(&Token::Punctuation(':')) =>
+                {
+                    traversal::traverse_till_root_par(
+                        &input[path..],
+                        (Token::Punctuation('{'), Token::Punctuation('}')),
+                    )
+                    .map(|pos| path + pos + 1)?
+                }
+                _ => path,
+            };
+
+            if self.continues_expression(input.get(end)) {
+                return None;
+            }
+
+            Some(RecognizableStructure::Variant((0, end)))
+        } else if input[0] == Token::Keyword(STATEMENT_MATCH.to_string()) {
+            let arms_start =
+                self.find_top_level(input, |token| token == &Token::Punctuation('{'))?;
+            let end = traversal::traverse_till_root_par(
+                &input[arms_start..],
+                (Token::Punctuation('{'), Token::Punctuation('}')),
+            )
+            .map(|pos| arms_start + pos + 1)?;
+
+            Some(RecognizableStructure::Statement((0, end)))
         } else if input[0] == Token::Keyword("if".to_string()) {
             Some(RecognizableStructure::Statement((0, input.len())))
         } else {
@@ -561,12 +1625,339 @@ impl Parser {
         }
     }
 
+    // tokens an expression statement can start with
+    fn starts_expression(&self, token: &Token) -> bool {
+        match token {
+            Token::Identifier(_) | Token::Literal(_) | Token::Punctuation('(' | '[') => true,
+            // a store through a pointer, `*p = value`
+            Token::Operator(op) => op == "*",
+            _ => false,
+        }
+    }
+
+    // prefix operators bind looser than field accesses and indices, but tighter than casts
+    fn parse_unary(&self, expression: &[Token]) -> Option<UnaryOperator> {
+        match expression.first()? {
+            Token::Operator(op) if op == "&" => Some(UnaryOperator::AddressOf),
+            Token::Operator(op) if op == "*" => Some(UnaryOperator::Deref),
+            _ => None,
+        }
+    }
+
+    fn continues_expression(&self, token: Option<&Token>) -> bool {
+        match token {
+            Some(Token::Operator(_)) => true,
+            Some(Token::Keyword(keyword)) => keyword == EXPRESSION_CAST,
+            Some(Token::Punctuation('.' | '[')) => true,
+            _ => false,
+        }
+    }
+
+    // builtin types are tokens of their own, anything else names a struct,
+    // returns the type and the count of tokens it spans
+    fn parse_type(&self, tokens: TokenSlice) -> Option<(Type, usize)> {
+        match tokens.first()? {
+            Token::Type(ty) => Some((ty.clone(), 1)),
+            Token::Identifier(_) => {
+                let (segments, size) = self.parse_path(&tokens);
+
+                Some((Type::Named(segments.join("::")), size))
+            }
+            // "*" + type
+            Token::Operator(op) if op == "*" => self
+                .parse_type(tokens.slice(1..))
+                .map(|(pointee, size)| (Type::Pointer(Box::new(pointee)), size + 1)),
+            // "(" + types separated by "," + ")", one type without a comma is only that type
+            Token::Punctuation('(') => {
+                let end = traversal::traverse_till_root_par(
+                    &tokens,
+                    (Token::Punctuation('('), Token::Punctuation(')')),
+                )?;
+
+                let inner = tokens.slice(1..end);
+                let elements = self
+                    .split_arguments(inner)
+                    .into_iter()
+                    .filter(|element| !element.is_empty())
+                    .map(|element| match self.parse_type(element) {
+                        Some((ty, size)) if size == element.len() => Some(ty),
+                        _ => None,
+                    })
+                    .collect::<Option<Vec<Type>>>()?;
+
+                let has_comma = self
+                    .find_top_level(&inner, |token| token == &Token::Punctuation(','))
+                    .is_some();
+
+                match elements.as_slice() {
+                    [] => None,
+                    [element] if !has_comma => Some((element.clone(), end + 1)),
+                    _ => Some((Type::Tuple(elements), end + 1)),
+                }
+            }
+            // "[" + type + ";" + size + "]", or "[" + type + "]" for a slice
+            Token::Punctuation('[') => {
+                let (element, size) = self.parse_type(tokens.slice(1..))?;
+
+                if tokens.get(1 + size) == Some(&Token::Punctuation(']')) {
+                    return Some((Type::Slice(Box::new(element)), size + 2));
+                }
+
+                if tokens.get(1 + size) != Some(&Token::Punctuation(';'))
+                    || tokens.get(3 + size) != Some(&Token::Punctuation(']'))
+                {
+                    return None;
+                }
+
+                let length = match tokens.get(2 + size)? {
+                    Token::Literal(token::Literal::Number(number)) => number.parse().ok()?,
+                    _ => return None,
+                };
+
+                Some((Type::Array(Box::new(element), length), size + 4))
+            }
+            _ => None,
+        }
+    }
+
+    // identifiers separated by "::", returns the segments and the count of tokens they span
+    fn parse_path(&self, tokens: &[Token]) -> (Vec<String>, usize) {
+        let mut segments = vec![];
+        let mut size = 0;
+
+        while let Some(Token::Identifier(segment)) = tokens.get(size) {
+            segments.push(segment.clone());
+            size += 1;
+
+            match &tokens[size..] {
+                [Token::Punctuation(':'), Token::Punctuation(':'), Token::Identifier(_), ..] => {
+                    size += 2
+                }
+                _ => break,
+            }
+        }
+
+        (segments, size)
+    }
+
+    // position of the first token outside of any parentheses, brackets or braces
+    fn find_top_level(
+        &self,
+        tokens: &[Token],
+        predicate: impl Fn(&Token) -> bool,
+    ) -> Option<usize> {
+        let mut depth = 0;
+
+        for (idx, token) in tokens.iter().enumerate() {
+            if depth == 0 && predicate(token) {
+                return Some(idx);
+            }
+
+            match token {
+                Token::Punctuation('(' | '[' | '{') => depth += 1,
+                Token::Punctuation(')' | ']' | '}') => depth -= 1,
+                _ => {}
+            }
+        }
+
+        None
+    }
+
+    fn split_arguments<'a>(&self, arguments: TokenSlice<'a>) -> Vec<TokenSlice<'a>> {
+        let mut split = vec![];
+        let mut depth = 0;
+        let mut start = 0;
+
+        for (idx, token) in arguments.iter().enumerate() {
+            match token {
+                Token::Punctuation('(' | '[' | '{') => depth += 1,
+                Token::Punctuation(')' | ']' | '}') => depth -= 1,
+                Token::Punctuation(',') if depth == 0 => {
+                    split.push(arguments.slice(start..idx));
+                    start = idx + 1;
+                }
+                _ => {}
+            }
+        }
+
+        split.push(arguments.slice(start..));
+
+        split
+    }
+
+    // `extern "ABI" fun` declaring a C function, or exporting one with a body after `pub`
+    fn parse_extern(
+        &self,
+        tokens: TokenSlice,
+        exported: bool,
+    ) -> Result<(Declaration, usize), Diagnostic> {
+        // extern keyword
+        let mut pos = 1;
+
+        // optional "ABI"
+        let abi = match tokens.get(pos) {
+            Some(Token::Literal(token::Literal::String(abi))) => {
+                pos += 1;
+                Some(abi.clone())
+            }
+            _ => None,
+        };
+
+        if tokens.get(pos).and_then(Token::as_keyword) != Some(DECLARATION_FUNCTION) {
+            return Err(self.error(
+                tokens,
+                pos,
+                format!("expected function declaration, got: {:?}", tokens.get(pos)),
+            ));
+        }
+        // fun keyword
+        pos += 1;
+
+        let (identifier, parameters, variadic, return_type, size) =
+            self.parse_signature(tokens.slice(pos..))?;
+        pos += size;
+
+        let has_body = tokens.get(pos) == Some(&Token::Punctuation('{'));
+
+        if !exported {
+            if has_body {
+                return Err(self.error(
+                    tokens,
+                    pos,
+                    "extern functions with a body must be exported with `pub`".to_string(),
+                ));
+            }
+
+            // signature + ";"
+            return Ok((
+                Declaration::ExternFunctionDeclaration {
+                    abi,
+                    identifier,
+                    parameters,
+                    variadic,
+                    return_type,
+                },
+                pos + 1,
+            ));
+        }
+
+        if variadic {
+            return Err(self.error(
+                tokens,
+                0,
+                "exported functions cannot be variadic".to_string(),
+            ));
+        }
+
+        let block_end_offset = self.braces(
+            tokens,
+            pos,
+            &format!("the body of exported function `{identifier}`"),
+        )?;
+
+        let (expression, _) = self.run_internal(tokens.slice(pos..(pos + block_end_offset)))?;
+
+        Ok((
+            Declaration::FunctionDeclaration {
+                identifier,
+                parameters,
+                return_type,
+                expression: Rc::new(expression),
+                public: true,
+                export: Some(abi.unwrap_or_else(|| ABI_C.to_string())),
+            },
+            pos + block_end_offset,
+        ))
+    }
+
+    // `name(a: T, ...) -> U`, up to the body, whether it ends with "..." and its size
+    #[allow(clippy::type_complexity)]
+    fn parse_signature(
+        &self,
+        tokens: TokenSlice,
+    ) -> Result<(String, Vec<(String, Type)>, bool, Type, usize), Diagnostic> {
+        let mut pos = 0;
+
+        let identifier = match tokens.get(pos) {
+            Some(Token::Identifier(ident)) => ident.clone(),
+            other => {
+                return Err(self.error(
+                    tokens,
+                    pos,
+                    format!("expected function identifier, got: {other:?}"),
+                ))
+            }
+        };
+        // ident
+        pos += 1;
+
+        if tokens.get(pos) != Some(&Token::Punctuation('(')) {
+            return Err(self.error(
+                tokens,
+                pos,
+                format!("expected the parameters of `{identifier}`"),
+            ));
+        }
+
+        let args_end_offset = self.closing(tokens, pos, '(')?;
+        // (
+        pos += 1;
+
+        let mut parameters = if tokens[pos] == Token::Punctuation(')') {
+            vec![]
+        } else {
+            self.split_arguments(tokens.slice(pos..(pos + args_end_offset - 1)))
+        };
+
+        let variadic = parameters.last().is_some_and(|param| {
+            param.len() == 3 && param.iter().all(|token| *token == Token::Punctuation('.'))
+        });
+        if variadic {
+            parameters.pop();
+        }
+
+        let parameters = parameters
+            .into_iter()
+            .map(|param| self.parse_field(param))
+            .collect::<Result<_, Diagnostic>>()?;
+
+        pos += args_end_offset;
+
+        // pos + "->".len()
+        let return_type = if tokens.get(pos) == Some(&Token::Operator("-".to_string()))
+            && tokens.get(pos + 1) == Some(&Token::Operator(">".to_string()))
+        {
+            // "->" + "type"
+            match tokens
+                .get_slice((pos + 2)..)
+                .and_then(|ty| self.parse_type(ty))
+            {
+                Some((ty, size)) => {
+                    pos += 2 + size;
+                    ty
+                }
+                None => {
+                    return Err(self.error(tokens, pos + 2, "expected return type".to_string()))
+                }
+            }
+        } else {
+            Type::Void
+        };
+
+        Ok((identifier, parameters, variadic, return_type, pos))
+    }
+
     fn parse_keyword(&self, keyword: &str) -> Option<Keyword> {
         match keyword {
             DECLARATION_CONSTANT => Some(Keyword::VariableDeclaration(
                 VariableDeclarationKeyword::Const,
             )),
             DECLARATION_FUNCTION => Some(Keyword::FunctionDeclaration),
+            DECLARATION_EXTERN => Some(Keyword::ExternFunctionDeclaration),
+            DECLARATION_PUBLIC => Some(Keyword::Public),
+            DECLARATION_IMPORT => Some(Keyword::Import),
+            DECLARATION_STRUCT => Some(Keyword::TypeDeclaration),
+            DECLARATION_ENUM => Some(Keyword::EnumDeclaration),
             DECLARATION_VARIABLE => Some(Keyword::VariableDeclaration(
                 VariableDeclarationKeyword::Let,
             )),
@@ -574,6 +1965,7 @@ impl Parser {
             STATEMENT_ELSE => Some(Keyword::ControlFlowElse),
             STATEMENT_WHILE => Some(Keyword::While),
             STATEMENT_RETURN => Some(Keyword::Return),
+            STATEMENT_MATCH => Some(Keyword::Match),
             _ => None,
         }
     }
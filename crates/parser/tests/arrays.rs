@@ -0,0 +1,170 @@
+use common::dump::TreeDump;
+use lexer::lexer::Lexer;
+use parser::Parser;
+
+#[test]
+pub fn arrays_work() {
+    let code = r#"
+    fun sum(xs: [i32; 3]) -> [i64; 2] {
+        let grid: [[u8; 2]; 2] = [[0; 2], [1, 2,]];
+        grid[1][0] = xs[0] + xs.len();
+        return [f(1)[0], p.xs[2]];
+    }
+    "#;
+
+    let tokens = Lexer::new().run(code);
+    let ast = Parser::new().run(&tokens);
+
+    assert_eq!(
+        ast.dump_tree(),
+        "\
+AbstractSyntaxTree
+  Block
+    FunctionDeclaration sum(xs: [i32; 3]) -> [i64; 2]
+      Block
+        VariableDeclaration let grid: [[u8; 2]; 2]
+          Array
+            ArrayRepeat 2
+              Literal Int32(0)
+            Array
+              Literal Int32(1)
+              Literal Int32(2)
+        BinaryExpression =
+          Index
+            Index
+              Identifier grid
+              Literal Int32(1)
+            Literal Int32(0)
+          BinaryExpression +
+            Index
+              Identifier xs
+              Literal Int32(0)
+            FunctionInvokation len
+              Identifier xs
+        Return
+          Array
+            Index
+              FunctionInvokation f
+                Literal Int32(1)
+              Literal Int32(0)
+            Index
+              FieldAccess xs
+                Identifier p
+              Literal Int32(2)
+"
+    );
+}
+
+#[test]
+pub fn loops_over_arrays_work() {
+    let code = r#"
+    while i < xs.len() {
+        i = i + 1;
+    }
+    return xs[0];
+    "#;
+
+    let tokens = Lexer::new().run(code);
+    let ast = Parser::new().run(&tokens);
+
+    assert_eq!(
+        ast.dump_tree(),
+        "\
+AbstractSyntaxTree
+  Block
+    While
+      Condition
+        BinaryExpression <
+          Identifier i
+          FunctionInvokation len
+            Identifier xs
+      Body
+        Block
+          BinaryExpression =
+            Identifier i
+            BinaryExpression +
+              Identifier i
+              Literal Int32(1)
+    Return
+      Index
+        Identifier xs
+        Literal Int32(0)
+"
+    );
+}
+
+#[test]
+pub fn subtractions_after_operands_work() {
+    let code = r#"
+    let a = xs[i] -1;
+    let b = f(x) -1;
+    let c = (a) -1;
+    a - b
+    "#;
+
+    let tokens = Lexer::new().run(code);
+    let ast = Parser::new().run(&tokens);
+
+    assert_eq!(
+        ast.dump_tree(),
+        "\
+AbstractSyntaxTree
+  Block
+    VariableDeclaration let a
+      BinaryExpression -
+        Index
+          Identifier xs
+          Identifier i
+        Literal Int32(1)
+    VariableDeclaration let b
+      BinaryExpression -
+        FunctionInvokation f
+          Identifier x
+        Literal Int32(1)
+    VariableDeclaration let c
+      BinaryExpression -
+        Identifier a
+        Literal Int32(1)
+    ImplicitReturn
+      BinaryExpression -
+        Identifier a
+        Identifier b
+"
+    );
+}
+
+#[test]
+pub fn slices_work() {
+    let code = r#"
+    fun middle(xs: [i32]) -> [i32] {
+        let all = xs[..];
+        return all[1..xs.len() - 1];
+    }
+    "#;
+
+    let tokens = Lexer::new().run(code);
+    let ast = Parser::new().run(&tokens);
+
+    assert_eq!(
+        ast.dump_tree(),
+        "\
+AbstractSyntaxTree
+  Block
+    FunctionDeclaration middle(xs: [i32]) -> [i32]
+      Block
+        VariableDeclaration let all
+          Slice
+            Identifier xs
+        Return
+          Slice
+            Identifier all
+            Start
+              Literal Int32(1)
+            End
+              BinaryExpression -
+                FunctionInvokation len
+                  Identifier xs
+                Literal Int32(1)
+"
+    );
+}
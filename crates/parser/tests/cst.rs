@@ -0,0 +1,205 @@
+use std::{fs, path::PathBuf};
+
+use common::span::Span;
+use lexer::lexer::Lexer;
+use parser::{
+    cst::{red::SyntaxNode, SyntaxKind},
+    Parser,
+};
+
+fn kinds(node: &SyntaxNode) -> Vec<SyntaxKind> {
+    node.child_nodes().iter().map(SyntaxNode::kind).collect()
+}
+
+// the source files in the directory and the projects inside it
+fn sources(directory: &str) -> Vec<PathBuf> {
+    let mut paths = vec![];
+    let mut pending = vec![PathBuf::from(directory)];
+
+    while let Some(directory) = pending.pop() {
+        for entry in fs::read_dir(directory).unwrap() {
+            let path = entry.unwrap().path();
+
+            if path.is_dir() {
+                pending.push(path);
+            } else if path
+                .extension()
+                .is_some_and(|extension| extension == "bloom")
+            {
+                paths.push(path);
+            }
+        }
+    }
+
+    paths
+}
+
+#[test]
+pub fn cst_round_trips_examples() {
+    for path in sources("../../examples") {
+        let code = fs::read_to_string(&path).unwrap();
+
+        let tokens = Lexer::new().run_lossless(&code);
+        let cst = Parser::new().run_lossless(&tokens);
+
+        assert_eq!(cst.text(), code, "{path:?} does not round-trip");
+        assert_eq!(cst.root().span(), Span::new(0, code.len()));
+    }
+}
+
+#[test]
+pub fn cst_round_trips_unbalanced_input() {
+    for code in [
+        "fun main( {",
+        "}} let a = (1 + ;",
+        "if a { else",
+        "",
+        "  // only\n",
+    ] {
+        let tokens = Lexer::new().run_lossless(code);
+        let cst = Parser::new().run_lossless(&tokens);
+
+        assert_eq!(cst.text(), code);
+    }
+}
+
+#[test]
+pub fn cst_structure_works() {
+    let code = r#"// entry point
+fun main() -> i32 {
+  let a = (1 + 2) * 3; // nine
+
+  if a > 5 {
+    return a;
+  } else {
+    a
+  }
+}
+"#;
+
+    let tokens = Lexer::new().run_lossless(code);
+    let cst = Parser::new().run_lossless(&tokens);
+    let root = cst.root();
+
+    assert_eq!(root.kind(), SyntaxKind::Root);
+    assert_eq!(kinds(root), vec![SyntaxKind::FunctionDeclaration]);
+
+    let function = &root.child_nodes()[0];
+    assert!(function.text().starts_with("// entry point\nfun main()"));
+    assert_eq!(
+        kinds(function),
+        vec![SyntaxKind::Parenthesized, SyntaxKind::Block]
+    );
+
+    let body = &function.child_nodes()[1];
+    assert_eq!(
+        kinds(body),
+        vec![SyntaxKind::VariableDeclaration, SyntaxKind::IfStatement]
+    );
+
+    let declaration = &body.child_nodes()[0];
+    assert_eq!(declaration.text(), "\n  let a = (1 + 2) * 3; // nine");
+    assert_eq!(kinds(declaration), vec![SyntaxKind::Parenthesized]);
+    assert_eq!(declaration.span(), Span::new(34, 65));
+    assert_eq!(
+        declaration.parent().map(SyntaxNode::kind),
+        Some(SyntaxKind::Block)
+    );
+
+    let if_statement = &body.child_nodes()[1];
+    assert_eq!(
+        kinds(if_statement),
+        vec![SyntaxKind::Block, SyntaxKind::ElseClause]
+    );
+    assert_eq!(
+        kinds(&if_statement.child_nodes()[0]),
+        vec![SyntaxKind::ReturnStatement]
+    );
+    assert_eq!(
+        kinds(&if_statement.child_nodes()[1].child_nodes()[0]),
+        vec![SyntaxKind::ExpressionStatement]
+    );
+
+    let comments = root
+        .tokens()
+        .into_iter()
+        .filter(|token| token.kind() == SyntaxKind::Comment)
+        .map(|token| token.text().to_string())
+        .collect::<Vec<String>>();
+    assert_eq!(comments, vec!["// entry point", "// nine"]);
+}
+
+#[test]
+pub fn cst_structure_of_types_and_matches_works() {
+    let code = r#"pub struct Point { x: i32 }
+enum Shape { Dot, Square { side: i32 } }
+fun main() {
+  let p = Point { x: 1 };
+  match p.x {
+    1 if true => 2,
+    _ => { 3 }
+  }
+}
+"#;
+
+    let tokens = Lexer::new().run_lossless(code);
+    let cst = Parser::new().run_lossless(&tokens);
+    let root = cst.root();
+
+    assert_eq!(cst.text(), code);
+    assert_eq!(
+        kinds(root),
+        vec![
+            SyntaxKind::StructDeclaration,
+            SyntaxKind::EnumDeclaration,
+            SyntaxKind::FunctionDeclaration
+        ]
+    );
+    assert_eq!(kinds(&root.child_nodes()[0]), vec![SyntaxKind::List]);
+    assert_eq!(
+        kinds(&root.child_nodes()[1].child_nodes()[0]),
+        vec![SyntaxKind::Braced]
+    );
+
+    let body = &root.child_nodes()[2].child_nodes()[1];
+    assert_eq!(
+        kinds(body),
+        vec![SyntaxKind::VariableDeclaration, SyntaxKind::MatchExpression]
+    );
+    assert_eq!(kinds(&body.child_nodes()[0]), vec![SyntaxKind::Braced]);
+    assert_eq!(
+        kinds(&body.child_nodes()[1].child_nodes()[0]),
+        vec![SyntaxKind::Block]
+    );
+}
+
+#[test]
+pub fn cst_token_spans_work() {
+    let code = "let x = - 5;\nlet y = 1 2;\n";
+
+    let tokens = Lexer::new().run_lossless(code);
+    let cst = Parser::new().run_lossless(&tokens);
+
+    let spans = cst
+        .root()
+        .tokens()
+        .into_iter()
+        .filter(|token| !token.kind().is_trivia())
+        .map(|token| (token.text().to_string(), token.span()))
+        .collect::<Vec<(String, Span)>>();
+
+    for (text, span) in &spans {
+        assert_eq!(&code[span.start..span.end], text);
+    }
+
+    let texts = spans
+        .iter()
+        .map(|(text, _)| text.as_str())
+        .collect::<Vec<&str>>();
+    assert_eq!(
+        texts,
+        vec!["let", "x", "=", "-", "5", ";", "let", "y", "=", "1", "2", ";", ""]
+    );
+    assert_eq!(spans[3].1, Span::new(8, 9));
+    assert_eq!(spans[4].1, Span::new(10, 11));
+}
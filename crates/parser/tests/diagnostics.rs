@@ -0,0 +1,60 @@
+use common::span::Span;
+use lexer::lexer::Lexer;
+use parser::Parser;
+
+#[test]
+pub fn missing_semicolon_is_reported() {
+    let code = "fun main() -> i32 {\n  let a = b c;\n  return a;\n}\n";
+
+    let (tokens, spans) = Lexer::new().try_run_spanned(code).unwrap();
+    let diagnostic = Parser::new().try_run_spanned(&tokens, &spans).unwrap_err();
+
+    assert_eq!(
+        diagnostic.message(),
+        "expected `;` after the value of the variable, got: Identifier(\"c\")"
+    );
+    assert_eq!(diagnostic.span(), Some(Span::new(32, 33)));
+    assert_eq!(diagnostic.span().unwrap().line_col(code), (2, 13));
+}
+
+#[test]
+pub fn unclosed_brace_is_reported() {
+    let code = "fun main() -> i32 {\n  let a = 1;\n  return a;\n";
+
+    let (tokens, spans) = Lexer::new().try_run_spanned(code).unwrap();
+    let diagnostic = Parser::new().try_run_spanned(&tokens, &spans).unwrap_err();
+
+    assert_eq!(diagnostic.message(), "unclosed `{`");
+    assert_eq!(diagnostic.span().unwrap().line_col(code), (1, 19));
+}
+
+#[test]
+pub fn malformed_declarations_are_reported() {
+    let code = "struct Point { x: }\n";
+
+    let (tokens, spans) = Lexer::new().try_run_spanned(code).unwrap();
+    let diagnostic = Parser::new().try_run_spanned(&tokens, &spans).unwrap_err();
+
+    assert_eq!(diagnostic.message(), "expected field type");
+    assert_eq!(diagnostic.span(), Some(Span::new(16, 17)));
+
+    let code = "fun main() -> i32 {\n  else { return 1; }\n}\n";
+
+    let (tokens, spans) = Lexer::new().try_run_spanned(code).unwrap();
+    let diagnostic = Parser::new().try_run_spanned(&tokens, &spans).unwrap_err();
+
+    assert_eq!(diagnostic.message(), "`else` without an `if` before it");
+    assert_eq!(diagnostic.span().unwrap().line_col(code), (2, 3));
+}
+
+#[test]
+pub fn unspanned_diagnostics_have_no_span() {
+    let tokens = Lexer::new().run("let a 1;");
+    let diagnostic = Parser::new().try_run(&tokens).unwrap_err();
+
+    assert_eq!(
+        diagnostic.message(),
+        "expected `=` after the variable, got: Some(Literal(Number(\"1\")))"
+    );
+    assert_eq!(diagnostic.span(), None);
+}
@@ -0,0 +1,126 @@
+use common::dump::{TreeDump, TreeWriter};
+use lexer::lexer::Lexer;
+use parser::Parser;
+
+#[test]
+pub fn ast_dump_works() {
+    let code = r#"
+    fun add(a: i32, b: i32) -> i32 {
+        if a > b {
+            return a - b;
+        } else {
+            return a + b as i32;
+        }
+    }
+
+    let x = add(1, 2);
+    "#;
+
+    let tokens = Lexer::new().run(code);
+    let ast = Parser::new().run(&tokens);
+
+    assert_eq!(
+        ast.dump_tree(),
+        "\
+AbstractSyntaxTree
+  Block
+    FunctionDeclaration add(a: i32, b: i32) -> i32
+      Block
+        ControlFlow
+          Condition
+            BinaryExpression >
+              Identifier a
+              Identifier b
+          Then
+            Block
+              Return
+                BinaryExpression -
+                  Identifier a
+                  Identifier b
+          Else
+            Block
+              Return
+                BinaryExpression +
+                  Identifier a
+                  Cast i32
+                    Identifier b
+    VariableDeclaration let x
+      FunctionInvokation add
+        Literal Int32(1)
+        Literal Int32(2)
+"
+    );
+}
+
+#[test]
+pub fn number_literals_are_parsed() {
+    let code = "let a = 2.0 * -0.5 + 3;";
+
+    let tokens = Lexer::new().run(code);
+    let ast = Parser::new().run(&tokens);
+
+    assert_eq!(
+        ast.dump_tree(),
+        "\
+AbstractSyntaxTree
+  Block
+    VariableDeclaration let a
+      BinaryExpression +
+        BinaryExpression *
+          Literal Float64(2.0)
+          Literal Float64(-0.5)
+        Literal Int32(3)
+"
+    );
+}
+
+#[test]
+pub fn casts_followed_by_arithmetic_are_parsed() {
+    let code = "let b = a as i32 -1;\nb * 2 as u8 - 1";
+
+    let tokens = Lexer::new().run(code);
+    let ast = Parser::new().run(&tokens);
+
+    assert_eq!(
+        ast.dump_tree(),
+        "\
+AbstractSyntaxTree
+  Block
+    VariableDeclaration let b
+      BinaryExpression -
+        Cast i32
+          Identifier a
+        Literal Int32(1)
+    ImplicitReturn
+      BinaryExpression -
+        BinaryExpression *
+          Identifier b
+          Cast u8
+            Literal Int32(2)
+        Literal Int32(1)
+"
+    );
+}
+
+#[test]
+pub fn token_dump_works() {
+    let tokens = Lexer::new().run("let c = 'a'; // done");
+    let mut writer = TreeWriter::new();
+
+    for token in &tokens {
+        token.dump(&mut writer);
+    }
+
+    assert_eq!(
+        writer.finish(),
+        "\
+Keyword let
+Identifier c
+Operator =
+Char 'a'
+Punctuation ;
+Comment \"// done\"
+EOF
+"
+    );
+}
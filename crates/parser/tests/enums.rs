@@ -0,0 +1,92 @@
+use common::dump::TreeDump;
+use lexer::lexer::Lexer;
+use parser::Parser;
+
+#[test]
+pub fn enum_declarations_work() {
+    let code = r#"
+    enum Shape { Circle(f64), Rect { w: f64, h: f64 }, Empty, }
+
+    fun unit() -> Shape {
+        return Shape::Rect { w: 1, h: 2 + 3 };
+    }
+
+    let a = Shape::Circle(f(1), 2);
+    let b = Shape::Empty;
+    "#;
+
+    let tokens = Lexer::new().run(code);
+    let ast = Parser::new().run(&tokens);
+
+    assert_eq!(
+        ast.dump_tree(),
+        "\
+AbstractSyntaxTree
+  Block
+    EnumDeclaration Shape { Circle(f64), Rect { w: f64, h: f64 }, Empty }
+    FunctionDeclaration unit() -> Shape
+      Block
+        Return
+          Variant Shape::Rect
+            Field w
+              Literal Int32(1)
+            Field h
+              BinaryExpression +
+                Literal Int32(2)
+                Literal Int32(3)
+    VariableDeclaration let a
+      Variant Shape::Circle
+        Field 0
+          FunctionInvokation f
+            Literal Int32(1)
+        Field 1
+          Literal Int32(2)
+    VariableDeclaration let b
+      Variant Shape::Empty
+"
+    );
+}
+
+#[test]
+pub fn match_works() {
+    let code = r#"
+    let area = match shape {
+        Shape::Circle(r) if r > 10 => r * r,
+        Shape::Rect { w, h: 0 } => { w }
+        1 => 2,
+        _ => 0
+    };
+    "#;
+
+    let tokens = Lexer::new().run(code);
+    let ast = Parser::new().run(&tokens);
+
+    assert_eq!(
+        ast.dump_tree(),
+        "\
+AbstractSyntaxTree
+  Block
+    VariableDeclaration let area
+      Block
+        ImplicitReturn
+          Match
+            Identifier shape
+            Arm Shape::Circle(r)
+              Guard
+                BinaryExpression >
+                  Identifier r
+                  Literal Int32(10)
+              BinaryExpression *
+                Identifier r
+                Identifier r
+            Arm Shape::Rect { w: w, h: Int32(0) }
+              Block
+                ImplicitReturn
+                  Identifier w
+            Arm Int32(1)
+              Literal Int32(2)
+            Arm _
+              Literal Int32(0)
+"
+    );
+}
@@ -0,0 +1,66 @@
+use common::dump::TreeDump;
+use lexer::lexer::Lexer;
+use parser::Parser;
+
+#[test]
+pub fn extern_functions_work() {
+    let code = r#"
+    extern fun puts(s: String) -> i32;
+    extern "C" fun printf(format: String, ...) -> i32;
+    extern fun abort();
+
+    fun main() -> i32 {
+        printf("%d\n", puts("hello"))
+    }
+    "#;
+
+    let tokens = Lexer::new().run(code);
+    let ast = Parser::new().run(&tokens);
+
+    assert_eq!(
+        ast.dump_tree(),
+        "\
+AbstractSyntaxTree
+  Block
+    ExternFunctionDeclaration puts(s: String) -> i32
+    ExternFunctionDeclaration \"C\" printf(format: String, ...) -> i32
+    ExternFunctionDeclaration abort() -> void
+    FunctionDeclaration main() -> i32
+      Block
+        ImplicitReturn
+          FunctionInvokation printf
+            Literal String(\"%d\\n\")
+            FunctionInvokation puts
+              Literal String(\"hello\")
+"
+    );
+}
+
+#[test]
+pub fn exported_functions_work() {
+    let code = r#"
+    pub extern fun add(a: i32, b: i32) -> i32 {
+        a + b
+    }
+    pub extern "C" fun reset() {}
+    "#;
+
+    let tokens = Lexer::new().run(code);
+    let ast = Parser::new().run(&tokens);
+
+    assert_eq!(
+        ast.dump_tree(),
+        "\
+AbstractSyntaxTree
+  Block
+    FunctionDeclaration pub extern \"C\" add(a: i32, b: i32) -> i32
+      Block
+        ImplicitReturn
+          BinaryExpression +
+            Identifier a
+            Identifier b
+    FunctionDeclaration pub extern \"C\" reset() -> void
+      Block
+"
+    );
+}
@@ -0,0 +1,71 @@
+use common::dump::TreeDump;
+use lexer::lexer::Lexer;
+use parser::Parser;
+
+#[test]
+pub fn imports_and_public_declarations_work() {
+    let code = r#"
+    import math;
+    import util::strings;
+
+    pub struct Point { x: i32, y: i32 }
+    pub enum Shape { Dot, Circle(i32) }
+    enum Hidden { Nothing }
+
+    pub fun origin() -> Point {
+        Point { x: 0, y: 0 }
+    }
+
+    fun helper() {}
+    "#;
+
+    let tokens = Lexer::new().run(code);
+    let ast = Parser::new().run(&tokens);
+
+    assert_eq!(
+        ast.dump_tree(),
+        "\
+AbstractSyntaxTree
+  Block
+    Import math
+    Import util::strings
+    TypeDeclaration pub Point { x: i32, y: i32 }
+    EnumDeclaration pub Shape { Dot, Circle(i32) }
+    EnumDeclaration Hidden { Nothing }
+    FunctionDeclaration pub origin() -> Point
+      Block
+        ImplicitReturn
+          Struct Point
+            Field x
+              Literal Int32(0)
+            Field y
+              Literal Int32(0)
+    FunctionDeclaration helper() -> void
+      Block
+"
+    );
+}
+
+#[test]
+pub fn paths_work() {
+    let code = r#"
+    let shape: math::Shape = math::Shape::Circle(util::strings::len(name));
+    "#;
+
+    let tokens = Lexer::new().run(code);
+    let ast = Parser::new().run(&tokens);
+
+    assert_eq!(
+        ast.dump_tree(),
+        "\
+AbstractSyntaxTree
+  Block
+    VariableDeclaration let shape: math::Shape
+      Variant math::Shape::Circle
+        Field 0
+          Variant util::strings::len
+            Field 0
+              Identifier name
+"
+    );
+}
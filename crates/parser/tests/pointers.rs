@@ -0,0 +1,56 @@
+use common::dump::TreeDump;
+use lexer::lexer::Lexer;
+use parser::Parser;
+
+#[test]
+pub fn pointers_work() {
+    let code = r#"
+    enum List { Cons(i32, *List), Nil }
+
+    fun bump(p: *i32, n: **Node) -> *i32 {
+        *p = *p * 2 + -1;
+        (*n).value = *p as i64;
+        free(alloc(&p.x[0]));
+        p
+    }
+    "#;
+
+    let tokens = Lexer::new().run(code);
+    let ast = Parser::new().run(&tokens);
+
+    assert_eq!(
+        ast.dump_tree(),
+        "\
+AbstractSyntaxTree
+  Block
+    EnumDeclaration List { Cons(i32, *List), Nil }
+    FunctionDeclaration bump(p: *i32, n: **Node) -> *i32
+      Block
+        BinaryExpression =
+          Deref
+            Identifier p
+          BinaryExpression +
+            BinaryExpression *
+              Deref
+                Identifier p
+              Literal Int32(2)
+            Literal Int32(-1)
+        BinaryExpression =
+          FieldAccess value
+            Deref
+              Identifier n
+          Cast i64
+            Deref
+              Identifier p
+        FunctionInvokation free
+          FunctionInvokation alloc
+            AddressOf
+              Index
+                FieldAccess x
+                  Identifier p
+                Literal Int32(0)
+        ImplicitReturn
+          Identifier p
+"
+    );
+}
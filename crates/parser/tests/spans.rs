@@ -0,0 +1,77 @@
+use std::rc::Rc;
+
+use common::span::Span;
+use lexer::lexer::Lexer;
+use parser::{
+    ast::{declaration::Declaration, statement::Statement, unit::ASTUnit},
+    Parser,
+};
+
+fn block(unit: &ASTUnit) -> &Vec<Rc<ASTUnit>> {
+    match unit {
+        ASTUnit::Block(block) => block,
+        other => panic!("expected block, got: {other:?}"),
+    }
+}
+
+#[test]
+pub fn spanned_parse_adds_locations() {
+    let code = "fun main() -> i32 {\n  let x = 1;\n  return x;\n}\n";
+
+    let (tokens, spans) = Lexer::new().try_run_spanned(code).unwrap();
+    let ast = Parser::new().run_spanned(&tokens, &spans);
+    let root = block(ast.root());
+
+    assert_eq!(root.len(), 2);
+    assert_eq!(root[0].as_ref(), &ASTUnit::Location(Span::new(0, 3)));
+
+    let body = match root[1].as_ref() {
+        ASTUnit::Declaration(Declaration::FunctionDeclaration { expression, .. }) => {
+            block(expression)
+        }
+        other => panic!("expected function declaration, got: {other:?}"),
+    };
+
+    assert_eq!(body.len(), 4);
+    assert_eq!(body[0].as_ref(), &ASTUnit::Location(Span::new(22, 25)));
+    assert_eq!(body[2].as_ref(), &ASTUnit::Location(Span::new(35, 41)));
+}
+
+#[test]
+pub fn unspanned_parse_has_no_locations() {
+    let code = "fun main() -> i32 {\n  let x = 1;\n  return x;\n}\n";
+
+    let (tokens, spans) = Lexer::new().try_run_spanned(code).unwrap();
+
+    let spanned = Parser::new().run_spanned(&tokens, &spans);
+    let ast = Parser::new().run(&tokens);
+
+    assert_eq!(block(ast.root()).len(), 1);
+    assert_ne!(spanned.root(), ast.root());
+}
+
+#[test]
+pub fn nested_statements_have_locations() {
+    let code = "fun main() -> i32 {\n  if true {\n    return 1;\n  }\n  return 0;\n}\n";
+
+    let (tokens, spans) = Lexer::new().try_run_spanned(code).unwrap();
+    let ast = Parser::new().run_spanned(&tokens, &spans);
+
+    let body = match block(ast.root())[1].as_ref() {
+        ASTUnit::Declaration(Declaration::FunctionDeclaration { expression, .. }) => {
+            block(expression)
+        }
+        other => panic!("expected function declaration, got: {other:?}"),
+    };
+    let execute = match body[1].as_ref() {
+        ASTUnit::Statement(Statement::ControlFlow { execute, .. }) => block(execute),
+        other => panic!("expected if statement, got: {other:?}"),
+    };
+
+    let start = code.find("return 1").unwrap();
+
+    assert_eq!(
+        execute[0].as_ref(),
+        &ASTUnit::Location(Span::new(start, start + 6))
+    );
+}
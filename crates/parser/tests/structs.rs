@@ -0,0 +1,91 @@
+use common::dump::TreeDump;
+use lexer::lexer::Lexer;
+use parser::Parser;
+
+#[test]
+pub fn struct_declarations_work() {
+    let code = r#"
+    struct Point { x: f64, y: f64, }
+    struct Line { from: Point, to: Point }
+
+    fun origin() -> Point {
+        return Point { x: 0, y: 0 };
+    }
+    "#;
+
+    let tokens = Lexer::new().run(code);
+    let ast = Parser::new().run(&tokens);
+
+    assert_eq!(
+        ast.dump_tree(),
+        "\
+AbstractSyntaxTree
+  Block
+    TypeDeclaration Point { x: f64, y: f64 }
+    TypeDeclaration Line { from: Point, to: Point }
+    FunctionDeclaration origin() -> Point
+      Block
+        Return
+          Struct Point
+            Field x
+              Literal Int32(0)
+            Field y
+              Literal Int32(0)
+"
+    );
+}
+
+#[test]
+pub fn field_access_works() {
+    let code = r#"
+    let line: Line = make(Point { x: 1, y: a.b + 2 });
+    line.to.x = line.from.y + 1;
+    while line.to.x < 3 {
+        line.to.x = 3;
+    }
+    "#;
+
+    let tokens = Lexer::new().run(code);
+    let ast = Parser::new().run(&tokens);
+
+    assert_eq!(
+        ast.dump_tree(),
+        "\
+AbstractSyntaxTree
+  Block
+    VariableDeclaration let line: Line
+      FunctionInvokation make
+        Struct Point
+          Field x
+            Literal Int32(1)
+          Field y
+            BinaryExpression +
+              FieldAccess b
+                Identifier a
+              Literal Int32(2)
+    BinaryExpression =
+      FieldAccess x
+        FieldAccess to
+          Identifier line
+      BinaryExpression +
+        FieldAccess y
+          FieldAccess from
+            Identifier line
+        Literal Int32(1)
+    While
+      Condition
+        BinaryExpression <
+          FieldAccess x
+            FieldAccess to
+              Identifier line
+          Literal Int32(3)
+      Body
+        Block
+          BinaryExpression =
+            FieldAccess x
+              FieldAccess to
+                Identifier line
+            Literal Int32(3)
+"
+    );
+}
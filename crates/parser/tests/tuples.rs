@@ -0,0 +1,102 @@
+use common::dump::TreeDump;
+use lexer::lexer::Lexer;
+use parser::Parser;
+
+#[test]
+pub fn tuples_work() {
+    let code = r#"
+    fun divide(a: i32, b: i32) -> (i32, bool) {
+        let pair: ((i32, bool), u8) = ((a / b, true), 1);
+        pair.0.1 = b == 0;
+        (pair.0.0, (a, b).1 == 0)
+    }
+
+    let (quotient, _) = divide(4, 2);
+    const ((a, b), c) = ((1, 2), 3);
+    let x = match divide(1, 0) { (_, false) => 0, (q, ok) => q };
+    "#;
+
+    let tokens = Lexer::new().run(code);
+    let ast = Parser::new().run(&tokens);
+
+    assert_eq!(
+        ast.dump_tree(),
+        "\
+AbstractSyntaxTree
+  Block
+    FunctionDeclaration divide(a: i32, b: i32) -> (i32, bool)
+      Block
+        VariableDeclaration let pair: ((i32, bool), u8)
+          Tuple
+            Tuple
+              BinaryExpression /
+                Identifier a
+                Identifier b
+              Literal Bool(true)
+            Literal Int32(1)
+        BinaryExpression =
+          FieldAccess 1
+            FieldAccess 0
+              Identifier pair
+          BinaryExpression ==
+            Identifier b
+            Literal Int32(0)
+        ImplicitReturn
+          Tuple
+            FieldAccess 0
+              FieldAccess 0
+                Identifier pair
+            BinaryExpression ==
+              FieldAccess 1
+                Tuple
+                  Identifier a
+                  Identifier b
+              Literal Int32(0)
+    DestructuringDeclaration let (quotient, _)
+      FunctionInvokation divide
+        Literal Int32(4)
+        Literal Int32(2)
+    DestructuringDeclaration const ((a, b), c)
+      Tuple
+        Tuple
+          Literal Int32(1)
+          Literal Int32(2)
+        Literal Int32(3)
+    VariableDeclaration let x
+      Block
+        ImplicitReturn
+          Match
+            FunctionInvokation divide
+              Literal Int32(1)
+              Literal Int32(0)
+            Arm (_, Bool(false))
+              Literal Int32(0)
+            Arm (q, ok)
+              Identifier q
+"
+    );
+}
+
+#[test]
+pub fn nested_elements_are_accessed() {
+    let code = "t.1.1.0 + t.0";
+
+    let tokens = Lexer::new().run(code);
+    let ast = Parser::new().run(&tokens);
+
+    assert_eq!(
+        ast.dump_tree(),
+        "\
+AbstractSyntaxTree
+  Block
+    ImplicitReturn
+      BinaryExpression +
+        FieldAccess 0
+          FieldAccess 1
+            FieldAccess 1
+              Identifier t
+        FieldAccess 0
+          Identifier t
+"
+    );
+}
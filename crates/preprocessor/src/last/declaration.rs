@@ -2,25 +2,48 @@ use std::rc::Rc;
 
 use common::types::Type;
 use parser::ast::declaration::VariableDeclarationKeyword;
+pub use parser::ast::declaration::{Variant, VariantKind};
 
 use super::{expression::Expression, unit::LASTUnit};
 
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum Declaration {
+    TypeDeclaration {
+        identifier: String,
+        fields: Vec<(String, Type)>,
+    },
+    // tags are the positions of the variants
+    EnumDeclaration {
+        identifier: String,
+        variants: Vec<Variant>,
+    },
     VariableDeclaration {
         allocation: VariableAllocation,
         identifier: String,
+        // the type of the variable, when the type checker could infer it
+        ty: Option<Type>,
         expression: Rc<Expression>,
     },
+    // exported functions keep the identifier of the source
     FunctionDeclaration {
         identifier: String,
+        exported: bool,
         parameters: Vec<(String, Type)>,
         return_type: Type,
         body: Vec<Rc<LASTUnit>>,
     },
+    // declared only, the identifier is the symbol of the C function
+    ExternFunctionDeclaration {
+        identifier: String,
+        parameters: Vec<(String, Type)>,
+        variadic: bool,
+        return_type: Type,
+    },
 }
 
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum VariableAllocation {
     SSA,
     Stack,
@@ -0,0 +1,270 @@
+use std::rc::Rc;
+
+use common::dump::{TreeDump, TreeWriter};
+
+use super::{
+    declaration::{Declaration, VariableAllocation},
+    expression::Expression,
+    statement::Statement,
+    unit::LASTUnit,
+    LoweredAbstractSyntaxTree,
+};
+
+impl TreeDump for LoweredAbstractSyntaxTree {
+    fn dump(&self, writer: &mut TreeWriter) {
+        writer.node("LoweredAbstractSyntaxTree", |writer| {
+            dump_block(self.root(), writer)
+        });
+    }
+}
+
+fn dump_block(block: &[Rc<LASTUnit>], writer: &mut TreeWriter) {
+    for unit in block {
+        unit.dump(writer);
+    }
+}
+
+impl TreeDump for LASTUnit {
+    fn dump(&self, writer: &mut TreeWriter) {
+        match self {
+            Self::Statement(statement) => statement.dump(writer),
+            Self::Expression(expression) => expression.dump(writer),
+            Self::Declaration(declaration) => declaration.dump(writer),
+            Self::Location(span) => writer.leaf(format!("Location {}..{}", span.start, span.end)),
+        }
+    }
+}
+
+impl TreeDump for Declaration {
+    fn dump(&self, writer: &mut TreeWriter) {
+        match self {
+            Self::TypeDeclaration { identifier, fields } => {
+                let fields = fields
+                    .iter()
+                    .map(|(name, ty)| format!("{name}: {ty}"))
+                    .collect::<Vec<String>>()
+                    .join(", ");
+
+                writer.leaf(format!("TypeDeclaration {identifier} {{ {fields} }}"));
+            }
+            Self::EnumDeclaration {
+                identifier,
+                variants,
+            } => {
+                let variants = variants
+                    .iter()
+                    .map(|variant| variant.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ");
+
+                writer.leaf(format!("EnumDeclaration {identifier} {{ {variants} }}"));
+            }
+            Self::VariableDeclaration {
+                allocation,
+                identifier,
+                expression,
+                ..
+            } => {
+                let allocation = match allocation {
+                    VariableAllocation::SSA => "ssa",
+                    VariableAllocation::Stack => "stack",
+                };
+
+                writer.node(
+                    format!("VariableDeclaration {allocation} {identifier}"),
+                    |writer| expression.dump(writer),
+                );
+            }
+            Self::FunctionDeclaration {
+                identifier,
+                exported,
+                parameters,
+                return_type,
+                body,
+            } => {
+                let parameters = parameters
+                    .iter()
+                    .map(|(name, ty)| format!("{name}: {ty}"))
+                    .collect::<Vec<String>>()
+                    .join(", ");
+
+                let exported = if *exported { "pub extern " } else { "" };
+
+                writer.node(
+                    format!(
+                        "FunctionDeclaration {exported}{identifier}({parameters}) -> {return_type}"
+                    ),
+                    |writer| dump_block(body, writer),
+                );
+            }
+            Self::ExternFunctionDeclaration {
+                identifier,
+                parameters,
+                variadic,
+                return_type,
+            } => {
+                let mut parameters = parameters
+                    .iter()
+                    .map(|(name, ty)| format!("{name}: {ty}"))
+                    .collect::<Vec<String>>();
+                if *variadic {
+                    parameters.push("...".to_string());
+                }
+
+                writer.leaf(format!(
+                    "ExternFunctionDeclaration {identifier}({}) -> {return_type}",
+                    parameters.join(", ")
+                ));
+            }
+        }
+    }
+}
+
+impl TreeDump for Statement {
+    fn dump(&self, writer: &mut TreeWriter) {
+        match self {
+            Self::ControlFlow {
+                condition,
+                execute,
+                alternative,
+            } => writer.node("ControlFlow", |writer| {
+                writer.node("Condition", |writer| condition.dump(writer));
+                writer.node("Then", |writer| dump_block(execute, writer));
+
+                if let Some(alternative) = alternative {
+                    writer.node("Else", |writer| dump_block(alternative, writer));
+                }
+            }),
+            Self::Loop {
+                header,
+                condition,
+                body,
+            } => writer.node("Loop", |writer| {
+                writer.node("Header", |writer| dump_block(header, writer));
+                writer.node("Condition", |writer| condition.dump(writer));
+                writer.node("Body", |writer| dump_block(body, writer));
+            }),
+            Self::Switch {
+                value,
+                cases,
+                default,
+            } => writer.node("Switch", |writer| {
+                value.dump(writer);
+
+                for (value, case) in cases {
+                    writer.node(format!("Case {value}"), |writer| dump_block(case, writer));
+                }
+
+                if let Some(default) = default {
+                    writer.node("Default", |writer| dump_block(default, writer));
+                }
+            }),
+            Self::Return(expression) => writer.node("Return", |writer| expression.dump(writer)),
+            Self::Unreachable => writer.leaf("Unreachable"),
+        }
+    }
+}
+
+impl TreeDump for Expression {
+    fn dump(&self, writer: &mut TreeWriter) {
+        match self {
+            Self::Literal(literal) => writer.leaf(format!("Literal {literal:?}")),
+            Self::Identifier(identifier) => writer.leaf(format!("Identifier {identifier}")),
+            Self::FunctionInvokation { name, args } => {
+                writer.node(format!("FunctionInvokation {name}"), |writer| {
+                    for arg in args {
+                        arg.dump(writer);
+                    }
+                })
+            }
+            Self::BinaryExpression {
+                left,
+                right,
+                operation,
+                ..
+            } => writer.node(
+                format!("BinaryExpression {}", operation.as_str()),
+                |writer| {
+                    left.dump(writer);
+                    right.dump(writer);
+                },
+            ),
+            Self::Index { target, index, .. } => writer.node("Index", |writer| {
+                target.dump(writer);
+                index.dump(writer);
+            }),
+            Self::Slice {
+                target, start, end, ..
+            } => writer.node("Slice", |writer| {
+                target.dump(writer);
+
+                if let Some(start) = start {
+                    writer.node("Start", |writer| start.dump(writer));
+                }
+                if let Some(end) = end {
+                    writer.node("End", |writer| end.dump(writer));
+                }
+            }),
+            Self::Cast {
+                expression,
+                from,
+                ty,
+            } => {
+                let label = match from {
+                    Some(from) => format!("Cast {from} -> {ty}"),
+                    None => format!("Cast {ty}"),
+                };
+
+                writer.node(label, |writer| expression.dump(writer))
+            }
+            Self::Struct { name, fields } => writer.node(format!("Struct {name}"), |writer| {
+                for field in fields {
+                    field.dump(writer);
+                }
+            }),
+            Self::FieldAccess {
+                target,
+                field,
+                index,
+            } => writer.node(format!("FieldAccess {field} ({index})"), |writer| {
+                target.dump(writer)
+            }),
+            Self::Variant {
+                name,
+                variant,
+                tag,
+                fields,
+            } => writer.node(format!("Variant {name}::{variant} ({tag})"), |writer| {
+                for field in fields {
+                    field.dump(writer);
+                }
+            }),
+            Self::Tag(target) => writer.node("Tag", |writer| target.dump(writer)),
+            Self::Payload {
+                target,
+                name,
+                variant,
+                index,
+            } => writer.node(format!("Payload {name}::{variant} ({index})"), |writer| {
+                target.dump(writer)
+            }),
+            Self::AddressOf(place) => writer.node("AddressOf", |writer| place.dump(writer)),
+            Self::Deref { target, ty } => {
+                writer.node(format!("Deref {ty}"), |writer| target.dump(writer))
+            }
+            Self::Tuple(elements) => writer.node("Tuple", |writer| {
+                for element in elements {
+                    element.dump(writer);
+                }
+            }),
+            Self::Array(elements) => writer.node("Array", |writer| {
+                for element in elements {
+                    element.dump(writer);
+                }
+            }),
+            Self::ArrayRepeat { value, count } => {
+                writer.node(format!("ArrayRepeat {count}"), |writer| value.dump(writer))
+            }
+        }
+    }
+}
@@ -1,10 +1,12 @@
 use std::rc::Rc;
 
+use common::types::Type;
 pub use parser::ast::literal::Literal;
 
 use super::operation::Operation;
 
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum Expression {
     Literal(Literal),
     Identifier(String),
@@ -16,5 +18,69 @@ pub enum Expression {
         left: Rc<Expression>,
         right: Rc<Expression>,
         operation: Operation,
+        // the type both operands have, when the type checker could infer it
+        operands: Option<Type>,
+    },
+    // arrays are indexed in place, strings and slices by value
+    Index {
+        target: Rc<Expression>,
+        index: Rc<Expression>,
+        // the element type, when the type checker could infer it
+        element: Option<Type>,
+    },
+    // a slice of an array in place or of another slice, from `start` or 0 up to `end`
+    // or the length
+    Slice {
+        target: Rc<Expression>,
+        start: Option<Rc<Expression>>,
+        end: Option<Rc<Expression>>,
+        // the element type, when the type checker could infer it
+        element: Option<Type>,
+    },
+    Cast {
+        expression: Rc<Expression>,
+        // the operand type, when the type checker could infer it
+        from: Option<Type>,
+        ty: Type,
+    },
+    // field values in declaration order
+    Struct {
+        name: String,
+        fields: Vec<Expression>,
+    },
+    FieldAccess {
+        target: Rc<Expression>,
+        field: String,
+        index: u32,
+    },
+    // field values in declaration order
+    Variant {
+        name: String,
+        variant: String,
+        tag: u32,
+        fields: Vec<Expression>,
+    },
+    // the tag of an enum value, as u32
+    Tag(Rc<Expression>),
+    // a field of an enum value, which has to hold the given variant
+    Payload {
+        target: Rc<Expression>,
+        name: String,
+        variant: String,
+        index: u32,
+    },
+    Tuple(Vec<Expression>),
+    // the address of a place, like the left side of an assignment
+    AddressOf(Rc<Expression>),
+    // the value a pointer points to, of the given type
+    Deref {
+        target: Rc<Expression>,
+        ty: Type,
+    },
+    // element values, already converted to the element type
+    Array(Vec<Expression>),
+    ArrayRepeat {
+        value: Rc<Expression>,
+        count: u64,
     },
 }
@@ -0,0 +1,77 @@
+use common::types::Type;
+
+use super::{declaration::Declaration, unit::LASTUnit, LoweredAbstractSyntaxTree};
+
+impl LoweredAbstractSyntaxTree {
+    // a C header declaring the exported functions, guarded by the module name
+    pub fn c_header(&self, module_name: &str) -> String {
+        let guard = module_name
+            .chars()
+            .map(|char| {
+                if char.is_ascii_alphanumeric() {
+                    char.to_ascii_uppercase()
+                } else {
+                    '_'
+                }
+            })
+            .collect::<String>();
+
+        let prototypes = self
+            .root()
+            .iter()
+            .filter_map(|unit| match unit.as_ref() {
+                LASTUnit::Declaration(Declaration::FunctionDeclaration {
+                    identifier,
+                    exported: true,
+                    parameters,
+                    return_type,
+                    ..
+                }) => Some(prototype(identifier, parameters, return_type)),
+                _ => None,
+            })
+            .collect::<Vec<String>>();
+
+        let mut header = format!("#ifndef BLOOM_{guard}_H\n#define BLOOM_{guard}_H\n\n");
+        header.push_str("#include <stdbool.h>\n#include <stdint.h>\n\n");
+        header.push_str("#ifdef __cplusplus\nextern \"C\" {\n#endif\n\n");
+
+        for prototype in prototypes {
+            header.push_str(&prototype);
+            header.push('\n');
+        }
+        if !header.ends_with("\n\n") {
+            header.push('\n');
+        }
+
+        header.push_str("#ifdef __cplusplus\n}\n#endif\n\n");
+        header.push_str(&format!("#endif // BLOOM_{guard}_H\n"));
+
+        header
+    }
+}
+
+// the type checker only lets types C knows through exported signatures
+fn prototype(identifier: &str, parameters: &[(String, Type)], return_type: &Type) -> String {
+    let parameters = if parameters.is_empty() {
+        "void".to_string()
+    } else {
+        parameters
+            .iter()
+            .map(|(name, ty)| declarator(ty, name))
+            .collect::<Vec<String>>()
+            .join(", ")
+    };
+
+    format!("{}({parameters});", declarator(return_type, identifier))
+}
+
+// `int32_t name`, or `uint8_t *name` without a space after the star
+fn declarator(ty: &Type, name: &str) -> String {
+    let ty = ty.c_name().unwrap();
+
+    if ty.ends_with('*') {
+        format!("{ty}{name}")
+    } else {
+        format!("{ty} {name}")
+    }
+}
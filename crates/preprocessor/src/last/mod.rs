@@ -1,24 +1,48 @@
 pub mod declaration;
+pub mod dump;
 pub mod expression;
+pub mod header;
 pub mod operation;
 pub mod statement;
 pub mod unit;
 
-use std::rc::Rc;
+use std::{collections::HashMap, rc::Rc};
 
+use common::constants::builtins::ENTRY_POINT;
 use unit::LASTUnit;
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct LoweredAbstractSyntaxTree {
     root: Vec<Rc<LASTUnit>>,
+    function_names: HashMap<String, String>,
 }
 
 impl LoweredAbstractSyntaxTree {
-    pub fn new(root: Vec<Rc<LASTUnit>>) -> Self {
-        Self { root }
+    pub fn new(root: Vec<Rc<LASTUnit>>, function_names: HashMap<String, String>) -> Self {
+        Self {
+            root,
+            function_names,
+        }
     }
 
     pub fn root(&self) -> &Vec<Rc<LASTUnit>> {
         &self.root
     }
+
+    // mangled name of the program's `main`, if it declares one
+    pub fn entry_point(&self) -> Option<&str> {
+        self.function_names
+            .iter()
+            .find(|(_, name)| *name == ENTRY_POINT)
+            .map(|(mangled, _)| mangled.as_str())
+    }
+
+    // source name of a mangled function
+    pub fn function_name<'a>(&'a self, mangled: &'a str) -> &'a str {
+        self.function_names
+            .get(mangled)
+            .map(String::as_str)
+            .unwrap_or(mangled)
+    }
 }
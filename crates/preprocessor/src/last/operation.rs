@@ -1,18 +1,33 @@
-pub use parser::ast::operation::{AlgebraicOperation, LogicalOperation};
+use std::str::FromStr;
+
+pub use parser::ast::operation::{AlgebraicOperation, LogicalOperation, UnknownOperation};
 
 #[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum Operation {
     Algebraic(AlgebraicOperation),
     Logical(LogicalOperation),
     Assignment,
 }
 
+// every assignment operator lowers to a plain assignment
+impl FromStr for Operation {
+    type Err = UnknownOperation;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        value
+            .parse::<parser::ast::operation::Operation>()
+            .map(|operation| (&operation).into())
+    }
+}
+
 impl Operation {
-    pub fn from_str(value: &str) -> Option<Self> {
-        AlgebraicOperation::from_str(value)
-            .map(|alg| Self::Algebraic(alg))
-            .or(LogicalOperation::from_str(value).map(|log| Self::Logical(log)))
-            .or(Some(Self::Assignment))
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Algebraic(alg) => alg.as_str(),
+            Self::Logical(log) => log.as_str(),
+            Self::Assignment => "=",
+        }
     }
 }
 
@@ -20,7 +35,7 @@ impl From<&parser::ast::operation::Operation> for Operation {
     fn from(value: &parser::ast::operation::Operation) -> Self {
         match value {
             parser::ast::operation::Operation::Algebraic(alg) => Self::Algebraic(alg.clone()),
-            parser::ast::operation::Operation::Logical(log) => Self::Logical(log.clone()),
+            parser::ast::operation::Operation::Logical(log) => Self::Logical(*log),
             parser::ast::operation::Operation::Assignment(_) => Self::Assignment,
         }
     }
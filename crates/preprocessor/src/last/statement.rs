@@ -3,6 +3,7 @@ use std::rc::Rc;
 use super::{expression::Expression, unit::LASTUnit};
 
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum Statement {
     ControlFlow {
         condition: Rc<Expression>,
@@ -14,5 +15,12 @@ pub enum Statement {
         condition: Rc<Expression>,
         body: Vec<Rc<LASTUnit>>,
     },
+    // cases are keyed by the value, without a default the value is one of them
+    Switch {
+        value: Rc<Expression>,
+        cases: Vec<(u64, Vec<Rc<LASTUnit>>)>,
+        default: Option<Vec<Rc<LASTUnit>>>,
+    },
     Return(Rc<Expression>),
+    Unreachable,
 }
@@ -1,8 +1,13 @@
+use common::span::Span;
+
 use super::{declaration::Declaration, expression::Expression, statement::Statement};
 
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum LASTUnit {
     Statement(Statement),
     Expression(Expression),
     Declaration(Declaration),
+    // source position of the units that follow
+    Location(Span),
 }
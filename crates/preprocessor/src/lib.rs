@@ -1,50 +1,131 @@
 pub mod last;
 pub mod mangler;
+pub mod modules;
 pub mod scope;
+pub mod typecheck;
 
-use std::{borrow::Cow, collections::HashMap, rc::Rc};
+use std::{borrow::Cow, cell::RefCell, collections::HashSet, rc::Rc};
 
+use common::{
+    constants::builtins::{BUILTINS, OVERLOADED_BUILTINS},
+    diagnostic::Diagnostic,
+    types::Type,
+};
 use last::{
     declaration::{Declaration, VariableAllocation},
-    expression::{self, Expression},
+    expression::{Expression, Literal},
     operation::Operation,
     statement::Statement,
     unit::LASTUnit,
     LoweredAbstractSyntaxTree,
 };
 use mangler::Mangler;
-use parser::ast::{operation::AssignmentOperation, unit::ASTUnit, AbstractSyntaxTree};
+use parser::ast::{
+    pattern::{MatchArm, Pattern},
+    unit::ASTUnit,
+    AbstractSyntaxTree,
+};
 use scope::{Remapper, Scope};
+use typecheck::{literal_type, ExpressionTypes, TypeChecker};
 
 pub struct Preprocessor {
     fn_mangler: Mangler,
+    types: RefCell<ExpressionTypes>,
+    // declared functions shadow the builtins of the same name
+    declared: RefCell<HashSet<String>>,
+}
+
+// what the arms of a match are lowered against
+struct MatchContext<'a> {
+    scrutinee: String,
+    ty: Type,
+    result: String,
+    mangler: &'a Mangler,
+    scope: &'a Scope,
+}
+
+// a pattern is lowered into the tests deciding whether it matches,
+// whose results are the conditions, and the bindings made when it does
+#[derive(Default)]
+struct LoweredPattern {
+    tests: Vec<Rc<LASTUnit>>,
+    conditions: Vec<String>,
+    bindings: Vec<Rc<LASTUnit>>,
+}
+
+impl Default for Preprocessor {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Preprocessor {
     pub fn new() -> Self {
         Self {
             fn_mangler: Mangler::new(),
+            types: RefCell::new(ExpressionTypes::default()),
+            declared: RefCell::new(HashSet::new()),
         }
     }
 
     pub fn run(&self, ast: AbstractSyntaxTree) -> LoweredAbstractSyntaxTree {
+        self.try_run(ast)
+            .unwrap_or_else(|diagnostic| panic!("{diagnostic}"))
+    }
+
+    pub fn try_run(
+        &self,
+        ast: AbstractSyntaxTree,
+    ) -> Result<LoweredAbstractSyntaxTree, Diagnostic> {
+        *self.types.borrow_mut() = TypeChecker::new().run(&ast)?;
+
         let root = match ast.root() {
             ASTUnit::Block(block) => block,
             _ => unreachable!(),
         };
 
+        // extern and exported functions keep their names,
+        // even when called before they are declared
+        for unit in root {
+            if let ASTUnit::Declaration(
+                parser::ast::declaration::Declaration::ExternFunctionDeclaration {
+                    identifier, ..
+                }
+                | parser::ast::declaration::Declaration::FunctionDeclaration { identifier, .. },
+            ) = unit.as_ref()
+            {
+                self.declared.borrow_mut().insert(identifier.clone());
+            }
+
+            if let ASTUnit::Declaration(
+                parser::ast::declaration::Declaration::ExternFunctionDeclaration {
+                    identifier, ..
+                }
+                | parser::ast::declaration::Declaration::FunctionDeclaration {
+                    identifier,
+                    export: Some(_),
+                    ..
+                },
+            ) = unit.as_ref()
+            {
+                self.fn_mangler.keep(identifier);
+            }
+        }
+
         let root_scope = Scope::new();
 
         let last_root = root
             .iter()
             .map(Rc::clone)
-            .map(|unit| self.run_internal(unit, &Mangler::new(), None, Some(&root_scope), None))
-            .flatten()
+            .flat_map(|unit| {
+                self.run_internal(unit, &Mangler::new(), None, Some(&root_scope), None)
+            })
             .collect();
 
-        let last = LoweredAbstractSyntaxTree::new(last_root);
-
-        last
+        Ok(LoweredAbstractSyntaxTree::new(
+            last_root,
+            self.fn_mangler.names(),
+        ))
     }
 
     fn run_internal(
@@ -79,12 +160,13 @@ impl Preprocessor {
                 scope.unwrap_or(&Scope::new()),
                 remap,
             ),
+            ASTUnit::Location(span) => vec![Rc::new(LASTUnit::Location(*span))],
         }
     }
 
     fn run_block(
         &self,
-        block: &Vec<Rc<ASTUnit>>,
+        block: &[Rc<ASTUnit>],
         mangler: &Mangler,
         store_result_in: Option<String>,
         scope: &Scope,
@@ -95,7 +177,7 @@ impl Preprocessor {
         block
             .iter()
             .map(Rc::clone)
-            .map(|unit| {
+            .flat_map(|unit| {
                 self.run_internal(
                     unit,
                     mangler,
@@ -104,7 +186,6 @@ impl Preprocessor {
                     Some(remap.unwrap_or(&remaps_new)),
                 )
             })
-            .flatten()
             .collect()
     }
 
@@ -149,6 +230,7 @@ impl Preprocessor {
                     LASTUnit::Declaration(Declaration::VariableDeclaration {
                         allocation: VariableAllocation::SSA,
                         identifier: store_result_in,
+                        ty: None,
                         expression: Rc::new(Expression::Identifier(ret_ssa_name)),
                     })
                 } else {
@@ -183,17 +265,15 @@ impl Preprocessor {
                     remap,
                 );
 
-                let alternative_value = if let Some(alternative) = alternative {
-                    Some(self.run_internal(
+                let alternative_value = alternative.as_ref().map(|alternative| {
+                    self.run_internal(
                         Rc::clone(alternative),
                         mangler,
                         Some(result_ssa_name.clone()),
                         Some(scope),
                         remap,
-                    ))
-                } else {
-                    None
-                };
+                    )
+                });
 
                 LASTUnit::Statement(Statement::ControlFlow {
                     condition: Rc::new(Expression::Identifier(condition_ssa_name)),
@@ -225,6 +305,9 @@ impl Preprocessor {
                     body,
                 })
             }
+            parser::ast::statement::Statement::Match { .. } => {
+                return self.run_match(statement, mangler, store_result_in, scope, remap);
+            }
         };
 
         last_units.push(Rc::new(statement_unit));
@@ -232,6 +315,425 @@ impl Preprocessor {
         last_units
     }
 
+    // enum values are switched on their tag, with the arms of each variant
+    // becoming a chain of control flows, other values go through all arms
+    fn run_match(
+        &self,
+        statement: &parser::ast::statement::Statement,
+        mangler: &Mangler,
+        store_result_in: Option<String>,
+        scope: &Scope,
+        remap: Option<&Remapper>,
+    ) -> Vec<Rc<LASTUnit>> {
+        let parser::ast::statement::Statement::Match { scrutinee, arms } = statement else {
+            unreachable!()
+        };
+
+        let scrutinee_ssa_name = mangler.rng();
+        let mut last_units = self.run_internal(
+            Rc::clone(scrutinee),
+            mangler,
+            Some(scrutinee_ssa_name.clone()),
+            Some(scope),
+            remap,
+        );
+
+        let context = MatchContext {
+            scrutinee: scrutinee_ssa_name.clone(),
+            ty: self.types.borrow().scrutinee(statement).cloned().unwrap(),
+            result: store_result_in.unwrap_or_else(|| mangler.rng()),
+            mangler,
+            scope,
+        };
+
+        let remaps_new = Remapper::new();
+        let remap = remap.unwrap_or(&remaps_new);
+
+        let variants = match &context.ty {
+            Type::Named(name) => self.types.borrow().variants(name).cloned(),
+            _ => None,
+        };
+
+        let Some(variants) = variants else {
+            let arms = arms.iter().collect::<Vec<&MatchArm>>();
+            last_units.append(&mut self.run_arms(&arms, false, &context, remap));
+
+            return last_units;
+        };
+
+        let tag_ssa_name = mangler.rng();
+        last_units.push(Rc::new(LASTUnit::Declaration(
+            Declaration::VariableDeclaration {
+                allocation: VariableAllocation::SSA,
+                identifier: tag_ssa_name.clone(),
+                ty: None,
+                expression: Rc::new(Expression::Tag(Rc::new(Expression::Identifier(
+                    scrutinee_ssa_name,
+                )))),
+            },
+        )));
+
+        let cases = variants
+            .iter()
+            .enumerate()
+            .map(|(tag, variant)| {
+                let arms = arms
+                    .iter()
+                    .filter(|arm| match &arm.pattern {
+                        Pattern::Variant { variant: name, .. } => *name == variant.name,
+                        _ => true,
+                    })
+                    .collect::<Vec<&MatchArm>>();
+
+                (tag as u64, self.run_arms(&arms, true, &context, remap))
+            })
+            .collect();
+
+        last_units.push(Rc::new(LASTUnit::Statement(Statement::Switch {
+            value: Rc::new(Expression::Identifier(tag_ssa_name)),
+            cases,
+            default: None,
+        })));
+
+        last_units
+    }
+
+    // the first arm, falling back to the rest when it does not match,
+    // `skip_tag` is set once the tag of the value is known to be the one of the variant patterns
+    fn run_arms(
+        &self,
+        arms: &[&MatchArm],
+        skip_tag: bool,
+        context: &MatchContext,
+        remap: &Remapper,
+    ) -> Vec<Rc<LASTUnit>> {
+        let Some((arm, rest)) = arms.split_first() else {
+            // the type checker made sure that one of the arms matches
+            return vec![Rc::new(LASTUnit::Statement(Statement::Unreachable))];
+        };
+
+        let mangler = context.mangler;
+        let arm_remap = remap.child();
+        let mut lowered = LoweredPattern::default();
+
+        match &arm.pattern {
+            pattern @ Pattern::Variant { .. } if skip_tag => self.run_payload(
+                pattern,
+                &context.scrutinee,
+                context,
+                &arm_remap,
+                &mut lowered,
+            ),
+            pattern => self.run_pattern(
+                pattern,
+                &context.scrutinee,
+                &context.ty,
+                context,
+                &arm_remap,
+                &mut lowered,
+            ),
+        }
+
+        let mut last_units = lowered.tests;
+
+        let condition = lowered.conditions.into_iter().reduce(|left, right| {
+            let condition_ssa_name = mangler.rng();
+
+            last_units.push(Rc::new(LASTUnit::Declaration(
+                Declaration::VariableDeclaration {
+                    allocation: VariableAllocation::SSA,
+                    identifier: condition_ssa_name.clone(),
+                    ty: None,
+                    expression: Rc::new(Expression::BinaryExpression {
+                        left: Rc::new(Expression::Identifier(left)),
+                        right: Rc::new(Expression::Identifier(right)),
+                        operation: Operation::Logical(
+                            parser::ast::operation::LogicalOperation::And,
+                        ),
+                        operands: Some(Type::Bool),
+                    }),
+                },
+            )));
+
+            condition_ssa_name
+        });
+
+        let guard = arm.guard.as_ref().map(|guard| {
+            let guard_ssa_name = mangler.rng();
+            let guard_units = self.run_internal(
+                Rc::clone(guard),
+                mangler,
+                Some(guard_ssa_name.clone()),
+                Some(context.scope),
+                Some(&arm_remap),
+            );
+
+            (guard_ssa_name, guard_units)
+        });
+
+        let body = self.run_internal(
+            Rc::clone(&arm.body),
+            mangler,
+            Some(context.result.clone()),
+            Some(context.scope),
+            Some(&arm_remap),
+        );
+
+        // an arm matching anything leaves the rest unreachable
+        if condition.is_none() && guard.is_none() {
+            last_units.append(&mut lowered.bindings);
+            last_units.extend(body);
+
+            return last_units;
+        }
+
+        // lowered once, it is shared by both ways of not matching
+        let rest = self.run_arms(rest, skip_tag, context, remap);
+
+        let mut taken = lowered.bindings;
+
+        match guard {
+            Some((guard_ssa_name, mut guard_units)) => {
+                taken.append(&mut guard_units);
+                taken.push(Rc::new(LASTUnit::Statement(Statement::ControlFlow {
+                    condition: Rc::new(Expression::Identifier(guard_ssa_name)),
+                    execute: body,
+                    alternative: Some(rest.clone()),
+                })));
+            }
+            None => taken.extend(body),
+        }
+
+        match condition {
+            Some(condition) => {
+                last_units.push(Rc::new(LASTUnit::Statement(Statement::ControlFlow {
+                    condition: Rc::new(Expression::Identifier(condition)),
+                    execute: taken,
+                    alternative: Some(rest),
+                })))
+            }
+            None => last_units.append(&mut taken),
+        }
+
+        last_units
+    }
+
+    fn run_pattern(
+        &self,
+        pattern: &Pattern,
+        value: &str,
+        ty: &Type,
+        context: &MatchContext,
+        remap: &Remapper,
+        lowered: &mut LoweredPattern,
+    ) {
+        let mangler = context.mangler;
+
+        match pattern {
+            Pattern::Wildcard => {}
+            Pattern::Binding(name) => {
+                let binding = context.scope.add_to_stack(name.clone());
+                remap.remap(name.clone(), binding.clone());
+
+                lowered.bindings.push(Rc::new(LASTUnit::Declaration(
+                    Declaration::VariableDeclaration {
+                        allocation: VariableAllocation::SSA,
+                        identifier: binding,
+                        ty: Some(ty.clone()),
+                        expression: Rc::new(Expression::Identifier(value.to_string())),
+                    },
+                )));
+            }
+            Pattern::Literal(literal) => {
+                let literal_ssa_name = mangler.rng();
+                lowered.tests.push(Rc::new(LASTUnit::Declaration(
+                    Declaration::VariableDeclaration {
+                        allocation: VariableAllocation::SSA,
+                        identifier: literal_ssa_name.clone(),
+                        ty: None,
+                        expression: Rc::new(Expression::Literal(literal.clone())),
+                    },
+                )));
+
+                let literal_ssa_name = self.run_conversion(
+                    literal_ssa_name,
+                    Some(&literal_type(literal)),
+                    ty,
+                    mangler,
+                    &mut lowered.tests,
+                );
+
+                lowered.conditions.push(self.run_comparison(
+                    value.to_string(),
+                    literal_ssa_name,
+                    ty,
+                    mangler,
+                    &mut lowered.tests,
+                ));
+            }
+            Pattern::Variant {
+                enumeration,
+                variant,
+                ..
+            } => {
+                let tag_ssa_name = mangler.rng();
+                lowered.tests.push(Rc::new(LASTUnit::Declaration(
+                    Declaration::VariableDeclaration {
+                        allocation: VariableAllocation::SSA,
+                        identifier: tag_ssa_name.clone(),
+                        ty: None,
+                        expression: Rc::new(Expression::Tag(Rc::new(Expression::Identifier(
+                            value.to_string(),
+                        )))),
+                    },
+                )));
+
+                let (tag, _) = self.types.borrow().variant(enumeration, variant).unwrap();
+
+                let expected_ssa_name = mangler.rng();
+                lowered.tests.push(Rc::new(LASTUnit::Declaration(
+                    Declaration::VariableDeclaration {
+                        allocation: VariableAllocation::SSA,
+                        identifier: expected_ssa_name.clone(),
+                        ty: None,
+                        expression: Rc::new(Expression::Literal(Literal::UInt32(tag))),
+                    },
+                )));
+
+                lowered.conditions.push(self.run_comparison(
+                    tag_ssa_name,
+                    expected_ssa_name,
+                    &Type::UInt32,
+                    mangler,
+                    &mut lowered.tests,
+                ));
+
+                self.run_payload(pattern, value, context, remap, lowered);
+            }
+            Pattern::Tuple(elements) => {
+                let Type::Tuple(types) = ty else {
+                    unreachable!()
+                };
+
+                for (index, (pattern, ty)) in elements.iter().zip(types).enumerate() {
+                    if *pattern == Pattern::Wildcard {
+                        continue;
+                    }
+
+                    let element_ssa_name = mangler.rng();
+                    let element =
+                        Rc::new(LASTUnit::Declaration(Declaration::VariableDeclaration {
+                            allocation: VariableAllocation::SSA,
+                            identifier: element_ssa_name.clone(),
+                            ty: None,
+                            expression: Rc::new(Expression::FieldAccess {
+                                target: Rc::new(Expression::Identifier(value.to_string())),
+                                field: index.to_string(),
+                                index: index as u32,
+                            }),
+                        }));
+
+                    // like the fields of a variant
+                    if pattern.is_irrefutable() {
+                        lowered.bindings.push(element);
+                    } else {
+                        lowered.tests.push(element);
+                    }
+
+                    self.run_pattern(pattern, &element_ssa_name, ty, context, remap, lowered);
+                }
+            }
+        }
+    }
+
+    // matches the fields of a variant pattern, refutable ones are extracted to be tested,
+    // the others only once the arm is taken
+    fn run_payload(
+        &self,
+        pattern: &Pattern,
+        value: &str,
+        context: &MatchContext,
+        remap: &Remapper,
+        lowered: &mut LoweredPattern,
+    ) {
+        let Pattern::Variant {
+            enumeration,
+            variant,
+            fields,
+        } = pattern
+        else {
+            unreachable!()
+        };
+
+        let declared = self
+            .types
+            .borrow()
+            .variant(enumeration, variant)
+            .map(|(_, declared)| declared.clone())
+            .unwrap();
+
+        for (name, pattern) in fields {
+            if *pattern == Pattern::Wildcard {
+                continue;
+            }
+
+            let (index, (_, ty)) = declared
+                .fields
+                .iter()
+                .enumerate()
+                .find(|(_, (field, _))| field == name)
+                .unwrap();
+
+            let payload_ssa_name = context.mangler.rng();
+            let payload = Rc::new(LASTUnit::Declaration(Declaration::VariableDeclaration {
+                allocation: VariableAllocation::SSA,
+                identifier: payload_ssa_name.clone(),
+                ty: None,
+                expression: Rc::new(Expression::Payload {
+                    target: Rc::new(Expression::Identifier(value.to_string())),
+                    name: enumeration.clone(),
+                    variant: variant.clone(),
+                    index: index as u32,
+                }),
+            }));
+
+            if pattern.is_irrefutable() {
+                lowered.bindings.push(payload);
+            } else {
+                lowered.tests.push(payload);
+            }
+
+            self.run_pattern(pattern, &payload_ssa_name, ty, context, remap, lowered);
+        }
+    }
+
+    fn run_comparison(
+        &self,
+        left: String,
+        right: String,
+        ty: &Type,
+        mangler: &Mangler,
+        units: &mut Vec<Rc<LASTUnit>>,
+    ) -> String {
+        let condition_ssa_name = mangler.rng();
+
+        units.push(Rc::new(LASTUnit::Declaration(
+            Declaration::VariableDeclaration {
+                allocation: VariableAllocation::SSA,
+                identifier: condition_ssa_name.clone(),
+                ty: None,
+                expression: Rc::new(Expression::BinaryExpression {
+                    left: Rc::new(Expression::Identifier(left)),
+                    right: Rc::new(Expression::Identifier(right)),
+                    operation: Operation::Logical(parser::ast::operation::LogicalOperation::Equal),
+                    operands: Some(ty.clone()),
+                }),
+            },
+        )));
+
+        condition_ssa_name
+    }
+
     fn run_declaration(
         &self,
         declaration: &parser::ast::declaration::Declaration,
@@ -242,11 +744,27 @@ impl Preprocessor {
         let mut last_units = vec![];
 
         let declaration_unit = match declaration {
+            parser::ast::declaration::Declaration::TypeDeclaration {
+                identifier, fields, ..
+            } => LASTUnit::Declaration(Declaration::TypeDeclaration {
+                identifier: identifier.clone(),
+                fields: fields.clone(),
+            }),
+            parser::ast::declaration::Declaration::EnumDeclaration {
+                identifier,
+                variants,
+                ..
+            } => LASTUnit::Declaration(Declaration::EnumDeclaration {
+                identifier: identifier.clone(),
+                variants: variants.clone(),
+            }),
             parser::ast::declaration::Declaration::FunctionDeclaration {
                 identifier,
                 parameters,
                 return_type,
                 expression,
+                export,
+                ..
             } => {
                 let identifier = self.fn_mangler.mangle(Cow::Borrowed(identifier));
 
@@ -254,6 +772,7 @@ impl Preprocessor {
 
                 let declaration = Declaration::FunctionDeclaration {
                     identifier,
+                    exported: export.is_some(),
                     parameters: parameters.clone(),
                     return_type: return_type.clone(),
                     body: match expression.as_ref() {
@@ -262,36 +781,37 @@ impl Preprocessor {
                     }
                     .iter()
                     .map(Rc::clone)
-                    .map(|unit| self.run_internal(unit, mangler, None, Some(&fn_scope), remap))
-                    .flatten()
+                    .flat_map(|unit| self.run_internal(unit, mangler, None, Some(&fn_scope), remap))
                     .collect(),
                 };
 
                 LASTUnit::Declaration(declaration)
             }
+            // the ABI is checked to be C
+            parser::ast::declaration::Declaration::ExternFunctionDeclaration {
+                identifier,
+                parameters,
+                variadic,
+                return_type,
+                ..
+            } => LASTUnit::Declaration(Declaration::ExternFunctionDeclaration {
+                identifier: identifier.clone(),
+                parameters: parameters.clone(),
+                variadic: *variadic,
+                return_type: return_type.clone(),
+            }),
+            // rejected by the typecheck
+            parser::ast::declaration::Declaration::Import { .. } => unreachable!(),
             parser::ast::declaration::Declaration::VariableDeclaration {
                 keyword,
                 identifier,
+                ty,
                 expression,
             } => {
                 let ident_tmp = mangler.rng();
 
-                let mut expression_result = match expression.as_ref() {
-                    ASTUnit::Expression(expression) => {
-                        self.run_expression(expression, ident_tmp.clone(), mangler, remap)
-                    }
-                    ASTUnit::Block(block) => {
-                        self.run_block(block, mangler, Some(ident_tmp.clone()), scope, remap)
-                    }
-                    ASTUnit::Statement(statement) => self.run_statement(
-                        statement,
-                        mangler,
-                        Some(ident_tmp.clone()),
-                        scope,
-                        remap,
-                    ),
-                    ASTUnit::Declaration(_) => panic!("cannot use declaration in expression"),
-                };
+                let mut expression_result =
+                    self.run_initializer(expression, ident_tmp.clone(), mangler, scope, remap);
 
                 last_units.append(&mut expression_result);
 
@@ -303,21 +823,59 @@ impl Preprocessor {
                     }
                 }
 
+                let inferred = match expression.as_ref() {
+                    ASTUnit::Expression(expression) => self.types.borrow().get(expression).cloned(),
+                    _ => None,
+                };
+
                 let declaration = Declaration::VariableDeclaration {
-                    allocation: match keyword {
-                        parser::ast::declaration::VariableDeclarationKeyword::Const => {
-                            VariableAllocation::SSA
-                        }
-                        parser::ast::declaration::VariableDeclarationKeyword::Let => {
-                            VariableAllocation::Stack
-                        }
-                    },
+                    allocation: allocation_for(keyword),
                     identifier: identifier_new,
-                    expression: Rc::new(Expression::Identifier(ident_tmp)),
+                    ty: ty.clone().or_else(|| inferred.clone()),
+                    // a declared type converts the value to it
+                    expression: Rc::new(match ty {
+                        Some(ty) => Expression::Cast {
+                            expression: Rc::new(Expression::Identifier(ident_tmp)),
+                            from: inferred,
+                            ty: ty.clone(),
+                        },
+                        None => Expression::Identifier(ident_tmp),
+                    }),
                 };
 
                 LASTUnit::Declaration(declaration)
             }
+            // tuples only cast into themselves, so a declared type converts nothing
+            parser::ast::declaration::Declaration::DestructuringDeclaration {
+                keyword,
+                pattern,
+                ty,
+                expression,
+            } => {
+                let value_tmp = mangler.rng();
+                let ty = match expression.as_ref() {
+                    ASTUnit::Expression(expression) => ty
+                        .clone()
+                        .or_else(|| self.types.borrow().get(expression).cloned()),
+                    _ => ty.clone(),
+                };
+
+                let mut expression_result =
+                    self.run_initializer(expression, value_tmp.clone(), mangler, scope, remap);
+                last_units.append(&mut expression_result);
+
+                last_units.append(&mut self.run_destructuring(
+                    pattern,
+                    value_tmp,
+                    ty.as_ref(),
+                    keyword,
+                    mangler,
+                    scope,
+                    remap,
+                ));
+
+                return last_units;
+            }
         };
 
         last_units.push(Rc::new(declaration_unit));
@@ -325,6 +883,105 @@ impl Preprocessor {
         last_units
     }
 
+    // the value a variable is declared with, stored in `identifier`
+    fn run_initializer(
+        &self,
+        expression: &ASTUnit,
+        identifier: String,
+        mangler: &Mangler,
+        scope: &Scope,
+        remap: Option<&Remapper>,
+    ) -> Vec<Rc<LASTUnit>> {
+        match expression {
+            ASTUnit::Expression(expression) => {
+                self.run_expression(expression, identifier, mangler, remap)
+            }
+            ASTUnit::Block(block) => self.run_block(block, mangler, Some(identifier), scope, remap),
+            ASTUnit::Statement(statement) => {
+                self.run_statement(statement, mangler, Some(identifier), scope, remap)
+            }
+            ASTUnit::Declaration(_) => panic!("cannot use declaration in expression"),
+            ASTUnit::Location(_) => unreachable!(),
+        }
+    }
+
+    // every binding of the pattern becomes a variable of its own,
+    // the type checker made sure the pattern cannot fail
+    #[allow(clippy::too_many_arguments)]
+    fn run_destructuring(
+        &self,
+        pattern: &Pattern,
+        value: String,
+        ty: Option<&Type>,
+        keyword: &parser::ast::declaration::VariableDeclarationKeyword,
+        mangler: &Mangler,
+        scope: &Scope,
+        remap: Option<&Remapper>,
+    ) -> Vec<Rc<LASTUnit>> {
+        match pattern {
+            Pattern::Wildcard => vec![],
+            Pattern::Binding(name) => {
+                let identifier = scope.add_to_stack(name.clone());
+
+                if &identifier != name {
+                    if let Some(remaps) = remap {
+                        remaps.remap(name.clone(), identifier.clone());
+                    }
+                }
+
+                vec![Rc::new(LASTUnit::Declaration(
+                    Declaration::VariableDeclaration {
+                        allocation: allocation_for(keyword),
+                        identifier,
+                        ty: ty.cloned(),
+                        expression: Rc::new(Expression::Identifier(value)),
+                    },
+                ))]
+            }
+            Pattern::Tuple(elements) => {
+                let mut last_units = vec![];
+
+                for (index, pattern) in elements.iter().enumerate() {
+                    if *pattern == Pattern::Wildcard {
+                        continue;
+                    }
+
+                    let element_ssa_name = mangler.rng();
+                    last_units.push(Rc::new(LASTUnit::Declaration(
+                        Declaration::VariableDeclaration {
+                            allocation: VariableAllocation::SSA,
+                            identifier: element_ssa_name.clone(),
+                            ty: None,
+                            expression: Rc::new(Expression::FieldAccess {
+                                target: Rc::new(Expression::Identifier(value.clone())),
+                                field: index.to_string(),
+                                index: index as u32,
+                            }),
+                        },
+                    )));
+
+                    let element_ty = match ty {
+                        Some(Type::Tuple(types)) => types.get(index),
+                        _ => None,
+                    };
+
+                    last_units.append(&mut self.run_destructuring(
+                        pattern,
+                        element_ssa_name,
+                        element_ty,
+                        keyword,
+                        mangler,
+                        scope,
+                        remap,
+                    ));
+                }
+
+                last_units
+            }
+            Pattern::Literal(_) | Pattern::Variant { .. } => unreachable!(),
+        }
+    }
+
     fn run_expression(
         &self,
         expression: &parser::ast::expression::Expression,
@@ -353,26 +1010,255 @@ impl Preprocessor {
                 function_name,
                 parameters,
             } => {
-                let args = vec![];
+                let mut args = vec![];
 
-                // for param in parameters {
-                //   match param {
-                //     ASTUnit::Declaration(_) => panic!("cannot use declaration as function argument"),
-                //     ASTUnit::
-                //   }
-                // }
+                for param in parameters {
+                    let arg_ssa_name = mangler.rng();
+                    let mut arg_value = match param.as_ref() {
+                        ASTUnit::Expression(expr) => {
+                            self.run_expression(expr, arg_ssa_name.clone(), mangler, remap)
+                        }
+                        ASTUnit::Declaration(_) => {
+                            panic!("cannot use declaration as function argument")
+                        }
+                        _ => self.run_internal(
+                            Rc::clone(param),
+                            mangler,
+                            Some(arg_ssa_name.clone()),
+                            None,
+                            remap,
+                        ),
+                    };
+
+                    expression_units.append(&mut arg_value);
+                    args.push(Expression::Identifier(arg_ssa_name));
+                }
+
+                let builtin = BUILTINS.contains(&function_name.as_str())
+                    && !self.declared.borrow().contains(function_name);
 
                 Some(Expression::FunctionInvokation {
-                    name: self.fn_mangler.mangle(Cow::Borrowed(function_name)),
+                    name: if builtin && OVERLOADED_BUILTINS.contains(&function_name.as_str()) {
+                        // resolved by the type of the arguments, which are all the same
+                        let types = self.types.borrow();
+                        let ty = parameters.iter().find_map(|param| match param.as_ref() {
+                            ASTUnit::Expression(expr) => types.get(expr),
+                            _ => None,
+                        });
+
+                        match ty {
+                            Some(ty) => format!("{function_name}.{ty}"),
+                            None => function_name.clone(),
+                        }
+                    } else if builtin {
+                        function_name.clone()
+                    } else {
+                        self.fn_mangler.mangle(Cow::Borrowed(function_name))
+                    },
                     args,
                 })
             }
+            parser::ast::expression::Expression::Index { target, index } => {
+                let target = self.run_indexed(target, mangler, remap, &mut expression_units);
+                let index = self.run_operand(index, mangler, remap, &mut expression_units);
+
+                Some(Expression::Index {
+                    target: Rc::new(target),
+                    index: Rc::new(index),
+                    element: self.types.borrow().get(expression).cloned(),
+                })
+            }
+            parser::ast::expression::Expression::Slice { target, start, end } => {
+                let target = self.run_indexed(target, mangler, remap, &mut expression_units);
+
+                let mut bound = |bound: &Option<Rc<ASTUnit>>| {
+                    bound.as_ref().map(|bound| {
+                        Rc::new(self.run_operand(bound, mangler, remap, &mut expression_units))
+                    })
+                };
+                let start = bound(start);
+                let end = bound(end);
+
+                Some(Expression::Slice {
+                    target: Rc::new(target),
+                    start,
+                    end,
+                    element: match self.types.borrow().get(expression) {
+                        Some(Type::Slice(element)) => Some(*element.clone()),
+                        _ => None,
+                    },
+                })
+            }
+            parser::ast::expression::Expression::Cast { expression, ty } => {
+                let operand_ssa_name = mangler.rng();
+                let operand = match expression.as_ref() {
+                    ASTUnit::Expression(expr) => expr,
+                    _ => unreachable!(),
+                };
+
+                let mut operand_value =
+                    self.run_expression(operand, operand_ssa_name.clone(), mangler, remap);
+
+                expression_units.append(&mut operand_value);
+
+                Some(Expression::Cast {
+                    expression: Rc::new(Expression::Identifier(operand_ssa_name)),
+                    from: self.types.borrow().get(operand).cloned(),
+                    ty: ty.clone(),
+                })
+            }
+            parser::ast::expression::Expression::Struct { identifier, fields } => {
+                let declared = self.types.borrow().fields(identifier).cloned().unwrap();
+
+                Some(Expression::Struct {
+                    name: identifier.clone(),
+                    fields: self.run_fields(
+                        fields,
+                        &declared,
+                        mangler,
+                        remap,
+                        &mut expression_units,
+                    ),
+                })
+            }
+            parser::ast::expression::Expression::Variant {
+                enumeration,
+                variant,
+                fields,
+            } => {
+                let (tag, declared) = self
+                    .types
+                    .borrow()
+                    .variant(enumeration, variant)
+                    .map(|(tag, declared)| (tag, declared.fields.clone()))
+                    .unwrap();
+
+                Some(Expression::Variant {
+                    name: enumeration.clone(),
+                    variant: variant.clone(),
+                    tag,
+                    fields: self.run_fields(
+                        fields,
+                        &declared,
+                        mangler,
+                        remap,
+                        &mut expression_units,
+                    ),
+                })
+            }
+            parser::ast::expression::Expression::AddressOf(place) => Some(Expression::AddressOf(
+                Rc::new(self.run_place(place, mangler, remap, &mut expression_units)),
+            )),
+            parser::ast::expression::Expression::Deref(pointer) => {
+                let pointer_ssa_name = mangler.rng();
+                let pointer = match pointer.as_ref() {
+                    ASTUnit::Expression(expr) => expr,
+                    _ => unreachable!(),
+                };
+
+                let mut pointer_value =
+                    self.run_expression(pointer, pointer_ssa_name.clone(), mangler, remap);
+                expression_units.append(&mut pointer_value);
+
+                Some(Expression::Deref {
+                    target: Rc::new(Expression::Identifier(pointer_ssa_name)),
+                    ty: self.types.borrow().get(expression).cloned().unwrap(),
+                })
+            }
+            parser::ast::expression::Expression::Tuple(elements) => {
+                let mut values = vec![];
+
+                for element in elements {
+                    let element_ssa_name = mangler.rng();
+                    let element = match element.as_ref() {
+                        ASTUnit::Expression(expr) => expr,
+                        _ => unreachable!(),
+                    };
+
+                    let mut element_units =
+                        self.run_expression(element, element_ssa_name.clone(), mangler, remap);
+                    expression_units.append(&mut element_units);
+
+                    values.push(Expression::Identifier(element_ssa_name));
+                }
+
+                Some(Expression::Tuple(values))
+            }
+            parser::ast::expression::Expression::Array(elements) => {
+                let element = match self.types.borrow().get(expression) {
+                    Some(Type::Array(element, _)) => Some(*element.clone()),
+                    _ => None,
+                };
+
+                let mut values = vec![];
+
+                for value in elements {
+                    let value_ssa_name = mangler.rng();
+                    let value = match value.as_ref() {
+                        ASTUnit::Expression(expr) => expr,
+                        _ => unreachable!(),
+                    };
+
+                    let mut value_units =
+                        self.run_expression(value, value_ssa_name.clone(), mangler, remap);
+                    expression_units.append(&mut value_units);
+
+                    let from = self.types.borrow().get(value).cloned();
+
+                    values.push(Expression::Identifier(match (from, &element) {
+                        (Some(from), Some(to)) => self.run_conversion(
+                            value_ssa_name,
+                            Some(&from),
+                            to,
+                            mangler,
+                            &mut expression_units,
+                        ),
+                        _ => value_ssa_name,
+                    }));
+                }
+
+                Some(Expression::Array(values))
+            }
+            parser::ast::expression::Expression::ArrayRepeat { value, count } => {
+                let value_ssa_name = mangler.rng();
+                let value = match value.as_ref() {
+                    ASTUnit::Expression(expr) => expr,
+                    _ => unreachable!(),
+                };
+
+                let mut value_units =
+                    self.run_expression(value, value_ssa_name.clone(), mangler, remap);
+                expression_units.append(&mut value_units);
+
+                Some(Expression::ArrayRepeat {
+                    value: Rc::new(Expression::Identifier(value_ssa_name)),
+                    count: *count,
+                })
+            }
+            parser::ast::expression::Expression::FieldAccess { target, field } => {
+                let target_ssa_name = mangler.rng();
+                let target = match target.as_ref() {
+                    ASTUnit::Expression(expr) => expr,
+                    _ => unreachable!(),
+                };
+
+                let mut target_value =
+                    self.run_expression(target, target_ssa_name.clone(), mangler, remap);
+
+                expression_units.append(&mut target_value);
+
+                Some(Expression::FieldAccess {
+                    target: Rc::new(Expression::Identifier(target_ssa_name)),
+                    field: field.clone(),
+                    index: self.field_index(target, field),
+                })
+            }
             parser::ast::expression::Expression::BinaryExpression {
                 left,
                 right,
                 operation,
             } => match operation {
-                parser::ast::operation::Operation::Assignment(assign) => {
+                parser::ast::operation::Operation::Assignment(_) => {
                     let rhs = match right.as_ref() {
                         ASTUnit::Expression(expr) => expr,
                         _ => unreachable!(),
@@ -383,19 +1269,31 @@ impl Preprocessor {
 
                     expression_units.append(&mut rhs_expr);
 
+                    let from = self.types.borrow().get(rhs).cloned();
+                    let to = match left.as_ref() {
+                        ASTUnit::Expression(lhs) => self.types.borrow().get(lhs).cloned(),
+                        _ => None,
+                    };
+
+                    let rhs_ssa_name = match (from, &to) {
+                        (Some(from), Some(to)) => self.run_conversion(
+                            rhs_ssa_name,
+                            Some(&from),
+                            to,
+                            mangler,
+                            &mut expression_units,
+                        ),
+                        _ => rhs_ssa_name,
+                    };
+
+                    let place = self.run_place(left, mangler, remap, &mut expression_units);
+
                     expression_units.push(Rc::new(LASTUnit::Expression(
                         Expression::BinaryExpression {
-                            left: Rc::new(Expression::Identifier(match left.as_ref() {
-                                ASTUnit::Expression(expr) => match expr {
-                                    parser::ast::expression::Expression::Identifier(ident) => {
-                                        ident.to_string()
-                                    }
-                                    _ => unreachable!(),
-                                },
-                                _ => unreachable!(),
-                            })),
+                            left: Rc::new(place),
                             right: Rc::new(Expression::Identifier(rhs_ssa_name)),
                             operation: Operation::Assignment,
+                            operands: to,
                         },
                     )));
 
@@ -421,14 +1319,48 @@ impl Preprocessor {
                     expression_units.append(&mut rhs_expr);
                     expression_units.append(&mut lhs_expr);
 
+                    // both operands convert to the type the checker unified them to
+                    let operands = self.types.borrow().operands(expression).cloned();
+                    let (lhs_ssa_name, rhs_ssa_name) = match &operands {
+                        Some(operands) => {
+                            let lhs_type = self.types.borrow().get(lhs).cloned();
+                            let rhs_type = self.types.borrow().get(rhs).cloned();
+
+                            let rhs_ssa_name = match rhs_type {
+                                Some(from) => self.run_conversion(
+                                    rhs_ssa_name,
+                                    Some(&from),
+                                    operands,
+                                    mangler,
+                                    &mut expression_units,
+                                ),
+                                None => rhs_ssa_name,
+                            };
+                            let lhs_ssa_name = match lhs_type {
+                                Some(from) => self.run_conversion(
+                                    lhs_ssa_name,
+                                    Some(&from),
+                                    operands,
+                                    mangler,
+                                    &mut expression_units,
+                                ),
+                                None => lhs_ssa_name,
+                            };
+
+                            (lhs_ssa_name, rhs_ssa_name)
+                        }
+                        None => (lhs_ssa_name, rhs_ssa_name),
+                    };
+
                     Some(Expression::BinaryExpression {
                         left: Rc::new(Expression::Identifier(lhs_ssa_name)),
                         right: Rc::new(Expression::Identifier(rhs_ssa_name)),
                         operation: match operation {
                             parser::ast::operation::Operation::Algebraic(_)
                             | parser::ast::operation::Operation::Logical(_) => operation.into(),
-                            parser::ast::operation::Operation::Assignment(assign) => unreachable!(),
+                            parser::ast::operation::Operation::Assignment(_) => unreachable!(),
                         },
+                        operands,
                     })
                 }
             },
@@ -438,6 +1370,7 @@ impl Preprocessor {
             let result_ssa = LASTUnit::Declaration(Declaration::VariableDeclaration {
                 allocation: VariableAllocation::SSA,
                 identifier,
+                ty: None,
                 expression: Rc::new(expression),
             });
 
@@ -446,4 +1379,203 @@ impl Preprocessor {
 
         expression_units
     }
+
+    // values of a struct or variant are evaluated in the order they are written,
+    // converted to the field types and arranged in declaration order
+    fn run_fields(
+        &self,
+        fields: &[(String, Rc<ASTUnit>)],
+        declared: &[(String, Type)],
+        mangler: &Mangler,
+        remap: Option<&Remapper>,
+        units: &mut Vec<Rc<LASTUnit>>,
+    ) -> Vec<Expression> {
+        let mut values = vec![];
+
+        for (name, value) in fields {
+            let value_ssa_name = mangler.rng();
+            let value = match value.as_ref() {
+                ASTUnit::Expression(expr) => expr,
+                _ => unreachable!(),
+            };
+
+            let mut value_units =
+                self.run_expression(value, value_ssa_name.clone(), mangler, remap);
+            units.append(&mut value_units);
+
+            values.push((name, value, value_ssa_name));
+        }
+
+        let types = self.types.borrow();
+
+        declared
+            .iter()
+            .map(|(field, ty)| {
+                let (_, value, value_ssa_name) =
+                    values.iter().find(|(name, _, _)| *name == field).unwrap();
+                Expression::Identifier(self.run_conversion(
+                    value_ssa_name.clone(),
+                    types.get(value),
+                    ty,
+                    mangler,
+                    units,
+                ))
+            })
+            .collect()
+    }
+
+    // casts a value stored into a place of another type, like a declared variable type
+    fn run_conversion(
+        &self,
+        value: String,
+        from: Option<&Type>,
+        to: &Type,
+        mangler: &Mangler,
+        units: &mut Vec<Rc<LASTUnit>>,
+    ) -> String {
+        if from == Some(to) {
+            return value;
+        }
+
+        let cast_ssa_name = mangler.rng();
+
+        units.push(Rc::new(LASTUnit::Declaration(
+            Declaration::VariableDeclaration {
+                allocation: VariableAllocation::SSA,
+                identifier: cast_ssa_name.clone(),
+                ty: None,
+                expression: Rc::new(Expression::Cast {
+                    expression: Rc::new(Expression::Identifier(value)),
+                    from: from.cloned(),
+                    ty: to.clone(),
+                }),
+            },
+        )));
+
+        cast_ssa_name
+    }
+
+    // the variable, field, element or pointee an assignment stores into,
+    // indices and pointers are evaluated into the units before the store
+    fn run_place(
+        &self,
+        place: &ASTUnit,
+        mangler: &Mangler,
+        remap: Option<&Remapper>,
+        units: &mut Vec<Rc<LASTUnit>>,
+    ) -> Expression {
+        match place {
+            ASTUnit::Expression(parser::ast::expression::Expression::Identifier(ident)) => {
+                Expression::Identifier(
+                    remap
+                        .and_then(|remap| remap.get_remapped(ident))
+                        .unwrap_or_else(|| ident.to_string()),
+                )
+            }
+            ASTUnit::Expression(
+                expression @ parser::ast::expression::Expression::Deref(pointer),
+            ) => {
+                let pointer_ssa_name = mangler.rng();
+                let mut pointer_value = match pointer.as_ref() {
+                    ASTUnit::Expression(expr) => {
+                        self.run_expression(expr, pointer_ssa_name.clone(), mangler, remap)
+                    }
+                    _ => unreachable!(),
+                };
+                units.append(&mut pointer_value);
+
+                Expression::Deref {
+                    target: Rc::new(Expression::Identifier(pointer_ssa_name)),
+                    ty: self.types.borrow().get(expression).cloned().unwrap(),
+                }
+            }
+            ASTUnit::Expression(parser::ast::expression::Expression::FieldAccess {
+                target,
+                field,
+            }) => Expression::FieldAccess {
+                target: Rc::new(self.run_place(target, mangler, remap, units)),
+                field: field.clone(),
+                index: match target.as_ref() {
+                    ASTUnit::Expression(target) => self.field_index(target, field),
+                    _ => unreachable!(),
+                },
+            },
+            ASTUnit::Expression(
+                expression @ parser::ast::expression::Expression::Index { target, index },
+            ) => {
+                let target = self.run_indexed(target, mangler, remap, units);
+                let index = self.run_operand(index, mangler, remap, units);
+
+                Expression::Index {
+                    target: Rc::new(target),
+                    index: Rc::new(index),
+                    element: self.types.borrow().get(expression).cloned(),
+                }
+            }
+            _ => panic!("cannot assign to {place:?}"),
+        }
+    }
+
+    // arrays are indexed and sliced where they are stored instead of being
+    // copied first, slices and strings are values pointing to their elements
+    fn run_indexed(
+        &self,
+        target: &ASTUnit,
+        mangler: &Mangler,
+        remap: Option<&Remapper>,
+        units: &mut Vec<Rc<LASTUnit>>,
+    ) -> Expression {
+        let is_array_place = match target {
+            ASTUnit::Expression(
+                expression @ (parser::ast::expression::Expression::Identifier(_)
+                | parser::ast::expression::Expression::FieldAccess { .. }
+                | parser::ast::expression::Expression::Index { .. }
+                | parser::ast::expression::Expression::Deref(_)),
+            ) => matches!(self.types.borrow().get(expression), Some(Type::Array(..))),
+            _ => false,
+        };
+
+        match is_array_place {
+            true => self.run_place(target, mangler, remap, units),
+            false => self.run_operand(target, mangler, remap, units),
+        }
+    }
+
+    // the value of an expression in a new SSA variable
+    fn run_operand(
+        &self,
+        operand: &ASTUnit,
+        mangler: &Mangler,
+        remap: Option<&Remapper>,
+        units: &mut Vec<Rc<LASTUnit>>,
+    ) -> Expression {
+        let ssa_name = mangler.rng();
+        let mut value = match operand {
+            ASTUnit::Expression(expr) => {
+                self.run_expression(expr, ssa_name.clone(), mangler, remap)
+            }
+            _ => unreachable!(),
+        };
+        units.append(&mut value);
+
+        Expression::Identifier(ssa_name)
+    }
+
+    // the type checker made sure the field exists
+    fn field_index(&self, target: &parser::ast::expression::Expression, field: &str) -> u32 {
+        let types = self.types.borrow();
+        let ty = types.get(target).unwrap();
+
+        types.field(ty, field).unwrap().0
+    }
+}
+
+// constants are never stored, variables live on the stack
+fn allocation_for(
+    keyword: &parser::ast::declaration::VariableDeclarationKeyword,
+) -> VariableAllocation {
+    match keyword {
+        parser::ast::declaration::VariableDeclarationKeyword::Const => VariableAllocation::SSA,
+        parser::ast::declaration::VariableDeclarationKeyword::Let => VariableAllocation::Stack,
+    }
 }
@@ -5,6 +5,12 @@ pub struct Mangler {
     mangle_map: RefCell<HashMap<String, String>>,
 }
 
+impl Default for Mangler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Mangler {
     pub fn new() -> Self {
         Self {
@@ -29,6 +35,22 @@ impl Mangler {
         new_ident
     }
 
+    // the identifier stays as it is, like the symbols of extern functions
+    pub fn keep(&self, identifier: &str) {
+        self.mangle_map
+            .borrow_mut()
+            .insert(identifier.to_string(), identifier.to_string());
+    }
+
+    // mangled identifiers mapped back to the source ones
+    pub fn names(&self) -> HashMap<String, String> {
+        self.mangle_map
+            .borrow()
+            .iter()
+            .map(|(identifier, mangled)| (mangled.clone(), identifier.clone()))
+            .collect()
+    }
+
     pub fn is_mangled(&self, identifier: &str) -> bool {
         self.mangle_map.borrow().contains_key(identifier)
     }
@@ -0,0 +1,522 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    rc::Rc,
+};
+
+use common::{diagnostic::Diagnostic, span::Span, types::Type};
+use parser::ast::{
+    declaration::{Declaration, Variant},
+    expression::Expression,
+    pattern::{MatchArm, Pattern},
+    statement::{LoopStatement, Statement},
+    unit::ASTUnit,
+    AbstractSyntaxTree,
+};
+
+// a source file, named by its path from the source root like `util::strings`
+pub struct SourceModule {
+    pub name: String,
+    pub ast: AbstractSyntaxTree,
+}
+
+impl SourceModule {
+    pub fn new(name: String, ast: AbstractSyntaxTree) -> Self {
+        Self { name, ast }
+    }
+}
+
+// links modules into one tree, the functions and types of the imported ones are
+// qualified with the module, as in `math::square`, those of the root keep their names
+pub struct Linker {
+    // the functions and types of every module, with whether they are public
+    items: HashMap<String, Vec<(String, bool)>>,
+    root: String,
+    // the items of the current module, by their qualified names
+    scope: RefCell<HashMap<String, String>>,
+    // the modules it imports, by the last segment and by the whole path
+    imports: RefCell<HashMap<String, String>>,
+    location: Cell<Option<Span>>,
+}
+
+impl Default for Linker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Linker {
+    pub fn new() -> Self {
+        Self {
+            items: HashMap::new(),
+            root: String::new(),
+            scope: RefCell::new(HashMap::new()),
+            imports: RefCell::new(HashMap::new()),
+            location: Cell::new(None),
+        }
+    }
+
+    pub fn run(
+        mut self,
+        root: SourceModule,
+        modules: Vec<SourceModule>,
+    ) -> Result<AbstractSyntaxTree, Diagnostic> {
+        self.root = root.name.clone();
+
+        for module in modules.iter().chain([&root]) {
+            let items = self.items_of(module)?;
+            self.items.insert(module.name.clone(), items);
+        }
+
+        let mut units = vec![];
+        for module in modules.iter().chain([&root]) {
+            units.append(&mut self.module(module)?);
+        }
+
+        Ok(AbstractSyntaxTree::new(ASTUnit::Block(units)))
+    }
+
+    fn items_of(&self, module: &SourceModule) -> Result<Vec<(String, bool)>, Diagnostic> {
+        let mut items = vec![];
+
+        for unit in block(&module.ast) {
+            match unit.as_ref() {
+                ASTUnit::Location(span) => self.location.set(Some(*span)),
+                // exported and extern functions are named by C, for every module
+                ASTUnit::Declaration(
+                    Declaration::FunctionDeclaration {
+                        export: Some(_), ..
+                    }
+                    | Declaration::ExternFunctionDeclaration { .. }
+                    | Declaration::Import { .. },
+                ) => {}
+                ASTUnit::Declaration(
+                    Declaration::FunctionDeclaration {
+                        identifier, public, ..
+                    }
+                    | Declaration::TypeDeclaration {
+                        identifier, public, ..
+                    }
+                    | Declaration::EnumDeclaration {
+                        identifier, public, ..
+                    },
+                ) => items.push((identifier.clone(), *public)),
+                // only the root runs code of its own
+                _ if module.name != self.root => {
+                    return Err(self.error(format!(
+                        "module `{}` can only declare functions and types",
+                        module.name
+                    )))
+                }
+                _ => {}
+            }
+        }
+
+        Ok(items)
+    }
+
+    // the units of the module, with the names it uses resolved
+    fn module(&self, module: &SourceModule) -> Result<Vec<Rc<ASTUnit>>, Diagnostic> {
+        let mut imports = HashMap::new();
+
+        for unit in block(&module.ast) {
+            match unit.as_ref() {
+                ASTUnit::Location(span) => self.location.set(Some(*span)),
+                ASTUnit::Declaration(Declaration::Import { path }) => {
+                    let imported = path.join("::");
+                    if !self.items.contains_key(&imported) {
+                        return Err(self.error(format!("unresolved import `{imported}`")));
+                    }
+
+                    // `import util::strings;` names the module `strings`
+                    let name = path.last().unwrap();
+                    match imports.insert(name.clone(), imported.clone()) {
+                        Some(other) if other != imported => {
+                            return Err(self.error(format!(
+                                "`{name}` is imported from both `{other}` and `{imported}`"
+                            )));
+                        }
+                        _ => {}
+                    }
+                    imports.insert(imported.clone(), imported);
+                }
+                _ => {}
+            }
+        }
+
+        *self.scope.borrow_mut() = self.items[&module.name]
+            .iter()
+            .map(|(name, _)| (name.clone(), self.qualify(&module.name, name)))
+            .collect();
+        *self.imports.borrow_mut() = imports;
+
+        block(&module.ast)
+            .iter()
+            .filter(|unit| {
+                !matches!(
+                    unit.as_ref(),
+                    ASTUnit::Declaration(Declaration::Import { .. })
+                )
+            })
+            .map(|unit| self.unit(unit))
+            .collect()
+    }
+
+    fn qualify(&self, module: &str, name: &str) -> String {
+        if module == self.root {
+            name.to_string()
+        } else {
+            format!("{module}::{name}")
+        }
+    }
+
+    fn unit(&self, unit: &Rc<ASTUnit>) -> Result<Rc<ASTUnit>, Diagnostic> {
+        let unit = match unit.as_ref() {
+            ASTUnit::Declaration(declaration) => {
+                ASTUnit::Declaration(self.declaration(declaration)?)
+            }
+            ASTUnit::Statement(statement) => ASTUnit::Statement(self.statement(statement)?),
+            ASTUnit::Expression(expression) => ASTUnit::Expression(self.expression(expression)?),
+            ASTUnit::Block(block) => ASTUnit::Block(self.units(block)?),
+            ASTUnit::Location(span) => {
+                self.location.set(Some(*span));
+                ASTUnit::Location(*span)
+            }
+        };
+
+        Ok(Rc::new(unit))
+    }
+
+    fn units(&self, units: &[Rc<ASTUnit>]) -> Result<Vec<Rc<ASTUnit>>, Diagnostic> {
+        units.iter().map(|unit| self.unit(unit)).collect()
+    }
+
+    fn declaration(&self, declaration: &Declaration) -> Result<Declaration, Diagnostic> {
+        Ok(match declaration {
+            Declaration::TypeDeclaration {
+                identifier,
+                fields,
+                public,
+            } => Declaration::TypeDeclaration {
+                identifier: self.resolve(identifier)?,
+                fields: self.fields(fields)?,
+                public: *public,
+            },
+            Declaration::EnumDeclaration {
+                identifier,
+                variants,
+                public,
+            } => Declaration::EnumDeclaration {
+                identifier: self.resolve(identifier)?,
+                variants: variants
+                    .iter()
+                    .map(|variant| {
+                        Ok(Variant {
+                            fields: self.fields(&variant.fields)?,
+                            ..variant.clone()
+                        })
+                    })
+                    .collect::<Result<_, Diagnostic>>()?,
+                public: *public,
+            },
+            Declaration::VariableDeclaration {
+                keyword,
+                identifier,
+                ty,
+                expression,
+            } => Declaration::VariableDeclaration {
+                keyword: *keyword,
+                identifier: identifier.clone(),
+                ty: self.optional_ty(ty.as_ref())?,
+                expression: self.unit(expression)?,
+            },
+            Declaration::DestructuringDeclaration {
+                keyword,
+                pattern,
+                ty,
+                expression,
+            } => Declaration::DestructuringDeclaration {
+                keyword: *keyword,
+                pattern: self.pattern(pattern)?,
+                ty: self.optional_ty(ty.as_ref())?,
+                expression: self.unit(expression)?,
+            },
+            Declaration::FunctionDeclaration {
+                identifier,
+                parameters,
+                return_type,
+                expression,
+                public,
+                export,
+            } => Declaration::FunctionDeclaration {
+                identifier: match export {
+                    Some(_) => identifier.clone(),
+                    None => self.resolve(identifier)?,
+                },
+                parameters: self.fields(parameters)?,
+                return_type: self.ty(return_type)?,
+                expression: self.unit(expression)?,
+                public: *public,
+                export: export.clone(),
+            },
+            Declaration::ExternFunctionDeclaration {
+                abi,
+                identifier,
+                parameters,
+                variadic,
+                return_type,
+            } => Declaration::ExternFunctionDeclaration {
+                abi: abi.clone(),
+                identifier: identifier.clone(),
+                parameters: self.fields(parameters)?,
+                variadic: *variadic,
+                return_type: self.ty(return_type)?,
+            },
+            Declaration::Import { path } => Declaration::Import { path: path.clone() },
+        })
+    }
+
+    fn statement(&self, statement: &Statement) -> Result<Statement, Diagnostic> {
+        Ok(match statement {
+            Statement::Return(expression) => Statement::Return(self.unit(expression)?),
+            Statement::ImplicitReturn(expression) => {
+                Statement::ImplicitReturn(self.unit(expression)?)
+            }
+            Statement::ControlFlow {
+                condition,
+                execute,
+                alternative,
+            } => Statement::ControlFlow {
+                condition: self.unit(condition)?,
+                execute: self.unit(execute)?,
+                alternative: alternative
+                    .as_ref()
+                    .map(|alternative| self.unit(alternative))
+                    .transpose()?,
+            },
+            Statement::Loop(LoopStatement::While { condition, execute }) => {
+                Statement::Loop(LoopStatement::While {
+                    condition: self.unit(condition)?,
+                    execute: self.unit(execute)?,
+                })
+            }
+            Statement::Match { scrutinee, arms } => Statement::Match {
+                scrutinee: self.unit(scrutinee)?,
+                arms: arms
+                    .iter()
+                    .map(|arm| {
+                        Ok(MatchArm {
+                            pattern: self.pattern(&arm.pattern)?,
+                            guard: arm
+                                .guard
+                                .as_ref()
+                                .map(|guard| self.unit(guard))
+                                .transpose()?,
+                            body: self.unit(&arm.body)?,
+                        })
+                    })
+                    .collect::<Result<_, Diagnostic>>()?,
+            },
+        })
+    }
+
+    fn expression(&self, expression: &Expression) -> Result<Expression, Diagnostic> {
+        Ok(match expression {
+            Expression::BinaryExpression {
+                left,
+                right,
+                operation,
+            } => Expression::BinaryExpression {
+                left: self.unit(left)?,
+                right: self.unit(right)?,
+                operation: operation.clone(),
+            },
+            Expression::Literal(literal) => Expression::Literal(literal.clone()),
+            Expression::Identifier(identifier) => Expression::Identifier(identifier.clone()),
+            Expression::FunctionInvokation {
+                function_name,
+                parameters,
+            } => Expression::FunctionInvokation {
+                function_name: self.resolve(function_name)?,
+                parameters: self.units(parameters)?,
+            },
+            Expression::Index { target, index } => Expression::Index {
+                target: self.unit(target)?,
+                index: self.unit(index)?,
+            },
+            Expression::Slice { target, start, end } => Expression::Slice {
+                target: self.unit(target)?,
+                start: start.as_ref().map(|start| self.unit(start)).transpose()?,
+                end: end.as_ref().map(|end| self.unit(end)).transpose()?,
+            },
+            Expression::Cast { expression, ty } => Expression::Cast {
+                expression: self.unit(expression)?,
+                ty: self.ty(ty)?,
+            },
+            Expression::Struct { identifier, fields } => Expression::Struct {
+                identifier: self.resolve(identifier)?,
+                fields: self.field_values(fields)?,
+            },
+            Expression::FieldAccess { target, field } => Expression::FieldAccess {
+                target: self.unit(target)?,
+                field: field.clone(),
+            },
+            // `math::square(2)` and `math::Point { .. }` parse like variants
+            Expression::Variant {
+                enumeration,
+                variant,
+                fields,
+            } if self.is_module(enumeration) => {
+                let identifier = self.resolve(&format!("{enumeration}::{variant}"))?;
+
+                if fields.iter().all(|(name, _)| name.parse::<usize>().is_ok()) {
+                    Expression::FunctionInvokation {
+                        function_name: identifier,
+                        parameters: self
+                            .field_values(fields)?
+                            .into_iter()
+                            .map(|(_, value)| value)
+                            .collect(),
+                    }
+                } else {
+                    Expression::Struct {
+                        identifier,
+                        fields: self.field_values(fields)?,
+                    }
+                }
+            }
+            Expression::Variant {
+                enumeration,
+                variant,
+                fields,
+            } => Expression::Variant {
+                enumeration: self.resolve(enumeration)?,
+                variant: variant.clone(),
+                fields: self.field_values(fields)?,
+            },
+            Expression::Tuple(elements) => Expression::Tuple(self.units(elements)?),
+            Expression::AddressOf(target) => Expression::AddressOf(self.unit(target)?),
+            Expression::Deref(target) => Expression::Deref(self.unit(target)?),
+            Expression::Array(elements) => Expression::Array(self.units(elements)?),
+            Expression::ArrayRepeat { value, count } => Expression::ArrayRepeat {
+                value: self.unit(value)?,
+                count: *count,
+            },
+        })
+    }
+
+    fn pattern(&self, pattern: &Pattern) -> Result<Pattern, Diagnostic> {
+        Ok(match pattern {
+            Pattern::Wildcard => Pattern::Wildcard,
+            Pattern::Binding(name) => Pattern::Binding(name.clone()),
+            Pattern::Literal(literal) => Pattern::Literal(literal.clone()),
+            Pattern::Variant {
+                enumeration,
+                variant,
+                fields,
+            } => Pattern::Variant {
+                enumeration: self.resolve(enumeration)?,
+                variant: variant.clone(),
+                fields: fields
+                    .iter()
+                    .map(|(name, pattern)| Ok((name.clone(), self.pattern(pattern)?)))
+                    .collect::<Result<_, Diagnostic>>()?,
+            },
+            Pattern::Tuple(elements) => Pattern::Tuple(
+                elements
+                    .iter()
+                    .map(|element| self.pattern(element))
+                    .collect::<Result<_, Diagnostic>>()?,
+            ),
+        })
+    }
+
+    fn ty(&self, ty: &Type) -> Result<Type, Diagnostic> {
+        Ok(match ty {
+            Type::Named(name) => Type::Named(self.resolve(name)?),
+            Type::Array(element, size) => Type::Array(Box::new(self.ty(element)?), *size),
+            Type::Slice(element) => Type::Slice(Box::new(self.ty(element)?)),
+            Type::Tuple(elements) => Type::Tuple(
+                elements
+                    .iter()
+                    .map(|element| self.ty(element))
+                    .collect::<Result<_, Diagnostic>>()?,
+            ),
+            Type::Pointer(pointee) => Type::Pointer(Box::new(self.ty(pointee)?)),
+            ty => ty.clone(),
+        })
+    }
+
+    fn optional_ty(&self, ty: Option<&Type>) -> Result<Option<Type>, Diagnostic> {
+        ty.map(|ty| self.ty(ty)).transpose()
+    }
+
+    fn fields(&self, fields: &[(String, Type)]) -> Result<Vec<(String, Type)>, Diagnostic> {
+        fields
+            .iter()
+            .map(|(name, ty)| Ok((name.clone(), self.ty(ty)?)))
+            .collect()
+    }
+
+    fn field_values(
+        &self,
+        fields: &[(String, Rc<ASTUnit>)],
+    ) -> Result<Vec<(String, Rc<ASTUnit>)>, Diagnostic> {
+        fields
+            .iter()
+            .map(|(name, value)| Ok((name.clone(), self.unit(value)?)))
+            .collect()
+    }
+
+    // whether the name refers to an imported module, its own items shadow them
+    fn is_module(&self, name: &str) -> bool {
+        !self.scope.borrow().contains_key(name) && self.imports.borrow().contains_key(name)
+    }
+
+    // `module::item` names an item of an imported module, other names the module does
+    // not know, like builtins, are left to the typecheck
+    fn resolve(&self, name: &str) -> Result<String, Diagnostic> {
+        let Some((module, item)) = name.rsplit_once("::") else {
+            return Ok(self
+                .scope
+                .borrow()
+                .get(name)
+                .cloned()
+                .unwrap_or_else(|| name.to_string()));
+        };
+
+        let Some(module) = self.imports.borrow().get(module).cloned() else {
+            return Err(self.error(format!("unresolved module `{module}`")));
+        };
+
+        match self.items[&module].iter().find(|(name, _)| name == item) {
+            Some((_, true)) => Ok(self.qualify(&module, item)),
+            Some((_, false)) => {
+                Err(self.error(format!("`{item}` is private to module `{module}`")))
+            }
+            None => Err(self.error(format!("module `{module}` has no item `{item}`"))),
+        }
+    }
+
+    fn error(&self, message: String) -> Diagnostic {
+        Diagnostic::new(message, self.location.get())
+    }
+}
+
+// the modules a tree imports, by their names
+pub fn imports(ast: &AbstractSyntaxTree) -> Vec<String> {
+    block(ast)
+        .iter()
+        .filter_map(|unit| match unit.as_ref() {
+            ASTUnit::Declaration(Declaration::Import { path }) => Some(path.join("::")),
+            _ => None,
+        })
+        .collect()
+}
+
+fn block(ast: &AbstractSyntaxTree) -> &[Rc<ASTUnit>] {
+    match ast.root() {
+        ASTUnit::Block(block) => block,
+        _ => unreachable!(),
+    }
+}
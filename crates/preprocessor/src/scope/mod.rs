@@ -7,6 +7,12 @@ pub struct Scope {
     stack_frame: RefCell<HashSet<String>>,
 }
 
+impl Default for Scope {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Scope {
     pub fn new() -> Self {
         Self {
@@ -33,6 +39,12 @@ pub struct Remapper {
     remaps: RefCell<HashMap<String, String>>,
 }
 
+impl Default for Remapper {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Remapper {
     pub fn new() -> Self {
         Self {
@@ -40,11 +52,18 @@ impl Remapper {
         }
     }
 
+    // starts out with the remaps of this one, later ones are not shared
+    pub fn child(&self) -> Self {
+        Self {
+            remaps: RefCell::new(self.remaps.borrow().clone()),
+        }
+    }
+
     pub fn remap(&self, from: String, into: String) -> Option<String> {
         self.remaps.borrow_mut().insert(from, into)
     }
 
     pub fn get_remapped(&self, from: &str) -> Option<String> {
-        self.remaps.borrow().get(from).map(String::clone)
+        self.remaps.borrow().get(from).cloned()
     }
 }
@@ -0,0 +1,1354 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    rc::Rc,
+};
+
+use common::{
+    constants::builtins::{
+        ABI_C, BUILTINS, BUILTIN_ABORT, BUILTIN_ABS, BUILTIN_ALLOC, BUILTIN_ASSERT, BUILTIN_EXIT,
+        BUILTIN_FREE, BUILTIN_LEN, BUILTIN_MAX, BUILTIN_MIN, BUILTIN_POW, BUILTIN_PRINT,
+        BUILTIN_PRINTLN, BUILTIN_SQRT, ENTRY_POINT,
+    },
+    diagnostic::Diagnostic,
+    span::Span,
+    types::Type,
+};
+use parser::ast::{
+    declaration::{Declaration, VariableDeclarationKeyword, Variant, VariantKind},
+    expression::Expression,
+    literal::Literal,
+    operation::{AlgebraicOperation, LogicalOperation, Operation},
+    pattern::{MatchArm, Pattern},
+    statement::{LoopStatement, Statement},
+    unit::ASTUnit,
+    AbstractSyntaxTree,
+};
+
+// types the checker could infer, keyed by the address of the expression in the tree,
+// the types both operands of binary expressions convert to, keyed the same way,
+// the types of matched values, keyed by the address of the match,
+// and the fields of the declared structs and variants of the declared enums
+#[derive(Default)]
+pub struct ExpressionTypes {
+    types: HashMap<usize, Type>,
+    operands: HashMap<usize, Type>,
+    scrutinees: HashMap<usize, Type>,
+    structs: HashMap<String, Vec<(String, Type)>>,
+    enums: HashMap<String, Vec<Variant>>,
+}
+
+impl ExpressionTypes {
+    pub fn get(&self, expression: &Expression) -> Option<&Type> {
+        self.types.get(&address(expression))
+    }
+
+    pub fn operands(&self, expression: &Expression) -> Option<&Type> {
+        self.operands.get(&address(expression))
+    }
+
+    pub fn scrutinee(&self, statement: &Statement) -> Option<&Type> {
+        self.scrutinees
+            .get(&(statement as *const Statement as usize))
+    }
+
+    pub fn fields(&self, name: &str) -> Option<&Vec<(String, Type)>> {
+        self.structs.get(name)
+    }
+
+    pub fn variants(&self, name: &str) -> Option<&Vec<Variant>> {
+        self.enums.get(name)
+    }
+
+    // tag and declaration of a variant of an enum
+    pub fn variant(&self, name: &str, variant: &str) -> Option<(u32, &Variant)> {
+        self.variants(name)?
+            .iter()
+            .enumerate()
+            .find(|(_, declared)| declared.name == variant)
+            .map(|(tag, declared)| (tag as u32, declared))
+    }
+
+    fn is_declared(&self, name: &str) -> bool {
+        self.structs.contains_key(name) || self.enums.contains_key(name)
+    }
+
+    // position and type of a field of a struct type or of an element of a tuple
+    pub fn field<'a>(&'a self, ty: &'a Type, field: &str) -> Option<(u32, &'a Type)> {
+        let name = match ty {
+            Type::Named(name) => name,
+            Type::Tuple(elements) => {
+                let index = field.parse::<usize>().ok()?;
+                return elements.get(index).map(|ty| (index as u32, ty));
+            }
+            _ => return None,
+        };
+
+        self.fields(name)?
+            .iter()
+            .enumerate()
+            .find(|(_, (name, _))| name == field)
+            .map(|(index, (_, ty))| (index as u32, ty))
+    }
+}
+
+// infers what it can and rejects what is certainly wrong,
+// unknown identifiers are left to later stages
+pub struct TypeChecker {
+    functions: RefCell<HashMap<String, Type>>,
+    // variadic functions and the number of their fixed parameters
+    variadics: RefCell<HashMap<String, usize>>,
+    scopes: RefCell<Vec<HashMap<String, Variable>>>,
+    location: Cell<Option<Span>>,
+    types: RefCell<ExpressionTypes>,
+}
+
+// only `let` bindings live on the stack, everything else cannot be assigned to
+struct Variable {
+    ty: Option<Type>,
+    mutable: bool,
+}
+
+impl Default for TypeChecker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TypeChecker {
+    pub fn new() -> Self {
+        Self {
+            functions: RefCell::new(HashMap::new()),
+            variadics: RefCell::new(HashMap::new()),
+            scopes: RefCell::new(vec![]),
+            location: Cell::new(None),
+            types: RefCell::new(ExpressionTypes::default()),
+        }
+    }
+
+    pub fn run(&self, ast: &AbstractSyntaxTree) -> Result<ExpressionTypes, Diagnostic> {
+        let root = match ast.root() {
+            ASTUnit::Block(block) => block,
+            _ => unreachable!(),
+        };
+
+        // functions can be called and types used before they are declared
+        for unit in root {
+            match unit.as_ref() {
+                ASTUnit::Location(span) => self.location.set(Some(*span)),
+                ASTUnit::Declaration(Declaration::FunctionDeclaration {
+                    identifier,
+                    return_type,
+                    ..
+                }) => {
+                    self.functions
+                        .borrow_mut()
+                        .insert(identifier.clone(), return_type.clone());
+                }
+                ASTUnit::Declaration(Declaration::ExternFunctionDeclaration {
+                    identifier,
+                    parameters,
+                    variadic,
+                    return_type,
+                    ..
+                }) => {
+                    self.functions
+                        .borrow_mut()
+                        .insert(identifier.clone(), return_type.clone());
+
+                    if *variadic {
+                        self.variadics
+                            .borrow_mut()
+                            .insert(identifier.clone(), parameters.len());
+                    }
+                }
+                ASTUnit::Declaration(Declaration::TypeDeclaration {
+                    identifier, fields, ..
+                }) => {
+                    if self.types.borrow().is_declared(identifier) {
+                        return Err(self.error(format!("struct `{identifier}` is declared twice")));
+                    }
+
+                    self.types
+                        .borrow_mut()
+                        .structs
+                        .insert(identifier.clone(), fields.clone());
+                }
+                ASTUnit::Declaration(Declaration::EnumDeclaration {
+                    identifier,
+                    variants,
+                    ..
+                }) => {
+                    if self.types.borrow().is_declared(identifier) {
+                        return Err(self.error(format!("enum `{identifier}` is declared twice")));
+                    }
+
+                    self.types
+                        .borrow_mut()
+                        .enums
+                        .insert(identifier.clone(), variants.clone());
+                }
+                _ => {}
+            }
+        }
+
+        self.location.set(None);
+
+        self.block(root)?;
+
+        Ok(self.types.take())
+    }
+
+    // the type of a block is the one of its implicit return
+    fn block(&self, block: &[Rc<ASTUnit>]) -> Result<Option<Type>, Diagnostic> {
+        self.scopes.borrow_mut().push(HashMap::new());
+
+        let mut ty = Ok(None);
+        for unit in block {
+            ty = self.unit(unit);
+
+            if ty.is_err() {
+                break;
+            }
+        }
+
+        self.scopes.borrow_mut().pop();
+
+        ty
+    }
+
+    fn unit(&self, unit: &ASTUnit) -> Result<Option<Type>, Diagnostic> {
+        match unit {
+            ASTUnit::Declaration(declaration) => self.declaration(declaration).map(|_| None),
+            ASTUnit::Statement(statement) => self.statement(statement),
+            ASTUnit::Expression(expression) => self.expression(expression),
+            ASTUnit::Block(block) => self.block(block),
+            ASTUnit::Location(span) => {
+                self.location.set(Some(*span));
+                Ok(None)
+            }
+        }
+    }
+
+    fn declaration(&self, declaration: &Declaration) -> Result<(), Diagnostic> {
+        match declaration {
+            Declaration::TypeDeclaration { fields, .. } => {
+                for (_, ty) in fields {
+                    self.check_type(ty)?;
+                }
+
+                Ok(())
+            }
+            Declaration::EnumDeclaration {
+                identifier,
+                variants,
+                ..
+            } => {
+                for (idx, variant) in variants.iter().enumerate() {
+                    if variants[..idx]
+                        .iter()
+                        .any(|declared| declared.name == variant.name)
+                    {
+                        return Err(self.error(format!(
+                            "variant `{identifier}::{}` is declared twice",
+                            variant.name
+                        )));
+                    }
+
+                    for (_, ty) in &variant.fields {
+                        self.check_type(ty)?;
+                    }
+                }
+
+                Ok(())
+            }
+            Declaration::VariableDeclaration {
+                keyword,
+                identifier,
+                ty,
+                expression,
+            } => {
+                let value = self.unit(expression)?;
+
+                if let Some(ty) = ty {
+                    self.check_type(ty)?;
+                }
+
+                // a declared type converts the value like a cast
+                if let (Some(from), Some(to)) = (&value, ty) {
+                    self.check_cast(expression, from, to)?;
+                }
+
+                self.scopes.borrow_mut().last_mut().unwrap().insert(
+                    identifier.clone(),
+                    Variable {
+                        ty: ty.clone().or(value),
+                        mutable: *keyword == VariableDeclarationKeyword::Let,
+                    },
+                );
+
+                Ok(())
+            }
+            Declaration::DestructuringDeclaration {
+                keyword,
+                pattern,
+                ty,
+                expression,
+            } => {
+                let value = self.unit(expression)?;
+
+                if let Some(ty) = ty {
+                    self.check_type(ty)?;
+                }
+
+                if let (Some(from), Some(to)) = (&value, ty) {
+                    self.check_cast(expression, from, to)?;
+                }
+
+                if !pattern.is_irrefutable() {
+                    return Err(self.error(format!(
+                        "pattern `{pattern}` can fail to match, use `match` instead"
+                    )));
+                }
+
+                let Some(value) = ty.clone().or(value) else {
+                    return Err(
+                        self.error("cannot infer the type of the destructured value".to_string())
+                    );
+                };
+
+                // bindings are declared in the innermost scope, like the ones of an arm
+                self.pattern(pattern, &value, *keyword == VariableDeclarationKeyword::Let)
+            }
+            Declaration::ExternFunctionDeclaration {
+                abi,
+                identifier,
+                parameters,
+                return_type,
+                ..
+            } => {
+                if let Some(abi) = abi.as_deref().filter(|abi| *abi != ABI_C) {
+                    return Err(self.error(format!(
+                        "unsupported ABI {abi:?} of `{identifier}`, only {ABI_C:?} is supported"
+                    )));
+                }
+
+                for (_, ty) in parameters {
+                    self.check_type(ty)?;
+
+                    if !is_ffi_safe(ty) {
+                        return Err(self.error(format!(
+                            "`{ty}` cannot be passed to the extern function `{identifier}`"
+                        )));
+                    }
+                }
+
+                // C strings carry no length, so they come back as pointers
+                if *return_type != Type::Void
+                    && (*return_type == Type::String || !is_ffi_safe(return_type))
+                {
+                    return Err(self.error(format!(
+                        "extern function `{identifier}` cannot return `{return_type}`"
+                    )));
+                }
+
+                Ok(())
+            }
+            // imports are resolved by linking the modules before
+            Declaration::Import { path } => {
+                Err(self.error(format!("unresolved import `{}`", path.join("::"))))
+            }
+            Declaration::FunctionDeclaration {
+                identifier,
+                parameters,
+                return_type,
+                expression,
+                export,
+                ..
+            } => {
+                for (_, ty) in parameters {
+                    self.check_type(ty)?;
+                }
+                self.check_type(return_type)?;
+
+                // the C `main` calls the entry point and exits with what it returns
+                if identifier == ENTRY_POINT {
+                    if !parameters.is_empty() {
+                        return Err(self.error(format!("`{ENTRY_POINT}` takes no parameters")));
+                    }
+
+                    if !return_type.is_integer() && !matches!(return_type, Type::Bool | Type::Void)
+                    {
+                        return Err(self.error(format!(
+                            "`{ENTRY_POINT}` must return an integer, a `bool` or nothing, not `{return_type}`"
+                        )));
+                    }
+                }
+
+                if let Some(abi) = export {
+                    self.export(identifier, abi, parameters, return_type)?;
+                }
+
+                self.scopes.borrow_mut().push(
+                    parameters
+                        .iter()
+                        .map(|(name, ty)| {
+                            let variable = Variable {
+                                ty: Some(ty.clone()),
+                                mutable: false,
+                            };
+
+                            (name.clone(), variable)
+                        })
+                        .collect(),
+                );
+
+                let result = self.unit(expression);
+
+                self.scopes.borrow_mut().pop();
+
+                result.map(|_| ())
+            }
+        }
+    }
+
+    // the type of a call of a builtin, overloaded ones need to know the type of their arguments
+    fn builtin(&self, name: &str, types: &[Option<Type>]) -> Result<Option<Type>, Diagnostic> {
+        match (name, types) {
+            (BUILTIN_LEN, [Some(Type::String | Type::Array(..) | Type::Slice(_)) | None]) => {
+                Ok(Some(Type::Int64))
+            }
+            (BUILTIN_LEN, [Some(ty)]) => Err(self.error(format!(
+                "`{BUILTIN_LEN}` expects a string, an array or a slice, got `{ty}`"
+            ))),
+            (BUILTIN_ALLOC, [Some(ty)]) => Ok(Some(Type::Pointer(Box::new(ty.clone())))),
+            (BUILTIN_ALLOC, [None]) => {
+                Err(self.error("cannot infer the type of the allocated value".to_string()))
+            }
+            (BUILTIN_FREE, [Some(Type::Pointer(_)) | None]) => Ok(None),
+            (BUILTIN_FREE, [Some(ty)]) => {
+                Err(self.error(format!("`{BUILTIN_FREE}` expects a pointer, got `{ty}`")))
+            }
+            (BUILTIN_PRINTLN, []) => Ok(None),
+            (BUILTIN_PRINT | BUILTIN_PRINTLN, [Some(ty)])
+                if is_scalar(ty) || *ty == Type::String =>
+            {
+                Ok(None)
+            }
+            (BUILTIN_PRINT | BUILTIN_PRINTLN, [Some(ty)]) => {
+                Err(self.error(format!("`{name}` cannot print `{ty}`")))
+            }
+            (BUILTIN_ASSERT, [condition, message]) => {
+                if let Some(ty) = condition.as_ref().filter(|ty| **ty != Type::Bool) {
+                    return Err(self.error(format!(
+                        "`{BUILTIN_ASSERT}` expects a `bool` condition, got `{ty}`"
+                    )));
+                }
+                if let Some(ty) = message.as_ref().filter(|ty| **ty != Type::String) {
+                    return Err(self.error(format!(
+                        "`{BUILTIN_ASSERT}` expects a `String` message, got `{ty}`"
+                    )));
+                }
+
+                Ok(None)
+            }
+            (BUILTIN_ABORT, []) => Ok(None),
+            (BUILTIN_EXIT, [Some(ty)]) if !ty.is_integer() => Err(self.error(format!(
+                "`{BUILTIN_EXIT}` expects an integer exit code, got `{ty}`"
+            ))),
+            (BUILTIN_EXIT, [_]) => Ok(None),
+            (BUILTIN_ABS, [Some(ty)]) if ty.is_numeric() => Ok(Some(ty.clone())),
+            (BUILTIN_ABS, [Some(ty)]) => {
+                Err(self.error(format!("`{BUILTIN_ABS}` expects a number, got `{ty}`")))
+            }
+            (BUILTIN_SQRT, [Some(ty)]) if ty.is_float() => Ok(Some(ty.clone())),
+            (BUILTIN_SQRT, [Some(ty)]) => {
+                Err(self.error(format!("`{BUILTIN_SQRT}` expects a float, got `{ty}`")))
+            }
+            (BUILTIN_SQRT, [None]) => Ok(None),
+            (BUILTIN_MIN | BUILTIN_MAX | BUILTIN_POW, [lhs, rhs]) => {
+                if let (Some(lhs), Some(rhs)) = (lhs, rhs) {
+                    if lhs != rhs {
+                        return Err(self.error(format!(
+                            "`{name}` expects two arguments of the same type, got `{lhs}` and `{rhs}`"
+                        )));
+                    }
+                }
+
+                match lhs.as_ref().or(rhs.as_ref()) {
+                    Some(ty) if name == BUILTIN_POW && !ty.is_float() => {
+                        Err(self.error(format!("`{BUILTIN_POW}` expects floats, got `{ty}`")))
+                    }
+                    Some(ty) if !ty.is_numeric() => {
+                        Err(self.error(format!("`{name}` expects numbers, got `{ty}`")))
+                    }
+                    Some(ty) => Ok(Some(ty.clone())),
+                    None if name == BUILTIN_POW => Ok(None),
+                    None => Err(self.error(format!(
+                        "cannot infer the type of the arguments of `{name}`"
+                    ))),
+                }
+            }
+            // overloaded builtins are resolved by the type of the argument
+            (BUILTIN_PRINT | BUILTIN_PRINTLN | BUILTIN_ABS, [None]) => {
+                Err(self.error(format!("cannot infer the type of the argument of `{name}`")))
+            }
+            _ => Err(self.error(format!(
+                "`{name}` expects {}, got {}",
+                match name {
+                    BUILTIN_ABORT => "no arguments",
+                    BUILTIN_PRINTLN => "at most 1 argument",
+                    BUILTIN_ASSERT | BUILTIN_MIN | BUILTIN_MAX | BUILTIN_POW => "2 arguments",
+                    _ => "1 argument",
+                },
+                types.len()
+            ))),
+        }
+    }
+
+    // exported functions are called by C, which passes no strings, structs or arrays by value
+    fn export(
+        &self,
+        identifier: &str,
+        abi: &str,
+        parameters: &[(String, Type)],
+        return_type: &Type,
+    ) -> Result<(), Diagnostic> {
+        if abi != ABI_C {
+            return Err(self.error(format!(
+                "unsupported ABI {abi:?} of `{identifier}`, only {ABI_C:?} is supported"
+            )));
+        }
+
+        if identifier == ENTRY_POINT {
+            return Err(self.error(format!("`{ENTRY_POINT}` cannot be exported")));
+        }
+
+        if let Some((_, ty)) = parameters
+            .iter()
+            .find(|(_, ty)| *ty == Type::String || !is_ffi_safe(ty))
+        {
+            return Err(self.error(format!(
+                "`{ty}` cannot be passed to the exported function `{identifier}`"
+            )));
+        }
+
+        if *return_type != Type::Void && (*return_type == Type::String || !is_ffi_safe(return_type))
+        {
+            return Err(self.error(format!(
+                "exported function `{identifier}` cannot return `{return_type}`"
+            )));
+        }
+
+        Ok(())
+    }
+
+    fn statement(&self, statement: &Statement) -> Result<Option<Type>, Diagnostic> {
+        match statement {
+            Statement::Return(value) => self.unit(value).map(|_| None),
+            Statement::ImplicitReturn(value) => self.unit(value),
+            Statement::ControlFlow {
+                condition,
+                execute,
+                alternative,
+            } => {
+                self.unit(condition)?;
+                let ty = self.unit(execute)?;
+
+                if let Some(alternative) = alternative {
+                    self.unit(alternative)?;
+                }
+
+                Ok(ty)
+            }
+            Statement::Loop(LoopStatement::While { condition, execute }) => {
+                self.unit(condition)?;
+                self.unit(execute)?;
+
+                Ok(None)
+            }
+            Statement::Match { scrutinee, arms } => {
+                let Some(ty) = self.unit(scrutinee)? else {
+                    return Err(
+                        self.error("cannot infer the type of the matched value".to_string())
+                    );
+                };
+
+                self.types
+                    .borrow_mut()
+                    .scrutinees
+                    .insert(statement as *const Statement as usize, ty.clone());
+
+                let mut result = None;
+
+                for arm in arms {
+                    self.scopes.borrow_mut().push(HashMap::new());
+                    let arm_type = self.arm(arm, &ty);
+                    self.scopes.borrow_mut().pop();
+
+                    result = result.or(arm_type?);
+                }
+
+                self.check_exhaustive(arms, &ty)?;
+
+                Ok(result)
+            }
+        }
+    }
+
+    // bindings of the pattern are declared in the innermost scope
+    fn arm(&self, arm: &MatchArm, ty: &Type) -> Result<Option<Type>, Diagnostic> {
+        self.pattern(&arm.pattern, ty, false)?;
+
+        if let Some(guard) = &arm.guard {
+            match self.unit(guard)? {
+                Some(Type::Bool) | None => {}
+                Some(other) => {
+                    return Err(self.error(format!("match guard must be bool, got `{other}`")))
+                }
+            }
+        }
+
+        self.unit(&arm.body)
+    }
+
+    fn pattern(&self, pattern: &Pattern, ty: &Type, mutable: bool) -> Result<(), Diagnostic> {
+        match pattern {
+            Pattern::Wildcard => Ok(()),
+            Pattern::Binding(name) => {
+                let variable = Variable {
+                    ty: Some(ty.clone()),
+                    mutable,
+                };
+
+                self.scopes
+                    .borrow_mut()
+                    .last_mut()
+                    .unwrap()
+                    .insert(name.clone(), variable);
+
+                Ok(())
+            }
+            Pattern::Literal(literal) => {
+                let literal = literal_type(literal);
+
+                // literals convert to the matched type like a declared variable type
+                if !is_castable(&literal, ty) {
+                    return Err(self.error(format!(
+                        "pattern of type `{literal}` cannot match a value of type `{ty}`"
+                    )));
+                }
+
+                Ok(())
+            }
+            Pattern::Variant {
+                enumeration,
+                variant,
+                fields,
+            } => {
+                if !self.types.borrow().enums.contains_key(enumeration) {
+                    return Err(self.error(format!("unknown enum `{enumeration}`")));
+                }
+
+                if *ty != Type::Named(enumeration.clone()) {
+                    return Err(self.error(format!(
+                        "pattern of type `{enumeration}` cannot match a value of type `{ty}`"
+                    )));
+                }
+
+                let declared = self.declared_variant(enumeration, variant)?;
+
+                // fields of struct variants can be left out, tuple variants take them all
+                if declared.kind != VariantKind::Struct && fields.len() != declared.fields.len() {
+                    return Err(self.error(format!(
+                        "`{enumeration}::{variant}` has {} fields, the pattern has {}",
+                        declared.fields.len(),
+                        fields.len()
+                    )));
+                }
+
+                for (name, pattern) in fields {
+                    let Some((_, ty)) = declared.fields.iter().find(|(field, _)| field == name)
+                    else {
+                        return Err(self.error(format!(
+                            "variant `{enumeration}::{variant}` has no field `{name}`"
+                        )));
+                    };
+
+                    self.pattern(pattern, ty, mutable)?;
+                }
+
+                Ok(())
+            }
+            Pattern::Tuple(elements) => {
+                let Type::Tuple(types) = ty else {
+                    return Err(
+                        self.error(format!("tuple pattern cannot match a value of type `{ty}`"))
+                    );
+                };
+
+                if elements.len() != types.len() {
+                    return Err(self.error(format!(
+                        "tuple pattern has {} elements, `{ty}` has {}",
+                        elements.len(),
+                        types.len()
+                    )));
+                }
+
+                for (pattern, ty) in elements.iter().zip(types) {
+                    self.pattern(pattern, ty, mutable)?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+
+    // a value is covered by an unguarded arm that matches anything,
+    // enum values by one of those or one per variant matching any payload of it
+    fn check_exhaustive(&self, arms: &[MatchArm], ty: &Type) -> Result<(), Diagnostic> {
+        let unguarded = || arms.iter().filter(|arm| arm.guard.is_none());
+
+        if unguarded().any(|arm| arm.pattern.is_irrefutable()) {
+            return Ok(());
+        }
+
+        match ty {
+            Type::Named(name) if self.types.borrow().enums.contains_key(name) => {
+                let variants = self.types.borrow().enums[name].clone();
+
+                for variant in variants {
+                    let covered = unguarded().any(|arm| match &arm.pattern {
+                        Pattern::Variant {
+                            variant: covered,
+                            fields,
+                            ..
+                        } => {
+                            *covered == variant.name
+                                && fields.iter().all(|(_, pattern)| pattern.is_irrefutable())
+                        }
+                        _ => false,
+                    });
+
+                    if !covered {
+                        return Err(self.error(format!(
+                            "non-exhaustive match, `{name}::{}` is not covered",
+                            variant.name
+                        )));
+                    }
+                }
+
+                Ok(())
+            }
+            Type::Bool
+                if [true, false].iter().all(|value| {
+                    unguarded().any(|arm| arm.pattern == Pattern::Literal(Literal::Bool(*value)))
+                }) =>
+            {
+                Ok(())
+            }
+            _ => Err(self.error("non-exhaustive match, add a `_` arm".to_string())),
+        }
+    }
+
+    fn declared_variant(&self, enumeration: &str, variant: &str) -> Result<Variant, Diagnostic> {
+        match self.types.borrow().variant(enumeration, variant) {
+            Some((_, declared)) => Ok(declared.clone()),
+            None => Err(self.error(format!("enum `{enumeration}` has no variant `{variant}`"))),
+        }
+    }
+
+    fn expression(&self, expression: &Expression) -> Result<Option<Type>, Diagnostic> {
+        let ty = match expression {
+            Expression::Literal(literal) => Some(literal_type(literal)),
+            Expression::Identifier(identifier) => self.lookup(identifier),
+            Expression::BinaryExpression {
+                left,
+                right,
+                operation,
+            } => {
+                let left_type = self.unit(left)?;
+                let right_type = self.unit(right)?;
+
+                if let Operation::Assignment(_) = operation {
+                    self.check_assignable(left)?;
+
+                    // the value converts to the type of the place like a cast
+                    if let (Some(to), Some(from)) = (&left_type, &right_type) {
+                        self.check_cast(right, from, to)?;
+                    }
+
+                    return Ok(None);
+                }
+
+                let operands = self.operand_type(operation, left, right, left_type, right_type)?;
+
+                if let Some(ty) = &operands {
+                    self.types
+                        .borrow_mut()
+                        .operands
+                        .insert(address(expression), ty.clone());
+                }
+
+                match operation {
+                    Operation::Logical(_) => Some(Type::Bool),
+                    _ => operands,
+                }
+            }
+            Expression::FunctionInvokation {
+                function_name,
+                parameters,
+            } => {
+                let mut types = vec![];
+
+                for parameter in parameters {
+                    types.push(self.unit(parameter)?);
+                }
+
+                let declared = self.functions.borrow().contains_key(function_name);
+
+                match function_name.as_str() {
+                    // declared functions shadow the builtins of the same name
+                    name if !declared && BUILTINS.contains(&name) => self.builtin(name, &types)?,
+                    name if !declared => {
+                        return Err(self.error(format!("unknown function `{name}`")))
+                    }
+                    name => {
+                        // arguments past the fixed ones go to C as they are
+                        if let Some(&fixed) = self.variadics.borrow().get(name) {
+                            if types.len() < fixed {
+                                return Err(self.error(format!(
+                                    "`{name}` expects at least {fixed} argument{}, got {}",
+                                    if fixed == 1 { "" } else { "s" },
+                                    types.len()
+                                )));
+                            }
+
+                            if let Some(ty) =
+                                types[fixed..].iter().flatten().find(|ty| !is_ffi_safe(ty))
+                            {
+                                return Err(self.error(format!(
+                                    "`{ty}` cannot be passed to a variadic function"
+                                )));
+                            }
+                        }
+
+                        self.functions
+                            .borrow()
+                            .get(name)
+                            .filter(|ty| **ty != Type::Void)
+                            .cloned()
+                    }
+                }
+            }
+            Expression::Index { target, index } => {
+                let target = self.unit(target)?;
+
+                match self.unit(index)? {
+                    Some(ty) if !ty.is_integer() => {
+                        return Err(
+                            self.error(format!("array index must be an integer, got `{ty}`"))
+                        )
+                    }
+                    _ => {}
+                }
+
+                match target {
+                    // strings are indexed by byte
+                    Some(Type::String) => Some(Type::UInt8),
+                    Some(Type::Array(element, length)) => {
+                        // constant indices are checked here instead of at runtime
+                        if let ASTUnit::Expression(Expression::Literal(Literal::Int32(value))) =
+                            index.as_ref()
+                        {
+                            if u64::try_from(*value).map_or(true, |value| value >= length) {
+                                return Err(self.error(format!(
+                                    "index {value} is out of bounds for `[{element}; {length}]`"
+                                )));
+                            }
+                        }
+
+                        Some(*element)
+                    }
+                    // the length of a slice is only known at runtime
+                    Some(Type::Slice(element)) => Some(*element),
+                    Some(ty) => return Err(self.error(format!("`{ty}` cannot be indexed"))),
+                    None => None,
+                }
+            }
+            Expression::Slice { target, start, end } => {
+                let target = self.unit(target)?;
+
+                for bound in [start, end].into_iter().flatten() {
+                    match self.unit(bound)? {
+                        Some(ty) if !ty.is_integer() => {
+                            return Err(
+                                self.error(format!("slice bound must be an integer, got `{ty}`"))
+                            )
+                        }
+                        _ => {}
+                    }
+                }
+
+                match target {
+                    Some(Type::Array(element, _) | Type::Slice(element)) => {
+                        Some(Type::Slice(element))
+                    }
+                    Some(ty) => return Err(self.error(format!("`{ty}` cannot be sliced"))),
+                    None => None,
+                }
+            }
+            Expression::AddressOf(place) => {
+                let ty = self.unit(place)?;
+
+                let is_place = match place.as_ref() {
+                    ASTUnit::Expression(Expression::Identifier(_))
+                    | ASTUnit::Expression(Expression::FieldAccess { .. })
+                    | ASTUnit::Expression(Expression::Deref(_)) => true,
+                    // bytes of strings are read by value
+                    ASTUnit::Expression(Expression::Index { target, .. }) => !matches!(
+                        target.as_ref(),
+                        ASTUnit::Expression(target)
+                            if self.types.borrow().get(target) == Some(&Type::String)
+                    ),
+                    _ => false,
+                };
+
+                if !is_place {
+                    return Err(self.error(
+                        "only variables, fields, elements and dereferences have an address"
+                            .to_string(),
+                    ));
+                }
+
+                ty.map(|ty| Type::Pointer(Box::new(ty)))
+            }
+            Expression::Deref(pointer) => match self.unit(pointer)? {
+                Some(Type::Pointer(pointee)) => Some(*pointee),
+                Some(ty) => return Err(self.error(format!("`{ty}` cannot be dereferenced"))),
+                None => {
+                    return Err(
+                        self.error("cannot infer the type of the dereferenced value".to_string())
+                    )
+                }
+            },
+            // unknown element types leave the type of the tuple unknown
+            Expression::Tuple(elements) => {
+                let mut types = vec![];
+
+                for element in elements {
+                    types.push(self.unit(element)?);
+                }
+
+                types
+                    .into_iter()
+                    .collect::<Option<Vec<Type>>>()
+                    .map(Type::Tuple)
+            }
+            Expression::Array(elements) => {
+                // the element type and whether only number literals had it so far
+                let mut element: Option<(Type, bool)> = None;
+
+                // elements have the type of the first one, number literals take
+                // the one of the other elements like operands do
+                for (index, value) in elements.iter().enumerate() {
+                    let Some(ty) = self.unit(value)? else {
+                        continue;
+                    };
+                    let literal = is_number_literal(value);
+
+                    match &element {
+                        None => element = Some((ty, literal)),
+                        Some((expected, _)) if *expected == ty => {}
+                        Some((expected, _)) if literal && is_same_kind(&ty, expected) => {}
+                        Some((expected, true)) if is_same_kind(expected, &ty) => {
+                            element = Some((ty, false));
+                        }
+                        Some((expected, _)) => {
+                            return Err(self.error(format!(
+                                "array element {index} has type `{ty}`, expected `{expected}`"
+                            )));
+                        }
+                    }
+                }
+
+                let element = element.map(|(element, _)| element);
+
+                if let Some(element) = &element {
+                    for value in elements {
+                        self.check_literal(value, element)?;
+                    }
+                }
+
+                if elements.is_empty() {
+                    return Err(self.error("cannot infer the type of an empty array".to_string()));
+                }
+
+                element.map(|element| Type::Array(Box::new(element), elements.len() as u64))
+            }
+            Expression::ArrayRepeat { value, count } => self
+                .unit(value)?
+                .map(|element| Type::Array(Box::new(element), *count)),
+            Expression::Cast { expression, ty } => {
+                if let Some(from) = self.unit(expression)? {
+                    self.check_cast(expression, &from, ty)?;
+                }
+
+                Some(ty.clone())
+            }
+            Expression::Struct { identifier, fields } => {
+                let Some(declared) = self.types.borrow().fields(identifier).cloned() else {
+                    return Err(self.error(format!("unknown struct `{identifier}`")));
+                };
+
+                for (name, value) in fields {
+                    let Some((_, ty)) = declared.iter().find(|(field, _)| field == name) else {
+                        return Err(
+                            self.error(format!("struct `{identifier}` has no field `{name}`"))
+                        );
+                    };
+
+                    if fields.iter().filter(|(field, _)| field == name).count() > 1 {
+                        return Err(self.error(format!("field `{name}` is given twice")));
+                    }
+
+                    // values convert to the field type like a declared variable type
+                    if let Some(from) = self.unit(value)? {
+                        self.check_cast(value, &from, ty)?;
+                    }
+                }
+
+                if let Some((missing, _)) = declared
+                    .iter()
+                    .find(|(field, _)| fields.iter().all(|(name, _)| name != field))
+                {
+                    return Err(self.error(format!("missing field `{missing}` in `{identifier}`")));
+                }
+
+                Some(Type::Named(identifier.clone()))
+            }
+            Expression::Variant {
+                enumeration,
+                variant,
+                fields,
+            } => {
+                if !self.types.borrow().enums.contains_key(enumeration) {
+                    return Err(self.error(format!("unknown enum `{enumeration}`")));
+                }
+
+                let declared = self.declared_variant(enumeration, variant)?;
+
+                for (name, value) in fields {
+                    let Some((_, ty)) = declared.fields.iter().find(|(field, _)| field == name)
+                    else {
+                        return Err(self.error(format!(
+                            "variant `{enumeration}::{variant}` has no field `{name}`"
+                        )));
+                    };
+
+                    if fields.iter().filter(|(field, _)| field == name).count() > 1 {
+                        return Err(self.error(format!("field `{name}` is given twice")));
+                    }
+
+                    // values convert to the field type like a declared variable type
+                    if let Some(from) = self.unit(value)? {
+                        self.check_cast(value, &from, ty)?;
+                    }
+                }
+
+                if let Some((missing, _)) = declared
+                    .fields
+                    .iter()
+                    .find(|(field, _)| fields.iter().all(|(name, _)| name != field))
+                {
+                    return Err(self.error(format!(
+                        "missing field `{missing}` in `{enumeration}::{variant}`"
+                    )));
+                }
+
+                Some(Type::Named(enumeration.clone()))
+            }
+            Expression::FieldAccess { target, field } => match self.unit(target)? {
+                Some(ty @ Type::Named(_)) => match self.types.borrow().field(&ty, field) {
+                    Some((_, field_type)) => Some(field_type.clone()),
+                    None if self.types.borrow().fields(&ty.to_string()).is_some() => {
+                        return Err(self.error(format!("struct `{ty}` has no field `{field}`")))
+                    }
+                    None => return Err(self.error(format!("`{ty}` has no field `{field}`"))),
+                },
+                Some(ty @ Type::Tuple(_)) => match self.types.borrow().field(&ty, field) {
+                    Some((_, element)) => Some(element.clone()),
+                    None => return Err(self.error(format!("tuple `{ty}` has no field `{field}`"))),
+                },
+                Some(ty) => return Err(self.error(format!("`{ty}` has no field `{field}`"))),
+                None => {
+                    return Err(self.error(format!(
+                        "cannot infer the type of the value whose field `{field}` is accessed"
+                    )))
+                }
+            },
+        };
+
+        if let Some(ty) = &ty {
+            self.types
+                .borrow_mut()
+                .types
+                .insert(address(expression), ty.clone());
+        }
+
+        Ok(ty)
+    }
+
+    // the type both operands convert to, numbers in arithmetic have to be of the same
+    // type, except for literals which take the one of the other operand, comparisons
+    // also widen the narrower number, strings can only be concatenated and compared
+    fn operand_type(
+        &self,
+        operation: &Operation,
+        left: &ASTUnit,
+        right: &ASTUnit,
+        left_type: Option<Type>,
+        right_type: Option<Type>,
+    ) -> Result<Option<Type>, Diagnostic> {
+        let (left_type, right_type) = match (left_type, right_type) {
+            (Some(left_type), Some(right_type)) => (left_type, right_type),
+            // left to later stages
+            (left_type, right_type) => return Ok(left_type.or(right_type)),
+        };
+
+        let ty = if left_type == right_type
+            || is_number_literal(right) && is_same_kind(&right_type, &left_type)
+        {
+            left_type.clone()
+        } else if is_number_literal(left) && is_same_kind(&left_type, &right_type) {
+            right_type.clone()
+        } else if matches!(operation, Operation::Logical(_))
+            && is_same_kind(&left_type, &right_type)
+        {
+            match bit_width(&left_type) < bit_width(&right_type) {
+                true => right_type.clone(),
+                false => left_type.clone(),
+            }
+        } else {
+            return Err(self.error(format!(
+                "cannot apply `{}` to `{left_type}` and `{right_type}`",
+                operation.as_str()
+            )));
+        };
+
+        let supported = match operation {
+            Operation::Algebraic(AlgebraicOperation::Addition) => {
+                ty.is_numeric() || ty == Type::String
+            }
+            Operation::Algebraic(_) => ty.is_numeric(),
+            Operation::Logical(LogicalOperation::And | LogicalOperation::Or) => ty == Type::Bool,
+            Operation::Logical(LogicalOperation::Equal) => is_scalar(&ty) || ty == Type::String,
+            Operation::Logical(_) => ty.is_numeric() || ty == Type::Char,
+            Operation::Assignment(_) => true,
+        };
+
+        if !supported {
+            return Err(self.error(format!(
+                "cannot apply `{}` to `{left_type}` and `{right_type}`",
+                operation.as_str()
+            )));
+        }
+
+        self.check_literal(left, &ty)?;
+        self.check_literal(right, &ty)?;
+
+        Ok(Some(ty))
+    }
+
+    // integer literals also have to fit into the type they are converted to
+    fn check_cast(&self, value: &ASTUnit, from: &Type, to: &Type) -> Result<(), Diagnostic> {
+        if !is_castable(from, to) {
+            return Err(self.error(format!("cannot cast {from} to {to}")));
+        }
+
+        self.check_literal(value, to)
+    }
+
+    fn check_literal(&self, value: &ASTUnit, ty: &Type) -> Result<(), Diagnostic> {
+        match integer_literal(value) {
+            Some(literal) if !fits(literal, ty) => {
+                Err(self.error(format!("literal `{literal}` is out of range for `{ty}`")))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn check_type(&self, ty: &Type) -> Result<(), Diagnostic> {
+        match ty {
+            Type::Named(name) if !self.types.borrow().is_declared(name) => {
+                Err(self.error(format!("unknown type `{name}`")))
+            }
+            Type::Array(element, _) | Type::Slice(element) => self.check_type(element),
+            Type::Tuple(elements) => elements
+                .iter()
+                .try_for_each(|element| self.check_type(element)),
+            Type::Pointer(pointee) => self.check_type(pointee),
+            _ => Ok(()),
+        }
+    }
+
+    fn error(&self, message: String) -> Diagnostic {
+        Diagnostic::new(message, self.location.get())
+    }
+
+    fn lookup(&self, identifier: &str) -> Option<Type> {
+        self.scopes
+            .borrow()
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(identifier))
+            .and_then(|variable| variable.ty.clone())
+    }
+
+    // fields and elements belong to the variable they are in,
+    // pointees and elements of slices can always be assigned to
+    fn check_assignable(&self, place: &ASTUnit) -> Result<(), Diagnostic> {
+        let ASTUnit::Expression(place) = place else {
+            return Ok(());
+        };
+
+        match place {
+            Expression::Identifier(identifier) => {
+                let mutable = self
+                    .scopes
+                    .borrow()
+                    .iter()
+                    .rev()
+                    .find_map(|scope| scope.get(identifier))
+                    .is_none_or(|variable| variable.mutable);
+
+                match mutable {
+                    true => Ok(()),
+                    false => Err(self.error(format!(
+                        "cannot assign to `{identifier}`, only `let` variables can be assigned to"
+                    ))),
+                }
+            }
+            Expression::Index { target, .. }
+                if matches!(
+                    target.as_ref(),
+                    ASTUnit::Expression(target)
+                        if matches!(self.types.borrow().get(target), Some(Type::Slice(_)))
+                ) =>
+            {
+                Ok(())
+            }
+            Expression::FieldAccess { target, .. } | Expression::Index { target, .. } => {
+                self.check_assignable(target)
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+// numbers convert into each other, bools and chars only from and into integers,
+// strings, structs, enums, arrays, tuples and pointers only into themselves
+pub fn is_castable(from: &Type, to: &Type) -> bool {
+    if from == to {
+        return true;
+    }
+
+    match (from, to) {
+        (from, to) if !is_scalar(from) || !is_scalar(to) => false,
+        (Type::Bool | Type::Char, to) => to.is_integer(),
+        (from, Type::Bool | Type::Char) => from.is_integer(),
+        (from, to) => from.is_numeric() && to.is_numeric(),
+    }
+}
+
+// integer and float literals, also negated ones
+fn is_number_literal(unit: &ASTUnit) -> bool {
+    match unit {
+        ASTUnit::Expression(Expression::Literal(literal)) => literal_type(literal).is_numeric(),
+        ASTUnit::Expression(Expression::BinaryExpression {
+            left,
+            right,
+            operation: Operation::Algebraic(_),
+        }) => is_number_literal(left) && is_number_literal(right),
+        _ => false,
+    }
+}
+
+fn integer_literal(unit: &ASTUnit) -> Option<i128> {
+    match unit {
+        ASTUnit::Expression(Expression::Literal(literal)) => match *literal {
+            Literal::Int8(value) => Some(value.into()),
+            Literal::UInt8(value) => Some(value.into()),
+            Literal::Int16(value) => Some(value.into()),
+            Literal::UInt16(value) => Some(value.into()),
+            Literal::Int32(value) => Some(value.into()),
+            Literal::UInt32(value) => Some(value.into()),
+            Literal::Int64(value) => Some(value.into()),
+            Literal::UInt64(value) => Some(value.into()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+// whether an integer is in the range of an integer type, other types take any
+fn fits(value: i128, ty: &Type) -> bool {
+    let (min, max) = match ty {
+        Type::Int8 => (i8::MIN.into(), i8::MAX.into()),
+        Type::UInt8 => (0, u8::MAX.into()),
+        Type::Int16 => (i16::MIN.into(), i16::MAX.into()),
+        Type::UInt16 => (0, u16::MAX.into()),
+        Type::Int32 => (i32::MIN.into(), i32::MAX.into()),
+        Type::UInt32 => (0, u32::MAX.into()),
+        Type::Int64 => (i64::MIN.into(), i64::MAX.into()),
+        Type::UInt64 => (0, u64::MAX.into()),
+        _ => return true,
+    };
+
+    (min..=max).contains(&value)
+}
+
+// both integers or both floats
+fn is_same_kind(from: &Type, to: &Type) -> bool {
+    from.is_integer() && to.is_integer() || from.is_float() && to.is_float()
+}
+
+fn bit_width(ty: &Type) -> u32 {
+    match ty {
+        Type::Int8 | Type::UInt8 => 8,
+        Type::Int16 | Type::UInt16 => 16,
+        Type::Int32 | Type::UInt32 | Type::Float32 => 32,
+        _ => 64,
+    }
+}
+
+fn is_scalar(ty: &Type) -> bool {
+    ty.is_numeric() || matches!(ty, Type::Bool | Type::Char)
+}
+
+// what C functions take, strings are passed as pointers to their bytes
+fn is_ffi_safe(ty: &Type) -> bool {
+    is_scalar(ty) || matches!(ty, Type::Pointer(_) | Type::String)
+}
+
+pub(crate) fn literal_type(literal: &Literal) -> Type {
+    match literal {
+        Literal::String(_) => Type::String,
+        Literal::Int8(_) => Type::Int8,
+        Literal::UInt8(_) => Type::UInt8,
+        Literal::Int16(_) => Type::Int16,
+        Literal::UInt16(_) => Type::UInt16,
+        Literal::Int32(_) => Type::Int32,
+        Literal::UInt32(_) => Type::UInt32,
+        Literal::Int64(_) => Type::Int64,
+        Literal::UInt64(_) => Type::UInt64,
+        Literal::Float32(_) => Type::Float32,
+        Literal::Float64(_) => Type::Float64,
+        Literal::Bool(_) => Type::Bool,
+        Literal::Char(_) => Type::Char,
+    }
+}
+
+fn address(expression: &Expression) -> usize {
+    expression as *const Expression as usize
+}
@@ -0,0 +1,283 @@
+use common::dump::TreeDump;
+use lexer::lexer::Lexer;
+use parser::Parser;
+use preprocessor::Preprocessor;
+
+#[test]
+pub fn arrays_are_lowered() {
+    let code = r#"
+    fun last(xs: [i64; 3]) -> i64 {
+        let ys = [0; 2];
+        ys[1] = xs.len();
+        xs[2] + (ys[1] as i64)
+    }
+
+    let xs = [1 as i64, 2, 3];
+    "#;
+
+    let tokens = Lexer::new().run(code);
+    let ast = Parser::new().run(&tokens);
+    let last = Preprocessor::new().run(ast);
+
+    assert_eq!(
+        last.dump_tree(),
+        "\
+LoweredAbstractSyntaxTree
+  FunctionDeclaration 0(xs: [i64; 3]) -> i64
+    VariableDeclaration ssa 1
+      Literal Int32(0)
+    VariableDeclaration ssa 0
+      ArrayRepeat 2
+        Identifier 1
+    VariableDeclaration stack ys
+      Identifier 0
+    VariableDeclaration ssa 4
+      Identifier xs
+    VariableDeclaration ssa 3
+      FunctionInvokation len
+        Identifier 4
+    VariableDeclaration ssa 5
+      Cast i64 -> i32
+        Identifier 3
+    VariableDeclaration ssa 6
+      Literal Int32(1)
+    BinaryExpression =
+      Index
+        Identifier ys
+        Identifier 6
+      Identifier 5
+    VariableDeclaration ssa 12
+      Literal Int32(1)
+    VariableDeclaration ssa 11
+      Index
+        Identifier ys
+        Identifier 12
+    VariableDeclaration ssa 10
+      Cast i32 -> i64
+        Identifier 11
+    VariableDeclaration ssa 9
+      Literal Int32(2)
+    VariableDeclaration ssa 8
+      Index
+        Identifier xs
+        Identifier 9
+    VariableDeclaration ssa 7
+      BinaryExpression +
+        Identifier 8
+        Identifier 10
+    Return
+      Identifier 7
+  VariableDeclaration ssa 2
+    Literal Int32(1)
+  VariableDeclaration ssa 1
+    Cast i32 -> i64
+      Identifier 2
+  VariableDeclaration ssa 3
+    Literal Int32(2)
+  VariableDeclaration ssa 4
+    Cast i32 -> i64
+      Identifier 3
+  VariableDeclaration ssa 5
+    Literal Int32(3)
+  VariableDeclaration ssa 6
+    Cast i32 -> i64
+      Identifier 5
+  VariableDeclaration ssa 0
+    Array
+      Identifier 1
+      Identifier 4
+      Identifier 6
+  VariableDeclaration stack xs
+    Identifier 0
+"
+    );
+}
+
+fn diagnostic(code: &str) -> String {
+    let tokens = Lexer::new().run(code);
+    let ast = Parser::new().run(&tokens);
+
+    Preprocessor::new()
+        .try_run(ast)
+        .unwrap_err()
+        .message()
+        .to_string()
+}
+
+#[test]
+pub fn array_errors_are_reported() {
+    assert_eq!(
+        diagnostic("let a = [1, 2]; let b = a[true];"),
+        "array index must be an integer, got `bool`"
+    );
+    assert_eq!(
+        diagnostic("let a = [1, 2]; let b = a[2];"),
+        "index 2 is out of bounds for `[i32; 2]`"
+    );
+    assert_eq!(
+        diagnostic("let a = 1; let b = a[0];"),
+        "`i32` cannot be indexed"
+    );
+    assert_eq!(
+        diagnostic("let a = [1, \"b\"];"),
+        "array element 1 has type `String`, expected `i32`"
+    );
+    assert_eq!(
+        diagnostic("let a = [1, 2, true];"),
+        "array element 2 has type `bool`, expected `i32`"
+    );
+    assert_eq!(
+        diagnostic("let a = [1 as u8, 2 as i64];"),
+        "array element 1 has type `i64`, expected `u8`"
+    );
+    assert_eq!(
+        diagnostic("let a: [i64; 2] = [1, 2];"),
+        "cannot cast [i32; 2] to [i64; 2]"
+    );
+    assert_eq!(
+        diagnostic("let a = 1; let b = a.len();"),
+        "`len` expects a string, an array or a slice, got `i32`"
+    );
+    assert_eq!(
+        diagnostic("let a = [0; 2]; let b = a[0].w;"),
+        "`i32` has no field `w`"
+    );
+}
+
+#[test]
+pub fn slices_are_lowered() {
+    let code = r#"
+    fun first(xs: [i64]) -> i64 {
+        xs[0] = 2;
+        xs[0]
+    }
+
+    let xs = [1 as i64, 2, 3];
+    let tail = xs[1..];
+    "#;
+
+    let tokens = Lexer::new().run(code);
+    let ast = Parser::new().run(&tokens);
+    let last = Preprocessor::new().run(ast);
+
+    assert_eq!(
+        last.dump_tree(),
+        "\
+LoweredAbstractSyntaxTree
+  FunctionDeclaration 0(xs: [i64]) -> i64
+    VariableDeclaration ssa 1
+      Literal Int32(2)
+    VariableDeclaration ssa 2
+      Cast i32 -> i64
+        Identifier 1
+    VariableDeclaration ssa 3
+      Identifier xs
+    VariableDeclaration ssa 4
+      Literal Int32(0)
+    BinaryExpression =
+      Index
+        Identifier 3
+        Identifier 4
+      Identifier 2
+    VariableDeclaration ssa 6
+      Identifier xs
+    VariableDeclaration ssa 7
+      Literal Int32(0)
+    VariableDeclaration ssa 5
+      Index
+        Identifier 6
+        Identifier 7
+    Return
+      Identifier 5
+  VariableDeclaration ssa 2
+    Literal Int32(1)
+  VariableDeclaration ssa 1
+    Cast i32 -> i64
+      Identifier 2
+  VariableDeclaration ssa 3
+    Literal Int32(2)
+  VariableDeclaration ssa 4
+    Cast i32 -> i64
+      Identifier 3
+  VariableDeclaration ssa 5
+    Literal Int32(3)
+  VariableDeclaration ssa 6
+    Cast i32 -> i64
+      Identifier 5
+  VariableDeclaration ssa 0
+    Array
+      Identifier 1
+      Identifier 4
+      Identifier 6
+  VariableDeclaration stack xs
+    Identifier 0
+  VariableDeclaration ssa 1
+    Literal Int32(1)
+  VariableDeclaration ssa 0
+    Slice
+      Identifier xs
+      Start
+        Identifier 1
+  VariableDeclaration stack tail
+    Identifier 0
+"
+    );
+}
+
+#[test]
+pub fn slice_errors_are_reported() {
+    assert_eq!(
+        diagnostic("let a = [1, 2]; let b = a[0..true];"),
+        "slice bound must be an integer, got `bool`"
+    );
+    assert_eq!(
+        diagnostic("let a = 1; let b = a[0..1];"),
+        "`i32` cannot be sliced"
+    );
+    assert_eq!(
+        diagnostic("let a = [1, 2]; let b: [i64] = a[..];"),
+        "cannot cast [i32] to [i64]"
+    );
+}
+
+#[test]
+pub fn literals_take_the_element_type() {
+    let code = "let x = 2 as i64;\nlet xs = [1, x, 3];";
+
+    let tokens = Lexer::new().run(code);
+    let ast = Parser::new().run(&tokens);
+    let last = Preprocessor::new().run(ast);
+
+    assert_eq!(
+        last.dump_tree(),
+        "\
+LoweredAbstractSyntaxTree
+  VariableDeclaration ssa 1
+    Literal Int32(2)
+  VariableDeclaration ssa 0
+    Cast i32 -> i64
+      Identifier 1
+  VariableDeclaration stack x
+    Identifier 0
+  VariableDeclaration ssa 1
+    Literal Int32(1)
+  VariableDeclaration ssa 2
+    Cast i32 -> i64
+      Identifier 1
+  VariableDeclaration ssa 3
+    Identifier x
+  VariableDeclaration ssa 4
+    Literal Int32(3)
+  VariableDeclaration ssa 5
+    Cast i32 -> i64
+      Identifier 4
+  VariableDeclaration ssa 0
+    Array
+      Identifier 2
+      Identifier 3
+      Identifier 5
+  VariableDeclaration stack xs
+    Identifier 0
+"
+    );
+}
@@ -1,5 +1,6 @@
 use std::rc::Rc;
 
+use common::types::Type;
 use lexer::lexer::Lexer;
 use parser::{
     ast::operation::{AlgebraicOperation, LogicalOperation},
@@ -42,6 +43,7 @@ pub fn code_blocks_work() {
         &LASTUnit::Declaration(Declaration::VariableDeclaration {
             allocation: VariableAllocation::SSA,
             identifier: "0".to_string(),
+            ty: None,
             expression: Rc::new(Expression::Literal(parser::ast::literal::Literal::Int32(1)))
         })
     );
@@ -50,6 +52,7 @@ pub fn code_blocks_work() {
         &LASTUnit::Declaration(Declaration::VariableDeclaration {
             allocation: VariableAllocation::Stack,
             identifier: "b".to_string(),
+            ty: Some(Type::Int32),
             expression: Rc::new(Expression::Identifier("0".to_string()))
         })
     );
@@ -58,6 +61,7 @@ pub fn code_blocks_work() {
         &LASTUnit::Declaration(Declaration::VariableDeclaration {
             allocation: VariableAllocation::SSA,
             identifier: "1".to_string(),
+            ty: None,
             expression: Rc::new(Expression::Literal(parser::ast::literal::Literal::Int32(2)))
         })
     );
@@ -66,6 +70,7 @@ pub fn code_blocks_work() {
         &LASTUnit::Declaration(Declaration::VariableDeclaration {
             allocation: VariableAllocation::Stack,
             identifier: "b0".to_string(),
+            ty: Some(Type::Int32),
             expression: Rc::new(Expression::Identifier("1".to_string()))
         })
     );
@@ -74,6 +79,7 @@ pub fn code_blocks_work() {
         &LASTUnit::Declaration(Declaration::VariableDeclaration {
             allocation: VariableAllocation::SSA,
             identifier: "4".to_string(),
+            ty: None,
             expression: Rc::new(Expression::Identifier("c".to_string()))
         })
     );
@@ -82,6 +88,7 @@ pub fn code_blocks_work() {
         &LASTUnit::Declaration(Declaration::VariableDeclaration {
             allocation: VariableAllocation::SSA,
             identifier: "3".to_string(),
+            ty: None,
             expression: Rc::new(Expression::Identifier("b0".to_string()))
         })
     );
@@ -90,10 +97,12 @@ pub fn code_blocks_work() {
         &LASTUnit::Declaration(Declaration::VariableDeclaration {
             allocation: VariableAllocation::SSA,
             identifier: "2".to_string(),
+            ty: None,
             expression: Rc::new(Expression::BinaryExpression {
                 left: Rc::new(Expression::Identifier("3".to_string())),
                 right: Rc::new(Expression::Identifier("4".to_string())),
-                operation: Operation::Algebraic(AlgebraicOperation::Addition)
+                operation: Operation::Algebraic(AlgebraicOperation::Addition),
+                operands: Some(Type::Int32)
             })
         })
     );
@@ -102,6 +111,7 @@ pub fn code_blocks_work() {
         &LASTUnit::Declaration(Declaration::VariableDeclaration {
             allocation: VariableAllocation::SSA,
             identifier: "0".to_string(),
+            ty: None,
             expression: Rc::new(Expression::Identifier("2".to_string()))
         })
     );
@@ -110,6 +120,7 @@ pub fn code_blocks_work() {
         &LASTUnit::Declaration(Declaration::VariableDeclaration {
             allocation: VariableAllocation::Stack,
             identifier: "a".to_string(),
+            ty: None,
             expression: Rc::new(Expression::Identifier("0".to_string()))
         })
     );
@@ -118,6 +129,7 @@ pub fn code_blocks_work() {
         &LASTUnit::Declaration(Declaration::VariableDeclaration {
             allocation: VariableAllocation::SSA,
             identifier: "0".to_string(),
+            ty: None,
             expression: Rc::new(Expression::Identifier("b".to_string()))
         })
     );
@@ -126,6 +138,7 @@ pub fn code_blocks_work() {
         &LASTUnit::Declaration(Declaration::VariableDeclaration {
             allocation: VariableAllocation::Stack,
             identifier: "c".to_string(),
+            ty: Some(Type::Int32),
             expression: Rc::new(Expression::Identifier("0".to_string()))
         })
     );
@@ -153,6 +166,7 @@ pub fn control_flow_as_expression_works() {
         &LASTUnit::Declaration(Declaration::VariableDeclaration {
             allocation: VariableAllocation::SSA,
             identifier: "3".to_string(),
+            ty: None,
             expression: Rc::new(Expression::Identifier("c".to_string()))
         })
     );
@@ -162,6 +176,7 @@ pub fn control_flow_as_expression_works() {
         &LASTUnit::Declaration(Declaration::VariableDeclaration {
             allocation: VariableAllocation::SSA,
             identifier: "2".to_string(),
+            ty: None,
             expression: Rc::new(Expression::Identifier("b".to_string()))
         })
     );
@@ -171,10 +186,12 @@ pub fn control_flow_as_expression_works() {
         &LASTUnit::Declaration(Declaration::VariableDeclaration {
             allocation: VariableAllocation::SSA,
             identifier: "1".to_string(),
+            ty: None,
             expression: Rc::new(Expression::BinaryExpression {
                 left: Rc::new(Expression::Identifier("2".to_string())),
                 right: Rc::new(Expression::Identifier("3".to_string())),
-                operation: Operation::Logical(LogicalOperation::Greater)
+                operation: Operation::Logical(LogicalOperation::Greater),
+                operands: None
             })
         })
     );
@@ -187,11 +204,13 @@ pub fn control_flow_as_expression_works() {
                 Rc::new(LASTUnit::Declaration(Declaration::VariableDeclaration {
                     allocation: VariableAllocation::SSA,
                     identifier: "4".to_string(),
+                    ty: None,
                     expression: Rc::new(Expression::Identifier("b".to_string()))
                 })),
                 Rc::new(LASTUnit::Declaration(Declaration::VariableDeclaration {
                     allocation: VariableAllocation::SSA,
                     identifier: "0".to_string(),
+                    ty: None,
                     expression: Rc::new(Expression::Identifier("4".to_string()))
                 }))
             ],
@@ -199,11 +218,13 @@ pub fn control_flow_as_expression_works() {
                 Rc::new(LASTUnit::Declaration(Declaration::VariableDeclaration {
                     allocation: VariableAllocation::SSA,
                     identifier: "5".to_string(),
+                    ty: None,
                     expression: Rc::new(Expression::Identifier("c".to_string()))
                 })),
                 Rc::new(LASTUnit::Declaration(Declaration::VariableDeclaration {
                     allocation: VariableAllocation::SSA,
                     identifier: "0".to_string(),
+                    ty: None,
                     expression: Rc::new(Expression::Identifier("5".to_string()))
                 }))
             ])
@@ -215,6 +236,7 @@ pub fn control_flow_as_expression_works() {
         &LASTUnit::Declaration(Declaration::VariableDeclaration {
             allocation: VariableAllocation::Stack,
             identifier: "a".to_string(),
+            ty: None,
             expression: Rc::new(Expression::Identifier("0".to_string()))
         })
     );
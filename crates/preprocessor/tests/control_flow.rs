@@ -35,6 +35,7 @@ pub fn control_flow_works() {
         &LASTUnit::Declaration(Declaration::VariableDeclaration {
             allocation: VariableAllocation::SSA,
             identifier: "2".to_string(),
+            ty: None,
             expression: Rc::new(Expression::Identifier("b".to_string()))
         })
     );
@@ -44,6 +45,7 @@ pub fn control_flow_works() {
         &LASTUnit::Declaration(Declaration::VariableDeclaration {
             allocation: VariableAllocation::SSA,
             identifier: "1".to_string(),
+            ty: None,
             expression: Rc::new(Expression::Identifier("a".to_string()))
         })
     );
@@ -53,10 +55,12 @@ pub fn control_flow_works() {
         &LASTUnit::Declaration(Declaration::VariableDeclaration {
             allocation: VariableAllocation::SSA,
             identifier: "0".to_string(),
+            ty: None,
             expression: Rc::new(Expression::BinaryExpression {
                 left: Rc::new(Expression::Identifier("1".to_string())),
                 right: Rc::new(Expression::Identifier("2".to_string())),
-                operation: Operation::Logical(parser::ast::operation::LogicalOperation::Greater)
+                operation: Operation::Logical(parser::ast::operation::LogicalOperation::Greater),
+                operands: None
             })
         })
     );
@@ -69,11 +73,13 @@ pub fn control_flow_works() {
                 Rc::new(LASTUnit::Declaration(Declaration::VariableDeclaration {
                     allocation: VariableAllocation::SSA,
                     identifier: "4".to_string(),
+                    ty: None,
                     expression: Rc::new(Expression::Literal(Literal::Int32(1)))
                 })),
                 Rc::new(LASTUnit::Declaration(Declaration::VariableDeclaration {
                     allocation: VariableAllocation::SSA,
                     identifier: "3".to_string(),
+                    ty: None,
                     expression: Rc::new(Expression::Identifier("4".to_string()))
                 }))
             ],
@@ -81,11 +87,13 @@ pub fn control_flow_works() {
                 Rc::new(LASTUnit::Declaration(Declaration::VariableDeclaration {
                     allocation: VariableAllocation::SSA,
                     identifier: "5".to_string(),
+                    ty: None,
                     expression: Rc::new(Expression::Literal(Literal::Int32(2)))
                 })),
                 Rc::new(LASTUnit::Declaration(Declaration::VariableDeclaration {
                     allocation: VariableAllocation::SSA,
                     identifier: "3".to_string(),
+                    ty: None,
                     expression: Rc::new(Expression::Identifier("5".to_string()))
                 }))
             ])
@@ -0,0 +1,84 @@
+use common::dump::TreeDump;
+use lexer::lexer::Lexer;
+use parser::Parser;
+use preprocessor::Preprocessor;
+
+#[test]
+pub fn last_dump_works() {
+    let code = r#"
+    fun count(n: i32) -> i32 {
+        let i = 0;
+        while i < n {
+            i = i + 1;
+        }
+        return i;
+    }
+    "#;
+
+    let tokens = Lexer::new().run(code);
+    let ast = Parser::new().run(&tokens);
+    let last = Preprocessor::new().run(ast);
+
+    assert_eq!(
+        last.dump_tree(),
+        "\
+LoweredAbstractSyntaxTree
+  FunctionDeclaration 0(n: i32) -> i32
+    VariableDeclaration ssa 0
+      Literal Int32(0)
+    VariableDeclaration stack i
+      Identifier 0
+    Loop
+      Header
+        VariableDeclaration ssa 3
+          Identifier n
+        VariableDeclaration ssa 2
+          Identifier i
+        VariableDeclaration ssa 1
+          BinaryExpression <
+            Identifier 2
+            Identifier 3
+      Condition
+        Identifier 1
+      Body
+        VariableDeclaration ssa 7
+          Literal Int32(1)
+        VariableDeclaration ssa 6
+          Identifier i
+        VariableDeclaration ssa 5
+          BinaryExpression +
+            Identifier 6
+            Identifier 7
+        BinaryExpression =
+          Identifier i
+          Identifier 5
+    VariableDeclaration ssa 8
+      Identifier i
+    Return
+      Identifier 8
+"
+    );
+}
+
+#[test]
+pub fn last_dump_keeps_locations() {
+    let code = "fun main() -> i32 {\n  return 1;\n}\n";
+
+    let (tokens, spans) = Lexer::new().try_run_spanned(code).unwrap();
+    let ast = Parser::new().run_spanned(&tokens, &spans);
+    let last = Preprocessor::new().run(ast);
+
+    assert_eq!(
+        last.dump_tree(),
+        "\
+LoweredAbstractSyntaxTree
+  Location 0..3
+  FunctionDeclaration 0() -> i32
+    Location 22..28
+    VariableDeclaration ssa 0
+      Literal Int32(1)
+    Return
+      Identifier 0
+"
+    );
+}
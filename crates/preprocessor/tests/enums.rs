@@ -0,0 +1,234 @@
+use common::dump::TreeDump;
+use lexer::lexer::Lexer;
+use parser::Parser;
+use preprocessor::Preprocessor;
+
+#[test]
+pub fn enums_are_lowered() {
+    let code = r#"
+    enum Shape { Circle(i32), Rect { w: i32, h: i32 } }
+
+    fun area(s: Shape) -> i32 {
+        match s {
+            Shape::Circle(r) if r > 10 => r * r,
+            Shape::Rect { w, h: 0 } => w,
+            _ => 0
+        }
+    }
+
+    let s = Shape::Rect { h: 2, w: 3 };
+    "#;
+
+    let tokens = Lexer::new().run(code);
+    let ast = Parser::new().run(&tokens);
+    let last = Preprocessor::new().run(ast);
+
+    assert_eq!(
+        last.dump_tree(),
+        "\
+LoweredAbstractSyntaxTree
+  EnumDeclaration Shape { Circle(i32), Rect { w: i32, h: i32 } }
+  FunctionDeclaration 0(s: Shape) -> i32
+    VariableDeclaration ssa 1
+      Identifier s
+    VariableDeclaration ssa 2
+      Tag
+        Identifier 1
+    Switch
+      Identifier 2
+      Case 0
+        VariableDeclaration ssa 3
+          Payload Shape::Circle (0)
+            Identifier 1
+        VariableDeclaration ssa r
+          Identifier 3
+        VariableDeclaration ssa 6
+          Literal Int32(10)
+        VariableDeclaration ssa 5
+          Identifier r
+        VariableDeclaration ssa 4
+          BinaryExpression >
+            Identifier 5
+            Identifier 6
+        ControlFlow
+          Condition
+            Identifier 4
+          Then
+            VariableDeclaration ssa 8
+              Identifier r
+            VariableDeclaration ssa 7
+              Identifier r
+            VariableDeclaration ssa 0
+              BinaryExpression *
+                Identifier 7
+                Identifier 8
+          Else
+            VariableDeclaration ssa 0
+              Literal Int32(0)
+      Case 1
+        VariableDeclaration ssa 10
+          Payload Shape::Rect (1)
+            Identifier 1
+        VariableDeclaration ssa 11
+          Literal Int32(0)
+        VariableDeclaration ssa 12
+          BinaryExpression ==
+            Identifier 10
+            Identifier 11
+        ControlFlow
+          Condition
+            Identifier 12
+          Then
+            VariableDeclaration ssa 9
+              Payload Shape::Rect (0)
+                Identifier 1
+            VariableDeclaration ssa w
+              Identifier 9
+            VariableDeclaration ssa 0
+              Identifier w
+          Else
+            VariableDeclaration ssa 0
+              Literal Int32(0)
+    Return
+      Identifier 0
+  VariableDeclaration ssa 1
+    Literal Int32(2)
+  VariableDeclaration ssa 2
+    Literal Int32(3)
+  VariableDeclaration ssa 0
+    Variant Shape::Rect (1)
+      Identifier 2
+      Identifier 1
+  VariableDeclaration stack s
+    Identifier 0
+"
+    );
+}
+
+#[test]
+pub fn literal_patterns_are_lowered() {
+    let code = r#"
+    let a = 1 as i64;
+    let b = match a {
+        1 => 2,
+        n => n
+    };
+    "#;
+
+    let tokens = Lexer::new().run(code);
+    let ast = Parser::new().run(&tokens);
+    let last = Preprocessor::new().run(ast);
+
+    assert_eq!(
+        last.dump_tree(),
+        "\
+LoweredAbstractSyntaxTree
+  VariableDeclaration ssa 1
+    Literal Int32(1)
+  VariableDeclaration ssa 0
+    Cast i32 -> i64
+      Identifier 1
+  VariableDeclaration stack a
+    Identifier 0
+  VariableDeclaration ssa 2
+    Identifier a
+  VariableDeclaration ssa 3
+    Literal Int32(1)
+  VariableDeclaration ssa 4
+    Cast i32 -> i64
+      Identifier 3
+  VariableDeclaration ssa 5
+    BinaryExpression ==
+      Identifier 2
+      Identifier 4
+  ControlFlow
+    Condition
+      Identifier 5
+    Then
+      VariableDeclaration ssa 1
+        Literal Int32(2)
+    Else
+      VariableDeclaration ssa n
+        Identifier 2
+      VariableDeclaration ssa 1
+        Identifier n
+  VariableDeclaration ssa 0
+    Identifier 1
+  VariableDeclaration stack b
+    Identifier 0
+"
+    );
+}
+
+fn diagnostic(code: &str) -> String {
+    let tokens = Lexer::new().run(code);
+    let ast = Parser::new().run(&tokens);
+
+    Preprocessor::new()
+        .try_run(ast)
+        .unwrap_err()
+        .message()
+        .to_string()
+}
+
+#[test]
+pub fn enum_errors_are_reported() {
+    let shape = "enum Shape { Circle(i32), Rect { w: i32, h: i32 } } let s = Shape::Circle(1);";
+
+    assert_eq!(
+        diagnostic(&format!(
+            "{shape} let a = match s {{ Shape::Circle(r) => r }};"
+        )),
+        "non-exhaustive match, `Shape::Rect` is not covered"
+    );
+    assert_eq!(
+        diagnostic(&format!(
+            "{shape} let a = match s {{ Shape::Circle(r) if r > 1 => r, Shape::Rect {{ w, h }} => w }};"
+        )),
+        "non-exhaustive match, `Shape::Circle` is not covered"
+    );
+    assert_eq!(
+        diagnostic(&format!(
+            "{shape} let a = match s {{ Shape::Circle(1) => 1, Shape::Rect {{ w, h }} => w }};"
+        )),
+        "non-exhaustive match, `Shape::Circle` is not covered"
+    );
+    assert_eq!(
+        diagnostic("let a = match 1 { 1 => 2 };"),
+        "non-exhaustive match, add a `_` arm"
+    );
+    assert_eq!(
+        diagnostic(&format!(
+            "{shape} let a = match 1 {{ Shape::Circle(r) => r, _ => 0 }};"
+        )),
+        "pattern of type `Shape` cannot match a value of type `i32`"
+    );
+    assert_eq!(
+        diagnostic(&format!(
+            "{shape} let a = match s {{ Shape::Square => 1, _ => 0 }};"
+        )),
+        "enum `Shape` has no variant `Square`"
+    );
+    assert_eq!(
+        diagnostic(&format!(
+            "{shape} let a = match s {{ _ if \"a\" => 1, _ => 0 }};"
+        )),
+        "match guard must be bool, got `String`"
+    );
+    assert_eq!(
+        diagnostic(&format!("{shape} let t = Shape::Rect {{ w: 1 }};")),
+        "missing field `h` in `Shape::Rect`"
+    );
+    assert_eq!(
+        diagnostic(&format!("{shape} let t = Shape::Circle(1, 2);")),
+        "variant `Shape::Circle` has no field `1`"
+    );
+    assert_eq!(
+        diagnostic(&format!("{shape} let w = s.w;")),
+        "`Shape` has no field `w`"
+    );
+    assert_eq!(
+        diagnostic("struct Shape { a: i32 } enum Shape { A }"),
+        "enum `Shape` is declared twice"
+    );
+}
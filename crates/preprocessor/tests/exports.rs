@@ -0,0 +1,144 @@
+use common::dump::TreeDump;
+use lexer::lexer::Lexer;
+use parser::Parser;
+use preprocessor::Preprocessor;
+
+#[test]
+pub fn exported_functions_are_lowered() {
+    let code = r#"
+    fun twice(x: i32) -> i32 {
+        x * 2
+    }
+
+    pub extern fun add(a: i32, b: i32) -> i32 {
+        twice(a) + b
+    }
+
+    let x = add(1, 2);
+    "#;
+
+    let tokens = Lexer::new().run(code);
+    let ast = Parser::new().run(&tokens);
+    let last = Preprocessor::new().run(ast);
+
+    assert_eq!(
+        last.dump_tree(),
+        "\
+LoweredAbstractSyntaxTree
+  FunctionDeclaration 0(x: i32) -> i32
+    VariableDeclaration ssa 2
+      Literal Int32(2)
+    VariableDeclaration ssa 1
+      Identifier x
+    VariableDeclaration ssa 0
+      BinaryExpression *
+        Identifier 1
+        Identifier 2
+    Return
+      Identifier 0
+  FunctionDeclaration pub extern add(a: i32, b: i32) -> i32
+    VariableDeclaration ssa 3
+      Identifier b
+    VariableDeclaration ssa 2
+      Identifier a
+    VariableDeclaration ssa 1
+      FunctionInvokation 0
+        Identifier 2
+    VariableDeclaration ssa 0
+      BinaryExpression +
+        Identifier 1
+        Identifier 3
+    Return
+      Identifier 0
+  VariableDeclaration ssa 1
+    Literal Int32(1)
+  VariableDeclaration ssa 2
+    Literal Int32(2)
+  VariableDeclaration ssa 0
+    FunctionInvokation add
+      Identifier 1
+      Identifier 2
+  VariableDeclaration stack x
+    Identifier 0
+"
+    );
+}
+
+#[test]
+pub fn c_headers_declare_exported_functions() {
+    let code = r#"
+    fun hidden() {}
+
+    pub extern fun add(a: i32, b: u64) -> i64 {
+        a as i64 + b as i64
+    }
+    pub extern "C" fun fill(bytes: *u8, count: u64, ratio: f64) {}
+    pub extern fun next(node: **Node, flag: bool) -> *Node {
+        *node
+    }
+    pub extern fun reset() {}
+
+    struct Node { value: i32 }
+    "#;
+
+    let tokens = Lexer::new().run(code);
+    let ast = Parser::new().run(&tokens);
+    let last = Preprocessor::new().run(ast);
+
+    assert_eq!(
+        last.c_header("my-lib"),
+        "\
+#ifndef BLOOM_MY_LIB_H
+#define BLOOM_MY_LIB_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern \"C\" {
+#endif
+
+int64_t add(int32_t a, uint64_t b);
+void fill(uint8_t *bytes, uint64_t count, double ratio);
+void *next(void **node, bool flag);
+void reset(void);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif // BLOOM_MY_LIB_H
+"
+    );
+}
+
+fn diagnostic(code: &str) -> String {
+    let tokens = Lexer::new().run(code);
+    let ast = Parser::new().run(&tokens);
+
+    Preprocessor::new()
+        .try_run(ast)
+        .unwrap_err()
+        .message()
+        .to_string()
+}
+
+#[test]
+pub fn export_errors_are_reported() {
+    assert_eq!(
+        diagnostic(r#"pub extern "Rust" fun f() {}"#),
+        "unsupported ABI \"Rust\" of `f`, only \"C\" is supported"
+    );
+    assert_eq!(
+        diagnostic("pub extern fun main() -> i32 { 0 }"),
+        "`main` cannot be exported"
+    );
+    assert_eq!(
+        diagnostic("pub extern fun f(s: String) {}"),
+        "`String` cannot be passed to the exported function `f`"
+    );
+    assert_eq!(
+        diagnostic("pub extern fun f() -> (i32, i32) { (1, 2) }"),
+        "exported function `f` cannot return `(i32, i32)`"
+    );
+}
@@ -0,0 +1,91 @@
+use common::dump::TreeDump;
+use lexer::lexer::Lexer;
+use parser::Parser;
+use preprocessor::Preprocessor;
+
+#[test]
+pub fn extern_functions_are_lowered() {
+    let code = r#"
+    fun greet() -> i32 {
+        printf("%s %d\n", "hello", 42)
+    }
+
+    extern "C" fun printf(format: String, ...) -> i32;
+    extern fun abs(x: i32) -> i32;
+
+    let x = abs(greet());
+    "#;
+
+    let tokens = Lexer::new().run(code);
+    let ast = Parser::new().run(&tokens);
+    let last = Preprocessor::new().run(ast);
+
+    assert_eq!(
+        last.dump_tree(),
+        "\
+LoweredAbstractSyntaxTree
+  FunctionDeclaration 0() -> i32
+    VariableDeclaration ssa 1
+      Literal String(\"%s %d\\n\")
+    VariableDeclaration ssa 2
+      Literal String(\"hello\")
+    VariableDeclaration ssa 3
+      Literal Int32(42)
+    VariableDeclaration ssa 0
+      FunctionInvokation printf
+        Identifier 1
+        Identifier 2
+        Identifier 3
+    Return
+      Identifier 0
+  ExternFunctionDeclaration printf(format: String, ...) -> i32
+  ExternFunctionDeclaration abs(x: i32) -> i32
+  VariableDeclaration ssa 1
+    FunctionInvokation 0
+  VariableDeclaration ssa 0
+    FunctionInvokation abs
+      Identifier 1
+  VariableDeclaration stack x
+    Identifier 0
+"
+    );
+}
+
+fn diagnostic(code: &str) -> String {
+    let tokens = Lexer::new().run(code);
+    let ast = Parser::new().run(&tokens);
+
+    Preprocessor::new()
+        .try_run(ast)
+        .unwrap_err()
+        .message()
+        .to_string()
+}
+
+#[test]
+pub fn extern_function_errors_are_reported() {
+    assert_eq!(
+        diagnostic(r#"extern "Rust" fun f();"#),
+        "unsupported ABI \"Rust\" of `f`, only \"C\" is supported"
+    );
+    assert_eq!(
+        diagnostic("extern fun getenv(name: String) -> String;"),
+        "extern function `getenv` cannot return `String`"
+    );
+    assert_eq!(
+        diagnostic("extern fun f(xs: [i32; 2]);"),
+        "`[i32; 2]` cannot be passed to the extern function `f`"
+    );
+    assert_eq!(
+        diagnostic("extern fun f(p: *Missing);"),
+        "unknown type `Missing`"
+    );
+    assert_eq!(
+        diagnostic("extern fun printf(f: String, ...) -> i32; printf();"),
+        "`printf` expects at least 1 argument, got 0"
+    );
+    assert_eq!(
+        diagnostic("extern fun printf(f: String, ...) -> i32; printf(\"%d\", (1, 2));"),
+        "`(i32, i32)` cannot be passed to a variadic function"
+    );
+}
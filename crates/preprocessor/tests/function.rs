@@ -35,6 +35,7 @@ pub fn function_declaration_works() {
         mem::discriminant(&LASTUnit::Declaration(Declaration::VariableDeclaration {
             allocation: VariableAllocation::SSA,
             identifier: "".to_string(),
+            ty: None,
             expression: Rc::new(Expression::Literal(Literal::Int8(0)))
         }))
     );
@@ -45,6 +46,7 @@ pub fn function_declaration_works() {
                 mem::discriminant(decl),
                 mem::discriminant(&Declaration::FunctionDeclaration {
                     identifier: "".to_string(),
+                    exported: false,
                     parameters: vec![],
                     return_type: common::types::Type::Void,
                     body: vec![]
@@ -54,11 +56,13 @@ pub fn function_declaration_works() {
             match decl {
                 Declaration::FunctionDeclaration {
                     identifier,
+                    exported,
                     parameters,
                     return_type,
                     body,
                 } => {
                     assert_eq!(identifier, "0");
+                    assert!(!exported);
                     assert_eq!(parameters[0], ("x".to_string(), Type::Int32));
                     assert_eq!(parameters[1], ("y".to_string(), Type::Int32));
                     assert_eq!(return_type, &Type::Int32);
@@ -76,6 +80,7 @@ pub fn function_declaration_works() {
         &LASTUnit::Declaration(Declaration::VariableDeclaration {
             allocation: VariableAllocation::SSA,
             identifier: "2".to_string(),
+            ty: None,
             expression: Rc::new(Expression::Identifier("y".to_string()))
         })
     );
@@ -84,6 +89,7 @@ pub fn function_declaration_works() {
         &LASTUnit::Declaration(Declaration::VariableDeclaration {
             allocation: VariableAllocation::SSA,
             identifier: "1".to_string(),
+            ty: None,
             expression: Rc::new(Expression::Identifier("x".to_string()))
         })
     );
@@ -92,10 +98,12 @@ pub fn function_declaration_works() {
         &LASTUnit::Declaration(Declaration::VariableDeclaration {
             allocation: VariableAllocation::SSA,
             identifier: "0".to_string(),
+            ty: None,
             expression: Rc::new(Expression::BinaryExpression {
                 left: Rc::new(Expression::Identifier("1".to_string())),
                 right: Rc::new(Expression::Identifier("2".to_string())),
-                operation: Operation::Algebraic(AlgebraicOperation::Addition)
+                operation: Operation::Algebraic(AlgebraicOperation::Addition),
+                operands: Some(Type::Int32)
             })
         })
     );
@@ -137,3 +145,30 @@ pub fn function_implicit_return_works() {
 
     assert_eq!(root_explicit, root_implicit);
 }
+
+#[test]
+pub fn entry_point_works() {
+    let code = r#"
+    fun add(x: i32, y: i32) -> i32 {
+      return x + y;
+    }
+
+    fun main() -> i32 {
+      return add(1, 2);
+    }
+    "#;
+
+    let tokens = Lexer::new().run(code);
+    let ast = Parser::new().run(&tokens);
+
+    let last = Preprocessor::new().run(ast);
+
+    assert_eq!(last.entry_point(), Some("1"));
+    assert_eq!(last.function_name("0"), "add");
+    assert_eq!(last.function_name("1"), "main");
+
+    let tokens = Lexer::new().run("fun add(x: i32, y: i32) -> i32 { return x + y; }");
+    let ast = Parser::new().run(&tokens);
+
+    assert_eq!(Preprocessor::new().run(ast).entry_point(), None);
+}
@@ -0,0 +1,370 @@
+use common::dump::TreeDump;
+use lexer::lexer::Lexer;
+use parser::{ast::AbstractSyntaxTree, Parser};
+use preprocessor::{
+    modules::{self, Linker, SourceModule},
+    Preprocessor,
+};
+
+fn module(name: &str, code: &str) -> SourceModule {
+    SourceModule::new(name.to_string(), parse(code))
+}
+
+fn parse(code: &str) -> AbstractSyntaxTree {
+    Parser::new().run(&Lexer::new().run(code))
+}
+
+#[test]
+pub fn imported_items_are_qualified() {
+    let math = module(
+        "math",
+        r#"
+        pub struct Point { x: i32, y: i32 }
+
+        fun twice(x: i32) -> i32 {
+            x * 2
+        }
+
+        pub fun scale(point: Point) -> Point {
+            Point { x: twice(point.x), y: twice(point.y) }
+        }
+        "#,
+    );
+    let main = module(
+        "main",
+        r#"
+        import math;
+
+        // not the one of `math`
+        fun twice(x: i32) -> i32 {
+            x + x
+        }
+
+        let point = math::scale(math::Point { x: 1, y: twice(2) });
+        "#,
+    );
+
+    assert_eq!(modules::imports(&main.ast), vec!["math".to_string()]);
+
+    let ast = Linker::new().run(main, vec![math]).unwrap();
+
+    assert_eq!(
+        ast.dump_tree(),
+        "\
+AbstractSyntaxTree
+  Block
+    TypeDeclaration pub math::Point { x: i32, y: i32 }
+    FunctionDeclaration math::twice(x: i32) -> i32
+      Block
+        ImplicitReturn
+          BinaryExpression *
+            Identifier x
+            Literal Int32(2)
+    FunctionDeclaration pub math::scale(point: math::Point) -> math::Point
+      Block
+        ImplicitReturn
+          Struct math::Point
+            Field x
+              FunctionInvokation math::twice
+                FieldAccess x
+                  Identifier point
+            Field y
+              FunctionInvokation math::twice
+                FieldAccess y
+                  Identifier point
+    FunctionDeclaration twice(x: i32) -> i32
+      Block
+        ImplicitReturn
+          BinaryExpression +
+            Identifier x
+            Identifier x
+    VariableDeclaration let point
+      FunctionInvokation math::scale
+        Struct math::Point
+          Field x
+            Literal Int32(1)
+          Field y
+            FunctionInvokation twice
+              Literal Int32(2)
+"
+    );
+
+    let last = Preprocessor::new().run(ast);
+
+    assert_eq!(
+        last.dump_tree(),
+        "\
+LoweredAbstractSyntaxTree
+  TypeDeclaration math::Point { x: i32, y: i32 }
+  FunctionDeclaration 0(x: i32) -> i32
+    VariableDeclaration ssa 2
+      Literal Int32(2)
+    VariableDeclaration ssa 1
+      Identifier x
+    VariableDeclaration ssa 0
+      BinaryExpression *
+        Identifier 1
+        Identifier 2
+    Return
+      Identifier 0
+  FunctionDeclaration 1(point: math::Point) -> math::Point
+    VariableDeclaration ssa 3
+      Identifier point
+    VariableDeclaration ssa 2
+      FieldAccess x (0)
+        Identifier 3
+    VariableDeclaration ssa 1
+      FunctionInvokation 0
+        Identifier 2
+    VariableDeclaration ssa 6
+      Identifier point
+    VariableDeclaration ssa 5
+      FieldAccess y (1)
+        Identifier 6
+    VariableDeclaration ssa 4
+      FunctionInvokation 0
+        Identifier 5
+    VariableDeclaration ssa 0
+      Struct math::Point
+        Identifier 1
+        Identifier 4
+    Return
+      Identifier 0
+  FunctionDeclaration 2(x: i32) -> i32
+    VariableDeclaration ssa 2
+      Identifier x
+    VariableDeclaration ssa 1
+      Identifier x
+    VariableDeclaration ssa 0
+      BinaryExpression +
+        Identifier 1
+        Identifier 2
+    Return
+      Identifier 0
+  VariableDeclaration ssa 2
+    Literal Int32(1)
+  VariableDeclaration ssa 4
+    Literal Int32(2)
+  VariableDeclaration ssa 3
+    FunctionInvokation 2
+      Identifier 4
+  VariableDeclaration ssa 1
+    Struct math::Point
+      Identifier 2
+      Identifier 3
+  VariableDeclaration ssa 0
+    FunctionInvokation 1
+      Identifier 1
+  VariableDeclaration stack point
+    Identifier 0
+"
+    );
+}
+
+#[test]
+pub fn modules_import_each_other() {
+    let shapes = module(
+        "geometry::shapes",
+        r#"
+        import geometry::units;
+
+        pub enum Shape { Square(units::Length), Dot }
+
+        pub fun side(shape: Shape) -> units::Length {
+            match shape {
+                Shape::Square(length) => length,
+                Shape::Dot => units::zero()
+            }
+        }
+        "#,
+    );
+    let units = module(
+        "geometry::units",
+        r#"
+        pub struct Length { value: i32 }
+
+        pub fun zero() -> Length {
+            Length { value: 0 }
+        }
+        "#,
+    );
+    let main = module(
+        "main",
+        r#"
+        import geometry::shapes;
+        import geometry::units;
+
+        fun main() -> i32 {
+            shapes::side(shapes::Shape::Square(units::zero())).value
+        }
+        "#,
+    );
+
+    let ast = Linker::new().run(main, vec![shapes, units]).unwrap();
+
+    assert_eq!(
+        ast.dump_tree(),
+        "\
+AbstractSyntaxTree
+  Block
+    EnumDeclaration pub geometry::shapes::Shape { Square(geometry::units::Length), Dot }
+    FunctionDeclaration pub geometry::shapes::side(shape: geometry::shapes::Shape) -> geometry::units::Length
+      Block
+        ImplicitReturn
+          Match
+            Identifier shape
+            Arm geometry::shapes::Shape::Square(length)
+              Identifier length
+            Arm geometry::shapes::Shape::Dot
+              FunctionInvokation geometry::units::zero
+    TypeDeclaration pub geometry::units::Length { value: i32 }
+    FunctionDeclaration pub geometry::units::zero() -> geometry::units::Length
+      Block
+        ImplicitReturn
+          Struct geometry::units::Length
+            Field value
+              Literal Int32(0)
+    FunctionDeclaration main() -> i32
+      Block
+        ImplicitReturn
+          FieldAccess value
+            FunctionInvokation geometry::shapes::side
+              Variant geometry::shapes::Shape::Square
+                Field 0
+                  FunctionInvokation geometry::units::zero
+"
+    );
+    assert!(Preprocessor::new().try_run(ast).is_ok());
+}
+
+fn diagnostic(root: &str, modules: &[(&str, &str)]) -> String {
+    let modules = modules
+        .iter()
+        .map(|(name, code)| module(name, code))
+        .collect();
+
+    Linker::new()
+        .run(module("main", root), modules)
+        .and_then(|ast| Preprocessor::new().try_run(ast))
+        .map(|_| ())
+        .unwrap_err()
+        .message()
+        .to_string()
+}
+
+#[test]
+pub fn link_errors_are_reported() {
+    assert_eq!(diagnostic("import math;", &[]), "unresolved import `math`");
+    assert_eq!(
+        diagnostic(
+            "import a::util; import b::util;",
+            &[("a::util", "pub fun f() {}"), ("b::util", "pub fun f() {}")]
+        ),
+        "`util` is imported from both `a::util` and `b::util`"
+    );
+    assert_eq!(
+        diagnostic(
+            "import math; math::hidden();",
+            &[("math", "fun hidden() {}")]
+        ),
+        "`hidden` is private to module `math`"
+    );
+    assert_eq!(
+        diagnostic(
+            "import math; math::missing();",
+            &[("math", "pub fun f() {}")]
+        ),
+        "module `math` has no item `missing`"
+    );
+    assert_eq!(
+        diagnostic(
+            "let p: math::Point = 1;",
+            &[("math", "pub struct Point { x: i32 }")]
+        ),
+        "unresolved module `math`"
+    );
+    // imports only bring the module in scope, not its items
+    assert_eq!(
+        diagnostic(
+            "import math; square(2);",
+            &[("math", "pub fun square(x: i32) -> i32 { x * x }")]
+        ),
+        "unknown function `square`"
+    );
+    assert_eq!(
+        diagnostic("import math;", &[("math", "let x = 1;")]),
+        "module `math` can only declare functions and types"
+    );
+    assert_eq!(
+        Preprocessor::new()
+            .try_run(parse("import math;"))
+            .map(|_| ())
+            .unwrap_err()
+            .message(),
+        "unresolved import `math`"
+    );
+}
+
+#[test]
+pub fn qualified_paths_are_resolved() {
+    let shapes = module(
+        "geometry::shapes",
+        r#"
+        pub enum Shape { Circle(i32), Dot }
+
+        pub fun square(x: i32) -> i32 {
+            x * x
+        }
+        "#,
+    );
+    let main = module(
+        "main",
+        r#"
+        import geometry::shapes;
+
+        fun area(shape: shapes::Shape) -> i32 {
+            match shape {
+                shapes::Shape::Circle(radius) => 3 * shapes::square(radius),
+                geometry::shapes::Shape::Dot => 0
+            }
+        }
+
+        let area = area(shapes::Shape::Circle(2));
+        "#,
+    );
+
+    let ast = Linker::new().run(main, vec![shapes]).unwrap();
+
+    assert_eq!(
+        ast.dump_tree(),
+        "\
+AbstractSyntaxTree
+  Block
+    EnumDeclaration pub geometry::shapes::Shape { Circle(i32), Dot }
+    FunctionDeclaration pub geometry::shapes::square(x: i32) -> i32
+      Block
+        ImplicitReturn
+          BinaryExpression *
+            Identifier x
+            Identifier x
+    FunctionDeclaration area(shape: geometry::shapes::Shape) -> i32
+      Block
+        ImplicitReturn
+          Match
+            Identifier shape
+            Arm geometry::shapes::Shape::Circle(radius)
+              BinaryExpression *
+                Literal Int32(3)
+                FunctionInvokation geometry::shapes::square
+                  Identifier radius
+            Arm geometry::shapes::Shape::Dot
+              Literal Int32(0)
+    VariableDeclaration let area
+      FunctionInvokation area
+        Variant geometry::shapes::Shape::Circle
+          Field 0
+            Literal Int32(2)
+"
+    );
+    assert!(Preprocessor::new().try_run(ast).is_ok());
+}
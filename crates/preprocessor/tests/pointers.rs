@@ -0,0 +1,164 @@
+use common::dump::TreeDump;
+use lexer::lexer::Lexer;
+use parser::Parser;
+use preprocessor::Preprocessor;
+
+#[test]
+pub fn pointers_are_lowered() {
+    let code = r#"
+    enum List { Cons(i32, *List), Nil }
+
+    fun sum(list: *List) -> i32 {
+        match *list {
+            List::Cons(value, next) => value + sum(next),
+            List::Nil => 0
+        }
+    }
+
+    let x = 1;
+    let p = &x;
+    *p = *p + 2;
+    let list = alloc(List::Cons(x, alloc(List::Nil)));
+    free(list);
+    "#;
+
+    let tokens = Lexer::new().run(code);
+    let ast = Parser::new().run(&tokens);
+    let last = Preprocessor::new().run(ast);
+
+    assert_eq!(
+        last.dump_tree(),
+        "\
+LoweredAbstractSyntaxTree
+  EnumDeclaration List { Cons(i32, *List), Nil }
+  FunctionDeclaration 0(list: *List) -> i32
+    VariableDeclaration ssa 2
+      Identifier list
+    VariableDeclaration ssa 1
+      Deref List
+        Identifier 2
+    VariableDeclaration ssa 3
+      Tag
+        Identifier 1
+    Switch
+      Identifier 3
+      Case 0
+        VariableDeclaration ssa 4
+          Payload List::Cons (0)
+            Identifier 1
+        VariableDeclaration ssa value
+          Identifier 4
+        VariableDeclaration ssa 5
+          Payload List::Cons (1)
+            Identifier 1
+        VariableDeclaration ssa next
+          Identifier 5
+        VariableDeclaration ssa 8
+          Identifier next
+        VariableDeclaration ssa 7
+          FunctionInvokation 0
+            Identifier 8
+        VariableDeclaration ssa 6
+          Identifier value
+        VariableDeclaration ssa 0
+          BinaryExpression +
+            Identifier 6
+            Identifier 7
+      Case 1
+        VariableDeclaration ssa 0
+          Literal Int32(0)
+    Return
+      Identifier 0
+  VariableDeclaration ssa 0
+    Literal Int32(1)
+  VariableDeclaration stack x
+    Identifier 0
+  VariableDeclaration ssa 0
+    AddressOf
+      Identifier x
+  VariableDeclaration stack p
+    Identifier 0
+  VariableDeclaration ssa 4
+    Literal Int32(2)
+  VariableDeclaration ssa 3
+    Identifier p
+  VariableDeclaration ssa 2
+    Deref i32
+      Identifier 3
+  VariableDeclaration ssa 1
+    BinaryExpression +
+      Identifier 2
+      Identifier 4
+  VariableDeclaration ssa 5
+    Identifier p
+  BinaryExpression =
+    Deref i32
+      Identifier 5
+    Identifier 1
+  VariableDeclaration ssa 2
+    Identifier x
+  VariableDeclaration ssa 4
+    Variant List::Nil (1)
+  VariableDeclaration ssa 3
+    FunctionInvokation alloc
+      Identifier 4
+  VariableDeclaration ssa 1
+    Variant List::Cons (0)
+      Identifier 2
+      Identifier 3
+  VariableDeclaration ssa 0
+    FunctionInvokation alloc
+      Identifier 1
+  VariableDeclaration stack list
+    Identifier 0
+  VariableDeclaration ssa 1
+    Identifier list
+  VariableDeclaration ssa 0
+    FunctionInvokation free
+      Identifier 1
+"
+    );
+}
+
+fn diagnostic(code: &str) -> String {
+    let tokens = Lexer::new().run(code);
+    let ast = Parser::new().run(&tokens);
+
+    Preprocessor::new()
+        .try_run(ast)
+        .unwrap_err()
+        .message()
+        .to_string()
+}
+
+#[test]
+pub fn pointer_errors_are_reported() {
+    assert_eq!(
+        diagnostic("let p = &1;"),
+        "only variables, fields, elements and dereferences have an address"
+    );
+    assert_eq!(
+        diagnostic("let x = 1; let y = *x;"),
+        "`i32` cannot be dereferenced"
+    );
+    assert_eq!(
+        diagnostic("let x = 1; free(x);"),
+        "`free` expects a pointer, got `i32`"
+    );
+    assert_eq!(
+        diagnostic("let p = alloc();"),
+        "`alloc` expects 1 argument, got 0"
+    );
+    assert_eq!(
+        diagnostic("let p = alloc(1); free(p, p);"),
+        "`free` expects 1 argument, got 2"
+    );
+    assert_eq!(
+        diagnostic("let p = alloc(1); let q: *i64 = p;"),
+        "cannot cast *i32 to *i64"
+    );
+    assert_eq!(
+        diagnostic("let p = alloc(1); let x = p as i64;"),
+        "cannot cast *i32 to i64"
+    );
+}
@@ -0,0 +1,182 @@
+use common::dump::TreeDump;
+use lexer::lexer::Lexer;
+use parser::Parser;
+use preprocessor::Preprocessor;
+
+#[test]
+pub fn builtins_are_resolved_by_type() {
+    let code = r#"
+    let x = 3 as u8;
+    print(x);
+    println("hi");
+    println();
+    let y = min(x, 2 as u8) + abs(1 as u8);
+    assert(y > x, "y is greater");
+    "#;
+
+    let tokens = Lexer::new().run(code);
+    let ast = Parser::new().run(&tokens);
+    let last = Preprocessor::new().run(ast);
+
+    assert_eq!(
+        last.dump_tree(),
+        "\
+LoweredAbstractSyntaxTree
+  VariableDeclaration ssa 1
+    Literal Int32(3)
+  VariableDeclaration ssa 0
+    Cast i32 -> u8
+      Identifier 1
+  VariableDeclaration stack x
+    Identifier 0
+  VariableDeclaration ssa 1
+    Identifier x
+  VariableDeclaration ssa 0
+    FunctionInvokation print.u8
+      Identifier 1
+  VariableDeclaration ssa 1
+    Literal String(\"hi\")
+  VariableDeclaration ssa 0
+    FunctionInvokation println.String
+      Identifier 1
+  VariableDeclaration ssa 0
+    FunctionInvokation println
+  VariableDeclaration ssa 7
+    Literal Int32(1)
+  VariableDeclaration ssa 6
+    Cast i32 -> u8
+      Identifier 7
+  VariableDeclaration ssa 5
+    FunctionInvokation abs.u8
+      Identifier 6
+  VariableDeclaration ssa 2
+    Identifier x
+  VariableDeclaration ssa 4
+    Literal Int32(2)
+  VariableDeclaration ssa 3
+    Cast i32 -> u8
+      Identifier 4
+  VariableDeclaration ssa 1
+    FunctionInvokation min.u8
+      Identifier 2
+      Identifier 3
+  VariableDeclaration ssa 0
+    BinaryExpression +
+      Identifier 1
+      Identifier 5
+  VariableDeclaration stack y
+    Identifier 0
+  VariableDeclaration ssa 3
+    Identifier x
+  VariableDeclaration ssa 2
+    Identifier y
+  VariableDeclaration ssa 1
+    BinaryExpression >
+      Identifier 2
+      Identifier 3
+  VariableDeclaration ssa 4
+    Literal String(\"y is greater\")
+  VariableDeclaration ssa 0
+    FunctionInvokation assert
+      Identifier 1
+      Identifier 4
+"
+    );
+}
+
+#[test]
+pub fn declared_functions_shadow_builtins() {
+    let code = r#"
+    fun max(a: i32, b: i32) -> i32 {
+        a
+    }
+
+    let x = max(1, 2);
+    "#;
+
+    let tokens = Lexer::new().run(code);
+    let ast = Parser::new().run(&tokens);
+    let last = Preprocessor::new().run(ast);
+
+    assert_eq!(
+        last.dump_tree(),
+        "\
+LoweredAbstractSyntaxTree
+  FunctionDeclaration 0(a: i32, b: i32) -> i32
+    VariableDeclaration ssa 0
+      Identifier a
+    Return
+      Identifier 0
+  VariableDeclaration ssa 1
+    Literal Int32(1)
+  VariableDeclaration ssa 2
+    Literal Int32(2)
+  VariableDeclaration ssa 0
+    FunctionInvokation 0
+      Identifier 1
+      Identifier 2
+  VariableDeclaration stack x
+    Identifier 0
+"
+    );
+}
+
+fn diagnostic(code: &str) -> String {
+    let tokens = Lexer::new().run(code);
+    let ast = Parser::new().run(&tokens);
+
+    Preprocessor::new()
+        .try_run(ast)
+        .unwrap_err()
+        .message()
+        .to_string()
+}
+
+#[test]
+pub fn builtin_errors_are_reported() {
+    assert_eq!(
+        diagnostic("print([1, 2]);"),
+        "`print` cannot print `[i32; 2]`"
+    );
+    assert_eq!(
+        diagnostic("println(1, 2);"),
+        "`println` expects at most 1 argument, got 2"
+    );
+    assert_eq!(
+        diagnostic("abort(1);"),
+        "`abort` expects no arguments, got 1"
+    );
+    assert_eq!(
+        diagnostic(r#"assert(1, "one");"#),
+        "`assert` expects a `bool` condition, got `i32`"
+    );
+    assert_eq!(
+        diagnostic("assert(true, 1);"),
+        "`assert` expects a `String` message, got `i32`"
+    );
+    assert_eq!(
+        diagnostic("exit(true);"),
+        "`exit` expects an integer exit code, got `bool`"
+    );
+    assert_eq!(
+        diagnostic("let x = abs(true);"),
+        "`abs` expects a number, got `bool`"
+    );
+    assert_eq!(
+        diagnostic("let x = min(1, 2 as i64);"),
+        "`min` expects two arguments of the same type, got `i32` and `i64`"
+    );
+    assert_eq!(
+        diagnostic("let x = max(true, false);"),
+        "`max` expects numbers, got `bool`"
+    );
+    assert_eq!(
+        diagnostic("let x = sqrt(2 as f64, 3);"),
+        "`sqrt` expects 1 argument, got 2"
+    );
+    assert_eq!(
+        diagnostic("let x = pow(2, 3);"),
+        "`pow` expects floats, got `i32`"
+    );
+    assert_eq!(diagnostic("foo(1);"), "unknown function `foo`");
+}
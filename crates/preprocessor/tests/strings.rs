@@ -0,0 +1,194 @@
+use std::rc::Rc;
+
+use common::types::Type;
+use lexer::lexer::Lexer;
+use parser::{
+    ast::{
+        literal::Literal,
+        operation::{AlgebraicOperation, LogicalOperation},
+    },
+    Parser,
+};
+use preprocessor::{
+    last::{
+        declaration::{Declaration, VariableAllocation},
+        expression::Expression,
+        operation::Operation,
+        unit::LASTUnit,
+    },
+    Preprocessor,
+};
+
+#[test]
+pub fn string_operations_work() {
+    let code = r#"
+    const greeting = "hello, " + name;
+    const same = len(greeting) == 12;
+    "#;
+
+    let tokens = Lexer::new().run(code);
+    let ast = Parser::new().run(&tokens);
+
+    let last = Preprocessor::new().run(ast);
+    let root = last.root();
+
+    assert_eq!(
+        root[0].as_ref(),
+        &LASTUnit::Declaration(Declaration::VariableDeclaration {
+            allocation: VariableAllocation::SSA,
+            identifier: "2".to_string(),
+            ty: None,
+            expression: Rc::new(Expression::Identifier("name".to_string()))
+        })
+    );
+    assert_eq!(
+        root[1].as_ref(),
+        &LASTUnit::Declaration(Declaration::VariableDeclaration {
+            allocation: VariableAllocation::SSA,
+            identifier: "1".to_string(),
+            ty: None,
+            expression: Rc::new(Expression::Literal(Literal::String("hello, ".to_string())))
+        })
+    );
+    assert_eq!(
+        root[2].as_ref(),
+        &LASTUnit::Declaration(Declaration::VariableDeclaration {
+            allocation: VariableAllocation::SSA,
+            identifier: "0".to_string(),
+            ty: None,
+            expression: Rc::new(Expression::BinaryExpression {
+                left: Rc::new(Expression::Identifier("1".to_string())),
+                right: Rc::new(Expression::Identifier("2".to_string())),
+                operation: Operation::Algebraic(AlgebraicOperation::Addition),
+                operands: Some(Type::String)
+            })
+        })
+    );
+
+    // builtins keep their names through mangling
+    assert_eq!(
+        root[6].as_ref(),
+        &LASTUnit::Declaration(Declaration::VariableDeclaration {
+            allocation: VariableAllocation::SSA,
+            identifier: "1".to_string(),
+            ty: None,
+            expression: Rc::new(Expression::FunctionInvokation {
+                name: "len".to_string(),
+                args: vec![Expression::Identifier("2".to_string())]
+            })
+        })
+    );
+    // the literal takes the type of the length
+    assert_eq!(
+        root[7].as_ref(),
+        &LASTUnit::Declaration(Declaration::VariableDeclaration {
+            allocation: VariableAllocation::SSA,
+            identifier: "4".to_string(),
+            ty: None,
+            expression: Rc::new(Expression::Cast {
+                expression: Rc::new(Expression::Identifier("3".to_string())),
+                from: Some(Type::Int32),
+                ty: Type::Int64
+            })
+        })
+    );
+    assert_eq!(
+        root[8].as_ref(),
+        &LASTUnit::Declaration(Declaration::VariableDeclaration {
+            allocation: VariableAllocation::SSA,
+            identifier: "0".to_string(),
+            ty: None,
+            expression: Rc::new(Expression::BinaryExpression {
+                left: Rc::new(Expression::Identifier("1".to_string())),
+                right: Rc::new(Expression::Identifier("4".to_string())),
+                operation: Operation::Logical(LogicalOperation::Equal),
+                operands: Some(Type::Int64)
+            })
+        })
+    );
+}
+
+#[test]
+pub fn string_indexing_works() {
+    let code = r#"
+    const c = s[i + 1];
+    "#;
+
+    let tokens = Lexer::new().run(code);
+    let ast = Parser::new().run(&tokens);
+
+    let last = Preprocessor::new().run(ast);
+    let root = last.root();
+
+    assert_eq!(
+        root[0].as_ref(),
+        &LASTUnit::Declaration(Declaration::VariableDeclaration {
+            allocation: VariableAllocation::SSA,
+            identifier: "1".to_string(),
+            ty: None,
+            expression: Rc::new(Expression::Identifier("s".to_string()))
+        })
+    );
+    assert_eq!(
+        root[4].as_ref(),
+        &LASTUnit::Declaration(Declaration::VariableDeclaration {
+            allocation: VariableAllocation::SSA,
+            identifier: "0".to_string(),
+            ty: None,
+            expression: Rc::new(Expression::Index {
+                target: Rc::new(Expression::Identifier("1".to_string())),
+                index: Rc::new(Expression::Identifier("2".to_string())),
+                element: None
+            })
+        })
+    );
+}
+
+#[test]
+pub fn char_casts_work() {
+    let code = r#"
+    const code = s[0] as u32 + 'a' as u32;
+    "#;
+
+    let tokens = Lexer::new().run(code);
+    let ast = Parser::new().run(&tokens);
+
+    let last = Preprocessor::new().run(ast);
+    let root = last.root();
+
+    assert_eq!(
+        root[0].as_ref(),
+        &LASTUnit::Declaration(Declaration::VariableDeclaration {
+            allocation: VariableAllocation::SSA,
+            identifier: "6".to_string(),
+            ty: None,
+            expression: Rc::new(Expression::Literal(Literal::Char('a')))
+        })
+    );
+    assert_eq!(
+        root[1].as_ref(),
+        &LASTUnit::Declaration(Declaration::VariableDeclaration {
+            allocation: VariableAllocation::SSA,
+            identifier: "5".to_string(),
+            ty: None,
+            expression: Rc::new(Expression::Cast {
+                expression: Rc::new(Expression::Identifier("6".to_string())),
+                from: Some(Type::Char),
+                ty: Type::UInt32
+            })
+        })
+    );
+    assert_eq!(
+        root[5].as_ref(),
+        &LASTUnit::Declaration(Declaration::VariableDeclaration {
+            allocation: VariableAllocation::SSA,
+            identifier: "1".to_string(),
+            ty: None,
+            expression: Rc::new(Expression::Cast {
+                expression: Rc::new(Expression::Identifier("2".to_string())),
+                from: None,
+                ty: Type::UInt32
+            })
+        })
+    );
+}
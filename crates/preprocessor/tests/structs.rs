@@ -0,0 +1,103 @@
+use common::dump::TreeDump;
+use lexer::lexer::Lexer;
+use parser::Parser;
+use preprocessor::Preprocessor;
+
+#[test]
+pub fn structs_are_lowered() {
+    let code = r#"
+    struct Point { x: i64, y: i32 }
+
+    fun main() -> i32 {
+        let p = Point { y: 1, x: 2 };
+        p.x = 3;
+        return p.y;
+    }
+    "#;
+
+    let tokens = Lexer::new().run(code);
+    let ast = Parser::new().run(&tokens);
+    let last = Preprocessor::new().run(ast);
+
+    assert_eq!(
+        last.dump_tree(),
+        "\
+LoweredAbstractSyntaxTree
+  TypeDeclaration Point { x: i64, y: i32 }
+  FunctionDeclaration 0() -> i32
+    VariableDeclaration ssa 1
+      Literal Int32(1)
+    VariableDeclaration ssa 2
+      Literal Int32(2)
+    VariableDeclaration ssa 3
+      Cast i32 -> i64
+        Identifier 2
+    VariableDeclaration ssa 0
+      Struct Point
+        Identifier 3
+        Identifier 1
+    VariableDeclaration stack p
+      Identifier 0
+    VariableDeclaration ssa 5
+      Literal Int32(3)
+    VariableDeclaration ssa 6
+      Cast i32 -> i64
+        Identifier 5
+    BinaryExpression =
+      FieldAccess x (0)
+        Identifier p
+      Identifier 6
+    VariableDeclaration ssa 8
+      Identifier p
+    VariableDeclaration ssa 7
+      FieldAccess y (1)
+        Identifier 8
+    Return
+      Identifier 7
+"
+    );
+}
+
+fn diagnostic(code: &str) -> String {
+    let tokens = Lexer::new().run(code);
+    let ast = Parser::new().run(&tokens);
+
+    Preprocessor::new()
+        .try_run(ast)
+        .unwrap_err()
+        .message()
+        .to_string()
+}
+
+#[test]
+pub fn struct_errors_are_reported() {
+    let point = "struct Point { x: i32, y: i32 }";
+
+    assert_eq!(
+        diagnostic(&format!("{point} let p = Point {{ x: 1 }};")),
+        "missing field `y` in `Point`"
+    );
+    assert_eq!(
+        diagnostic(&format!("{point} let p = Point {{ x: 1, y: 2, z: 3 }};")),
+        "struct `Point` has no field `z`"
+    );
+    assert_eq!(
+        diagnostic(&format!(
+            "{point} let p = Point {{ x: 1, y: 2 }}; let z = p.z;"
+        )),
+        "struct `Point` has no field `z`"
+    );
+    assert_eq!(
+        diagnostic(&format!("{point} let p = Point {{ x: \"a\", y: 2 }};")),
+        "cannot cast String to i32"
+    );
+    assert_eq!(
+        diagnostic("let a = 1; let b = a.x;"),
+        "`i32` has no field `x`"
+    );
+    assert_eq!(diagnostic("fun f(p: Point) {}"), "unknown type `Point`");
+    assert_eq!(
+        diagnostic(&format!("{point} {point}")),
+        "struct `Point` is declared twice"
+    );
+}
@@ -0,0 +1,169 @@
+use common::dump::TreeDump;
+use lexer::lexer::Lexer;
+use parser::Parser;
+use preprocessor::Preprocessor;
+
+#[test]
+pub fn tuples_are_lowered() {
+    let code = r#"
+    fun divide(a: i32, b: i32) -> (i32, bool) {
+        match b {
+            0 => (0, false),
+            _ => (a / b, true)
+        }
+    }
+
+    let ((q, ok), _) = (divide(4, 2), 1);
+    let x = match divide(1, 0) { (_, false) => 0, (q, _) => q };
+    "#;
+
+    let tokens = Lexer::new().run(code);
+    let ast = Parser::new().run(&tokens);
+    let last = Preprocessor::new().run(ast);
+
+    assert_eq!(
+        last.dump_tree(),
+        "\
+LoweredAbstractSyntaxTree
+  FunctionDeclaration 0(a: i32, b: i32) -> (i32, bool)
+    VariableDeclaration ssa 1
+      Identifier b
+    VariableDeclaration ssa 2
+      Literal Int32(0)
+    VariableDeclaration ssa 3
+      BinaryExpression ==
+        Identifier 1
+        Identifier 2
+    ControlFlow
+      Condition
+        Identifier 3
+      Then
+        VariableDeclaration ssa 4
+          Literal Int32(0)
+        VariableDeclaration ssa 5
+          Literal Bool(false)
+        VariableDeclaration ssa 0
+          Tuple
+            Identifier 4
+            Identifier 5
+      Else
+        VariableDeclaration ssa 8
+          Identifier b
+        VariableDeclaration ssa 7
+          Identifier a
+        VariableDeclaration ssa 6
+          BinaryExpression /
+            Identifier 7
+            Identifier 8
+        VariableDeclaration ssa 9
+          Literal Bool(true)
+        VariableDeclaration ssa 0
+          Tuple
+            Identifier 6
+            Identifier 9
+    Return
+      Identifier 0
+  VariableDeclaration ssa 2
+    Literal Int32(4)
+  VariableDeclaration ssa 3
+    Literal Int32(2)
+  VariableDeclaration ssa 1
+    FunctionInvokation 0
+      Identifier 2
+      Identifier 3
+  VariableDeclaration ssa 4
+    Literal Int32(1)
+  VariableDeclaration ssa 0
+    Tuple
+      Identifier 1
+      Identifier 4
+  VariableDeclaration ssa 5
+    FieldAccess 0 (0)
+      Identifier 0
+  VariableDeclaration ssa 6
+    FieldAccess 0 (0)
+      Identifier 5
+  VariableDeclaration stack q
+    Identifier 6
+  VariableDeclaration ssa 7
+    FieldAccess 1 (1)
+      Identifier 5
+  VariableDeclaration stack ok
+    Identifier 7
+  VariableDeclaration ssa 3
+    Literal Int32(1)
+  VariableDeclaration ssa 4
+    Literal Int32(0)
+  VariableDeclaration ssa 2
+    FunctionInvokation 0
+      Identifier 3
+      Identifier 4
+  VariableDeclaration ssa 5
+    FieldAccess 1 (1)
+      Identifier 2
+  VariableDeclaration ssa 6
+    Literal Bool(false)
+  VariableDeclaration ssa 7
+    BinaryExpression ==
+      Identifier 5
+      Identifier 6
+  ControlFlow
+    Condition
+      Identifier 7
+    Then
+      VariableDeclaration ssa 1
+        Literal Int32(0)
+    Else
+      VariableDeclaration ssa 8
+        FieldAccess 0 (0)
+          Identifier 2
+      VariableDeclaration ssa q0
+        Identifier 8
+      VariableDeclaration ssa 1
+        Identifier q0
+  VariableDeclaration ssa 0
+    Identifier 1
+  VariableDeclaration stack x
+    Identifier 0
+"
+    );
+}
+
+fn diagnostic(code: &str) -> String {
+    let tokens = Lexer::new().run(code);
+    let ast = Parser::new().run(&tokens);
+
+    Preprocessor::new()
+        .try_run(ast)
+        .unwrap_err()
+        .message()
+        .to_string()
+}
+
+#[test]
+pub fn tuple_errors_are_reported() {
+    assert_eq!(
+        diagnostic("let t = (1, true); let a = t.2;"),
+        "tuple `(i32, bool)` has no field `2`"
+    );
+    assert_eq!(
+        diagnostic("let (a, b, c) = (1, true);"),
+        "tuple pattern has 3 elements, `(i32, bool)` has 2"
+    );
+    assert_eq!(
+        diagnostic("let (a, b) = 1;"),
+        "tuple pattern cannot match a value of type `i32`"
+    );
+    assert_eq!(
+        diagnostic("let (a, true) = (1, true);"),
+        "pattern `(a, Bool(true))` can fail to match, use `match` instead"
+    );
+    assert_eq!(
+        diagnostic("let t: (i64, bool) = (1, true);"),
+        "cannot cast (i32, bool) to (i64, bool)"
+    );
+    assert_eq!(
+        diagnostic("let a = match (1, 2) { (1, b) => b };"),
+        "non-exhaustive match, add a `_` arm"
+    );
+}
@@ -0,0 +1,236 @@
+use common::types::Type;
+use lexer::lexer::Lexer;
+use parser::Parser;
+use preprocessor::{typecheck::is_castable, Preprocessor};
+
+#[test]
+pub fn numeric_casts_are_accepted() {
+    let code = r#"
+    fun main() -> i32 {
+      let a = 255 as u8;
+      let b = -1 as i8 as u32;
+      let c = 3 as f32 as f64 as i64;
+      let d = 'a' as u32 as char;
+      let e = 1 as bool;
+      return a as i32;
+    }
+    "#;
+
+    let tokens = Lexer::new().run(code);
+    let ast = Parser::new().run(&tokens);
+
+    assert!(Preprocessor::new().try_run(ast).is_ok());
+}
+
+#[test]
+pub fn nonsense_casts_are_rejected() {
+    let code = "let a = 1;\nlet b = \"a\" as i32;";
+
+    let (tokens, spans) = Lexer::new().try_run_spanned(code).unwrap();
+    let ast = Parser::new().run_spanned(&tokens, &spans);
+
+    let diagnostic = Preprocessor::new().try_run(ast).unwrap_err();
+    assert_eq!(diagnostic.message(), "cannot cast String to i32");
+    assert_eq!(diagnostic.span().unwrap().line_col(code).0, 2);
+}
+
+#[test]
+pub fn declared_types_are_checked() {
+    let code = r#"
+    let a = 1 as f64;
+    let s: String = a;
+    "#;
+
+    let tokens = Lexer::new().run(code);
+    let ast = Parser::new().run(&tokens);
+
+    let diagnostic = Preprocessor::new().try_run(ast).unwrap_err();
+    assert_eq!(diagnostic.message(), "cannot cast f64 to String");
+}
+
+#[test]
+pub fn out_of_range_literals_are_rejected() {
+    for (code, message) in [
+        ("let a: u8 = 300;", "literal `300` is out of range for `u8`"),
+        (
+            "let a = 300 as u8;",
+            "literal `300` is out of range for `u8`",
+        ),
+        (
+            "let a = -1 as u32;",
+            "literal `-1` is out of range for `u32`",
+        ),
+        (
+            "let a = 1;\na = -129 as i8;",
+            "literal `-129` is out of range for `i8`",
+        ),
+        (
+            "let a = 1;\nlet b = a + 3000000000;",
+            "literal `3000000000` is out of range for `i32`",
+        ),
+        (
+            "let a = [1 as u8, 256];",
+            "literal `256` is out of range for `u8`",
+        ),
+    ] {
+        let tokens = Lexer::new().run(code);
+        let ast = Parser::new().run(&tokens);
+
+        let diagnostic = Preprocessor::new().try_run(ast).unwrap_err();
+        assert_eq!(diagnostic.message(), message);
+    }
+}
+
+#[test]
+pub fn large_integer_literals_are_accepted() {
+    let code = r#"
+    let a: i64 = 3000000000;
+    let b: u32 = 4000000000;
+    let c = 1 as u64 + 18446744073709551615;
+    let d = a - -9223372036854775808;
+    "#;
+
+    let tokens = Lexer::new().run(code);
+    let ast = Parser::new().run(&tokens);
+
+    assert!(Preprocessor::new().try_run(ast).is_ok());
+}
+
+#[test]
+pub fn operands_are_unified() {
+    let code = r#"
+    fun main() -> i32 {
+      let x = 1 as i64;
+      let y = x + 1;
+      let z = 2 as f32 * 0.5;
+      let w = 2 as f32 * (3 as f32);
+      let s = "a" + "b";
+      let less = x < 2 as i32;
+      return 0;
+    }
+    "#;
+
+    let tokens = Lexer::new().run(code);
+    let ast = Parser::new().run(&tokens);
+
+    assert!(Preprocessor::new().try_run(ast).is_ok());
+}
+
+#[test]
+pub fn mismatched_operands_are_rejected() {
+    let diagnostic = |code: &str| {
+        let ast = Parser::new().run(&Lexer::new().run(code));
+        Preprocessor::new()
+            .try_run(ast)
+            .unwrap_err()
+            .message()
+            .to_string()
+    };
+
+    assert_eq!(
+        diagnostic("let a = 1; let b = a + (2 as u8);"),
+        "cannot apply `+` to `i32` and `u8`"
+    );
+    assert_eq!(
+        diagnostic("let a = 1 as f64; let b = a + 1;"),
+        "cannot apply `+` to `f64` and `i32`"
+    );
+    assert_eq!(
+        diagnostic("let a = \"a\" - \"b\";"),
+        "cannot apply `-` to `String` and `String`"
+    );
+    assert_eq!(
+        diagnostic("let a = true + false;"),
+        "cannot apply `+` to `bool` and `bool`"
+    );
+}
+
+#[test]
+pub fn only_let_variables_are_assigned_to() {
+    let diagnostic = |code: &str| {
+        let (tokens, spans) = Lexer::new().try_run_spanned(code).unwrap();
+        let ast = Parser::new().run_spanned(&tokens, &spans);
+        let diagnostic = Preprocessor::new().try_run(ast).unwrap_err();
+
+        (
+            diagnostic.message().to_string(),
+            diagnostic.span().unwrap().line_col(code).0,
+        )
+    };
+
+    assert_eq!(
+        diagnostic("struct P { x: i32 }\nfun f(p: P) {\np.x = 1;\n}"),
+        (
+            "cannot assign to `p`, only `let` variables can be assigned to".to_string(),
+            3
+        )
+    );
+    assert_eq!(
+        diagnostic("const c = 1;\nc = 2;"),
+        (
+            "cannot assign to `c`, only `let` variables can be assigned to".to_string(),
+            2
+        )
+    );
+
+    let code = r#"
+    fun f(p: i32) -> i32 {
+      let q = p;
+      q = q + 1;
+      let (a, b) = (q, 2);
+      a = b;
+      return a;
+    }
+    "#;
+
+    let ast = Parser::new().run(&Lexer::new().run(code));
+    assert!(Preprocessor::new().try_run(ast).is_ok());
+}
+
+#[test]
+pub fn castability_works() {
+    assert!(is_castable(&Type::Int32, &Type::Float64));
+    assert!(is_castable(&Type::Float32, &Type::UInt8));
+    assert!(is_castable(&Type::Char, &Type::UInt32));
+    assert!(is_castable(&Type::String, &Type::String));
+
+    assert!(!is_castable(&Type::Float64, &Type::Bool));
+    assert!(!is_castable(&Type::Char, &Type::Float32));
+    assert!(!is_castable(&Type::Int32, &Type::String));
+    assert!(!is_castable(&Type::Void, &Type::Int32));
+}
+
+#[test]
+pub fn entry_point_signature_is_checked() {
+    for (code, message) in [
+        (
+            "fun main(argc: i32) -> i32 { return argc; }",
+            "`main` takes no parameters",
+        ),
+        (
+            "fun main() -> f64 { return 1 as f64; }",
+            "`main` must return an integer, a `bool` or nothing, not `f64`",
+        ),
+        (
+            "fun main() -> String { return \"a\"; }",
+            "`main` must return an integer, a `bool` or nothing, not `String`",
+        ),
+    ] {
+        let tokens = Lexer::new().run(code);
+        let ast = Parser::new().run(&tokens);
+
+        let diagnostic = Preprocessor::new().try_run(ast).unwrap_err();
+        assert_eq!(diagnostic.message(), message);
+    }
+
+    for code in [
+        "fun main() { let a = 1; }",
+        "fun main() -> bool { return true; }",
+        "fun main() -> u8 { return 1 as u8; }",
+    ] {
+        let tokens = Lexer::new().run(code);
+        let ast = Parser::new().run(&tokens);
+
+        assert!(Preprocessor::new().try_run(ast).is_ok());
+    }
+}
@@ -1,5 +1,7 @@
 use std::rc::Rc;
 
+use common::types::Type;
+
 use lexer::lexer::Lexer;
 use parser::{
     ast::{literal::Literal, operation::AlgebraicOperation},
@@ -32,6 +34,7 @@ pub fn variable_declaration_works() {
         &LASTUnit::Declaration(Declaration::VariableDeclaration {
             allocation: VariableAllocation::SSA,
             identifier: "4".to_string(),
+            ty: None,
             expression: Rc::new(Expression::Literal(Literal::Int32(3)))
         })
     );
@@ -41,6 +44,7 @@ pub fn variable_declaration_works() {
         &LASTUnit::Declaration(Declaration::VariableDeclaration {
             allocation: VariableAllocation::SSA,
             identifier: "3".to_string(),
+            ty: None,
             expression: Rc::new(Expression::Literal(Literal::Int32(2)))
         })
     );
@@ -50,10 +54,12 @@ pub fn variable_declaration_works() {
         &LASTUnit::Declaration(Declaration::VariableDeclaration {
             allocation: VariableAllocation::SSA,
             identifier: "2".to_string(),
+            ty: None,
             expression: Rc::new(Expression::BinaryExpression {
                 left: Rc::new(Expression::Identifier("3".to_string())),
                 right: Rc::new(Expression::Identifier("4".to_string())),
-                operation: Operation::Algebraic(AlgebraicOperation::Addition)
+                operation: Operation::Algebraic(AlgebraicOperation::Addition),
+                operands: Some(Type::Int32)
             })
         })
     );
@@ -63,6 +69,7 @@ pub fn variable_declaration_works() {
         &LASTUnit::Declaration(Declaration::VariableDeclaration {
             allocation: VariableAllocation::SSA,
             identifier: "1".to_string(),
+            ty: None,
             expression: Rc::new(Expression::Literal(Literal::Int32(1)))
         })
     );
@@ -72,10 +79,12 @@ pub fn variable_declaration_works() {
         &LASTUnit::Declaration(Declaration::VariableDeclaration {
             allocation: VariableAllocation::SSA,
             identifier: "0".to_string(),
+            ty: None,
             expression: Rc::new(Expression::BinaryExpression {
                 left: Rc::new(Expression::Identifier("1".to_string())),
                 right: Rc::new(Expression::Identifier("2".to_string())),
-                operation: Operation::Algebraic(AlgebraicOperation::Addition)
+                operation: Operation::Algebraic(AlgebraicOperation::Addition),
+                operands: Some(Type::Int32)
             })
         })
     );
@@ -85,7 +94,45 @@ pub fn variable_declaration_works() {
         &LASTUnit::Declaration(Declaration::VariableDeclaration {
             allocation: VariableAllocation::Stack,
             identifier: "a".to_string(),
+            ty: Some(Type::Int32),
             expression: Rc::new(Expression::Identifier("0".to_string()))
         })
     );
 }
+
+#[test]
+pub fn typed_variable_declaration_works() {
+    let code = r#"
+  let a: i64 = 1;
+    "#;
+
+    let tokens = Lexer::new().run(code);
+    let ast = Parser::new().run(&tokens);
+
+    let last = Preprocessor::new().run(ast);
+    let root = last.root();
+
+    assert_eq!(
+        root[0].as_ref(),
+        &LASTUnit::Declaration(Declaration::VariableDeclaration {
+            allocation: VariableAllocation::SSA,
+            identifier: "0".to_string(),
+            ty: None,
+            expression: Rc::new(Expression::Literal(Literal::Int32(1)))
+        })
+    );
+
+    assert_eq!(
+        root[1].as_ref(),
+        &LASTUnit::Declaration(Declaration::VariableDeclaration {
+            allocation: VariableAllocation::Stack,
+            identifier: "a".to_string(),
+            ty: Some(Type::Int64),
+            expression: Rc::new(Expression::Cast {
+                expression: Rc::new(Expression::Identifier("0".to_string())),
+                from: Some(Type::Int32),
+                ty: Type::Int64,
+            })
+        })
+    );
+}
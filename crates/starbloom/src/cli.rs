@@ -0,0 +1,209 @@
+use std::path::PathBuf;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+
+pub const EXIT_SUCCESS: i32 = 0;
+pub const EXIT_COMPILE_ERROR: i32 = 1;
+pub const EXIT_USAGE_ERROR: i32 = 2;
+pub const EXIT_IO_ERROR: i32 = 3;
+pub const EXIT_BACKEND_ERROR: i32 = 4;
+
+const EXIT_CODES: &str = "\
+Exit codes:
+  0  success, `run` exits with the program's exit code instead
+  1  the source contains errors, or `fmt --check` found unformatted files
+  2  invalid command-line usage
+  3  an input could not be read or an output could not be written
+  4  code generation failed or is not supported for the requested output";
+
+#[derive(Parser, Debug)]
+#[command(name = "starbloom", version, about = "The bloom compiler", after_help = EXIT_CODES)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Compile a source file, or the project in the current directory
+    Build(BuildArgs),
+    /// Compile a source file in memory and run it
+    Run(RunArgs),
+    /// Check a source file for errors without producing output
+    Check(CheckArgs),
+    /// Format source files in place
+    Fmt(FmtArgs),
+    /// Print an intermediate representation of a source file
+    Emit(EmitArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct InputArgs {
+    /// Source file, `-` reads from stdin
+    pub input: PathBuf,
+}
+
+#[derive(Args, Debug, Default, Clone)]
+pub struct CodegenArgs {
+    /// Optimization level, defaults to 0 or the one of the project profile
+    #[arg(
+        short = 'O',
+        value_name = "LEVEL",
+        value_enum,
+        hide_possible_values = true
+    )]
+    pub opt_level: Option<OptLevel>,
+
+    /// Target triple, defaults to the host
+    #[arg(long)]
+    pub target: Option<String>,
+
+    /// Emit DWARF debug information
+    #[arg(short = 'g')]
+    pub debug_info: bool,
+
+    /// LLVM pass pipeline to run instead of the -O one, e.g. `mem2reg,instcombine`
+    #[arg(long, value_name = "PIPELINE")]
+    pub passes: Option<String>,
+
+    /// Print how long each compiler stage and each LLVM pass took to stderr
+    #[arg(long)]
+    pub time_passes: bool,
+
+    /// Skip running the LLVM verifier on the generated code
+    #[arg(long)]
+    pub no_verify: bool,
+}
+
+impl CodegenArgs {
+    pub fn opt_level(&self) -> OptLevel {
+        self.opt_level.unwrap_or_default()
+    }
+}
+
+#[derive(Args, Debug)]
+pub struct BuildArgs {
+    /// Source file, `-` reads from stdin, without one the project is built from its bloom.toml
+    pub input: Option<PathBuf>,
+
+    /// Output path, `-` writes to stdout, defaults to the target directory of a project
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+
+    /// Kind of output to produce, defaults to an executable or the output of the project
+    #[arg(long, value_enum)]
+    pub emit: Option<EmitKind>,
+
+    /// Build the project with its release profile
+    #[arg(long)]
+    pub release: bool,
+
+    /// Format of the tokens, ast and last dumps
+    #[arg(long, value_enum, default_value_t = DumpFormat::Tree)]
+    pub format: DumpFormat,
+
+    #[command(flatten)]
+    pub codegen: CodegenArgs,
+}
+
+#[derive(Args, Debug)]
+pub struct RunArgs {
+    #[command(flatten)]
+    pub input: InputArgs,
+
+    #[command(flatten)]
+    pub codegen: CodegenArgs,
+
+    /// Arguments passed to the program
+    #[arg(last = true)]
+    pub args: Vec<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct CheckArgs {
+    #[command(flatten)]
+    pub input: InputArgs,
+}
+
+#[derive(Args, Debug)]
+pub struct FmtArgs {
+    /// Only report unformatted files instead of rewriting them
+    #[arg(long)]
+    pub check: bool,
+
+    /// Files or directories to format, defaults to the current directory
+    pub paths: Vec<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct EmitArgs {
+    /// Kind of output to produce
+    #[arg(value_enum)]
+    pub kind: EmitKind,
+
+    #[command(flatten)]
+    pub input: InputArgs,
+
+    /// Output path, defaults to stdout
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+
+    /// Format of the tokens, ast and last dumps
+    #[arg(long, value_enum, default_value_t = DumpFormat::Tree)]
+    pub format: DumpFormat,
+
+    #[command(flatten)]
+    pub codegen: CodegenArgs,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmitKind {
+    Tokens,
+    Ast,
+    Last,
+    LlvmIr,
+    LlvmBc,
+    Asm,
+    Obj,
+    Exe,
+    /// Prototypes of the `pub extern` functions
+    CHeader,
+}
+
+impl EmitKind {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Tokens => "tokens",
+            Self::Ast => "ast",
+            Self::Last => "last",
+            Self::LlvmIr => "ll",
+            Self::LlvmBc => "bc",
+            Self::Asm => "s",
+            Self::Obj => "o",
+            Self::Exe => "",
+            Self::CHeader => "h",
+        }
+    }
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DumpFormat {
+    /// Indented tree text
+    Tree,
+    /// JSON, requires the `serde` feature
+    #[cfg(feature = "serde")]
+    Json,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OptLevel {
+    #[default]
+    #[value(name = "0")]
+    O0,
+    #[value(name = "1")]
+    O1,
+    #[value(name = "2")]
+    O2,
+    #[value(name = "3")]
+    O3,
+}
@@ -0,0 +1,612 @@
+use std::{
+    collections::HashSet,
+    env, fmt, fs,
+    io::{self, Read, Write},
+    path::{Path, PathBuf},
+    process, ptr,
+    sync::Once,
+};
+
+use common::{
+    diagnostic::Diagnostic,
+    dump::{TreeDump, TreeWriter},
+    span::Span,
+};
+use inkwell::{
+    context::Context,
+    llvm_sys::support::LLVMParseCommandLineOptions,
+    module::Module,
+    passes::PassBuilderOptions,
+    targets::{
+        CodeModel, FileType, InitializationConfig, RelocMode, Target, TargetMachine, TargetTriple,
+    },
+    OptimizationLevel,
+};
+use lexer::lexer::{token::Token, Lexer};
+use parser::{ast::AbstractSyntaxTree, Parser};
+use preprocessor::{
+    last::LoweredAbstractSyntaxTree,
+    modules::{self, Linker, SourceModule},
+    Preprocessor,
+};
+
+use crate::{
+    cli::{
+        CodegenArgs, DumpFormat, EmitKind, OptLevel, EXIT_BACKEND_ERROR, EXIT_COMPILE_ERROR,
+        EXIT_IO_ERROR,
+    },
+    timings::Timings,
+};
+
+const STDIO_PATH: &str = "-";
+pub const SOURCE_EXTENSION: &str = "bloom";
+
+#[derive(Debug)]
+pub enum DriverError {
+    Compile(String),
+    Io(String),
+    Backend(String),
+}
+
+impl DriverError {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Self::Compile(_) => EXIT_COMPILE_ERROR,
+            Self::Io(_) => EXIT_IO_ERROR,
+            Self::Backend(_) => EXIT_BACKEND_ERROR,
+        }
+    }
+}
+
+impl fmt::Display for DriverError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Compile(message) => write!(f, "{message}"),
+            Self::Io(message) | Self::Backend(message) => write!(f, "error: {message}"),
+        }
+    }
+}
+
+pub struct Source {
+    pub filename: String,
+    pub module_name: String,
+    pub contents: String,
+    // the directories imports are looked up in, in order
+    pub roots: Vec<PathBuf>,
+}
+
+// a file imported by the source, its spans come after the ones of the files before it
+struct ImportedFile {
+    filename: String,
+    contents: String,
+    offset: usize,
+}
+
+impl Source {
+    pub fn read(path: &Path) -> Result<Self, DriverError> {
+        if path == Path::new(STDIO_PATH) {
+            let mut contents = String::new();
+            io::stdin()
+                .read_to_string(&mut contents)
+                .map_err(|error| DriverError::Io(format!("cannot read stdin: {error}")))?;
+
+            return Ok(Self {
+                filename: "<stdin>".to_string(),
+                module_name: "stdin".to_string(),
+                contents,
+                roots: vec![PathBuf::from(".")],
+            });
+        }
+
+        let contents = fs::read_to_string(path)
+            .map_err(|error| DriverError::Io(format!("cannot read {}: {error}", path.display())))?;
+
+        Ok(Self {
+            filename: path.to_string_lossy().to_string(),
+            module_name: path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_else(|| "main".to_string()),
+            contents,
+            roots: vec![path
+                .parent()
+                .map(Path::to_path_buf)
+                .unwrap_or_else(|| PathBuf::from("."))],
+        })
+    }
+
+    pub fn tokens(&self) -> Result<Vec<Token>, DriverError> {
+        self.spanned_tokens().map(|(tokens, _)| tokens)
+    }
+
+    pub fn spanned_tokens(&self) -> Result<(Vec<Token>, Vec<Span>), DriverError> {
+        Lexer::new()
+            .try_run_spanned(&self.contents)
+            .map_err(|diagnostic| self.error(diagnostic))
+    }
+
+    pub fn ast(&self) -> Result<AbstractSyntaxTree, DriverError> {
+        Parser::new()
+            .try_run(&self.tokens()?)
+            .map_err(|diagnostic| self.error(diagnostic))
+    }
+
+    pub fn last(&self) -> Result<LoweredAbstractSyntaxTree, DriverError> {
+        self.lower(self.ast()?, false).map(|(last, _)| last)
+    }
+
+    // like `last`, but with the source locations kept in the tree
+    pub fn spanned_last(&self) -> Result<LoweredAbstractSyntaxTree, DriverError> {
+        let (tokens, spans) = self.spanned_tokens()?;
+
+        let ast = Parser::new()
+            .try_run_spanned(&tokens, &spans)
+            .map_err(|diagnostic| self.error(diagnostic))?;
+
+        self.lower(ast, true).map(|(last, _)| last)
+    }
+
+    // links the modules the source imports into its tree and lowers it,
+    // the imported files are returned for the spans pointing into them
+    fn lower(
+        &self,
+        ast: AbstractSyntaxTree,
+        spanned: bool,
+    ) -> Result<(LoweredAbstractSyntaxTree, Vec<ImportedFile>), DriverError> {
+        let (modules, files) = self.imports(&ast, spanned)?;
+        let root = SourceModule::new(self.module_name.clone(), ast);
+
+        Linker::new()
+            .run(root, modules)
+            .and_then(|ast| Preprocessor::new().try_run(ast))
+            .map_err(|diagnostic| self.error_in(diagnostic, &files))
+            .map(|last| (last, files))
+    }
+
+    // reads and parses the imported modules, and the ones they import in turn
+    fn imports(
+        &self,
+        ast: &AbstractSyntaxTree,
+        spanned: bool,
+    ) -> Result<(Vec<SourceModule>, Vec<ImportedFile>), DriverError> {
+        let mut modules = vec![];
+        let mut files: Vec<ImportedFile> = vec![];
+        let mut seen = HashSet::from([self.module_name.clone()]);
+        let mut pending = modules::imports(ast);
+
+        while let Some(name) = pending.pop() {
+            if !seen.insert(name.clone()) {
+                continue;
+            }
+
+            let mut relative = name.split("::").collect::<PathBuf>();
+            relative.set_extension(SOURCE_EXTENSION);
+
+            let Some(path) = self
+                .roots
+                .iter()
+                .map(|root| root.join(&relative))
+                .find(|path| path.is_file())
+            else {
+                return Err(DriverError::Compile(format!(
+                    "error: cannot find module `{name}`, expected {}",
+                    self.roots
+                        .iter()
+                        .map(|root| root.join(&relative).display().to_string())
+                        .collect::<Vec<String>>()
+                        .join(" or ")
+                )));
+            };
+
+            let contents = fs::read_to_string(&path).map_err(|error| {
+                DriverError::Io(format!("cannot read {}: {error}", path.display()))
+            })?;
+            let filename = path.to_string_lossy().to_string();
+
+            let (tokens, spans) =
+                Lexer::new()
+                    .try_run_spanned(&contents)
+                    .map_err(|diagnostic| {
+                        DriverError::Compile(diagnostic.render(&filename, &contents))
+                    })?;
+
+            let offset = match files.last() {
+                Some(file) => file.offset + file.contents.len() + 1,
+                None => self.contents.len() + 1,
+            };
+            let ast = if spanned {
+                let spans = spans
+                    .into_iter()
+                    .map(|span| span.offset(offset))
+                    .collect::<Vec<Span>>();
+
+                Parser::new().try_run_spanned(&tokens, &spans)
+            } else {
+                Parser::new().try_run(&tokens)
+            }
+            .map_err(|diagnostic| {
+                // the spans of the diagnostic point past the files before this one
+                let span = diagnostic
+                    .span()
+                    .map(|span| Span::new(span.start - offset, span.end - offset));
+
+                DriverError::Compile(
+                    Diagnostic::new(diagnostic.message().to_string(), span)
+                        .render(&filename, &contents),
+                )
+            })?;
+
+            pending.extend(modules::imports(&ast));
+            modules.push(SourceModule::new(name, ast));
+            files.push(ImportedFile {
+                filename,
+                contents,
+                offset,
+            });
+        }
+
+        Ok((modules, files))
+    }
+
+    fn error(&self, diagnostic: Diagnostic) -> DriverError {
+        DriverError::Compile(diagnostic.render(&self.filename, &self.contents))
+    }
+
+    // renders the diagnostic in the file its span falls into
+    fn error_in(&self, diagnostic: Diagnostic, files: &[ImportedFile]) -> DriverError {
+        let file = diagnostic
+            .span()
+            .and_then(|span| files.iter().rev().find(|file| span.start >= file.offset));
+
+        match (file, diagnostic.span()) {
+            (Some(file), Some(span)) => {
+                let span = Span::new(span.start - file.offset, span.end - file.offset);
+
+                DriverError::Compile(
+                    Diagnostic::new(diagnostic.message().to_string(), Some(span))
+                        .render(&file.filename, &file.contents),
+                )
+            }
+            _ => self.error(diagnostic),
+        }
+    }
+}
+
+// runs the pipeline up to the requested representation
+pub fn emit(
+    source: &Source,
+    kind: EmitKind,
+    format: DumpFormat,
+    codegen: &CodegenArgs,
+) -> Result<Vec<u8>, DriverError> {
+    match (kind, format) {
+        #[cfg(feature = "serde")]
+        (EmitKind::Tokens | EmitKind::Ast | EmitKind::Last, DumpFormat::Json) => {
+            let json = match kind {
+                EmitKind::Tokens => serde_json::to_string_pretty(&source.tokens()?),
+                EmitKind::Ast => serde_json::to_string_pretty(&source.ast()?),
+                _ => serde_json::to_string_pretty(&source.last()?),
+            };
+
+            return json
+                .map(|json| format!("{json}\n").into_bytes())
+                .map_err(|error| DriverError::Backend(error.to_string()));
+        }
+        (EmitKind::Tokens, _) => {
+            let tokens = source.tokens()?;
+            let mut writer = TreeWriter::new();
+
+            writer.node("Tokens", |writer| {
+                for token in &tokens {
+                    token.dump(writer);
+                }
+            });
+
+            return Ok(writer.finish().into_bytes());
+        }
+        (EmitKind::Ast, _) => return Ok(source.ast()?.dump_tree().into_bytes()),
+        (EmitKind::Last, _) => return Ok(source.last()?.dump_tree().into_bytes()),
+        (EmitKind::CHeader, _) => {
+            return Ok(source.last()?.c_header(&source.module_name).into_bytes())
+        }
+        _ => {}
+    }
+
+    let mut timings = Timings::new(codegen.time_passes);
+
+    if codegen.time_passes {
+        enable_pass_timing();
+    }
+
+    let (tokens, spans) = timings.time("lexing", || source.spanned_tokens())?;
+    // spans are kept so type errors can point into the source
+    let ast = timings.time("parsing", || {
+        Parser::new()
+            .try_run_spanned(&tokens, &spans)
+            .map_err(|diagnostic| source.error(diagnostic))
+    })?;
+    let (last, files) = timings.time("lowering", || source.lower(ast, true))?;
+
+    let context = Context::create();
+    let mut module_generator =
+        ir::generator::module::LLVMModuleGenerator::new(&context, &source.module_name);
+    module_generator.set_verify(!codegen.no_verify);
+
+    if codegen.debug_info {
+        module_generator.set_debug_info(&source.filename, &source.contents);
+
+        for file in &files {
+            module_generator.add_debug_file(&file.filename, &file.contents, file.offset);
+        }
+    }
+
+    timings
+        .time("code generation", || {
+            module_generator.generate_from_ast(last)
+        })
+        .map_err(|diagnostic| source.error(diagnostic))?;
+
+    let module = module_generator.module();
+    let pipeline = pipeline(codegen);
+
+    // passes and native code need a machine, textual ir only for a foreign target
+    let machine = match (kind, &codegen.target, &pipeline) {
+        (EmitKind::LlvmIr | EmitKind::LlvmBc, None, None) => None,
+        _ => Some(target_machine(module, codegen)?),
+    };
+
+    if let (Some(pipeline), Some(machine)) = (&pipeline, &machine) {
+        timings.time("optimisation", || {
+            module
+                .run_passes(pipeline, machine, PassBuilderOptions::create())
+                .map_err(|error| {
+                    DriverError::Backend(format!("cannot run passes {pipeline}: {error}"))
+                })
+        })?;
+    }
+
+    let output = timings.time("emission", || emit_module(module, kind, machine.as_ref()));
+    timings.report();
+
+    output
+}
+
+fn emit_module(
+    module: &Module,
+    kind: EmitKind,
+    machine: Option<&TargetMachine>,
+) -> Result<Vec<u8>, DriverError> {
+    match kind {
+        EmitKind::LlvmIr => Ok(module.print_to_string().to_string().into_bytes()),
+        EmitKind::LlvmBc => Ok(module.write_bitcode_to_memory().as_slice().to_vec()),
+        // executables are linked from the object file
+        EmitKind::Asm | EmitKind::Obj | EmitKind::Exe => {
+            let file_type = match kind {
+                EmitKind::Asm => FileType::Assembly,
+                _ => FileType::Object,
+            };
+
+            machine
+                .unwrap()
+                .write_to_memory_buffer(module, file_type)
+                .map(|buffer| buffer.as_slice().to_vec())
+                .map_err(|error| DriverError::Backend(error.to_string()))
+        }
+        _ => unreachable!(),
+    }
+}
+
+// compiles the source in memory and runs its `main`
+pub fn run(source: &Source, codegen: &CodegenArgs, args: &[String]) -> Result<i32, DriverError> {
+    let last = source.spanned_last()?;
+    let args = args.iter().map(String::as_str).collect::<Vec<_>>();
+
+    if codegen.time_passes {
+        enable_pass_timing();
+    }
+
+    ir::jit::run_main(
+        last,
+        &source.module_name,
+        optimization_level(codegen.opt_level()),
+        pipeline(codegen).as_deref(),
+        &args,
+    )
+    .map_err(DriverError::Backend)
+}
+
+// has llvm time each pass it runs, it reports them on stderr
+// once the pass managers are torn down
+fn enable_pass_timing() {
+    static ENABLE: Once = Once::new();
+
+    ENABLE.call_once(|| {
+        let args = [c"starbloom".as_ptr(), c"-time-passes".as_ptr()];
+
+        unsafe {
+            LLVMParseCommandLineOptions(args.len() as i32, args.as_ptr(), ptr::null());
+        }
+    });
+}
+
+// the new pass manager pipeline for the -O level, unless one is given
+fn pipeline(codegen: &CodegenArgs) -> Option<String> {
+    match (&codegen.passes, codegen.opt_level()) {
+        (Some(passes), _) => Some(passes.clone()),
+        // leave the module exactly as generated
+        (None, OptLevel::O0) => None,
+        (None, OptLevel::O1) => Some("default<O1>".to_string()),
+        (None, OptLevel::O2) => Some("default<O2>".to_string()),
+        (None, OptLevel::O3) => Some("default<O3>".to_string()),
+    }
+}
+
+// configures the module for the requested target
+fn target_machine(module: &Module, codegen: &CodegenArgs) -> Result<TargetMachine, DriverError> {
+    Target::initialize_all(&InitializationConfig::default());
+
+    let (triple, cpu, features) = match &codegen.target {
+        Some(triple) => (
+            TargetTriple::create(triple),
+            "generic".to_string(),
+            String::new(),
+        ),
+        None => (
+            TargetMachine::get_default_triple(),
+            TargetMachine::get_host_cpu_name().to_string(),
+            TargetMachine::get_host_cpu_features().to_string(),
+        ),
+    };
+
+    let target = Target::from_triple(&triple).map_err(|error| {
+        DriverError::Backend(format!("unknown target {}: {error}", triple_name(&triple)))
+    })?;
+
+    let machine = target
+        .create_target_machine(
+            &triple,
+            &cpu,
+            &features,
+            optimization_level(codegen.opt_level()),
+            RelocMode::PIC,
+            CodeModel::Default,
+        )
+        .ok_or_else(|| {
+            DriverError::Backend(format!(
+                "cannot create a machine for {}",
+                triple_name(&triple)
+            ))
+        })?;
+
+    module.set_triple(&triple);
+    module.set_data_layout(&machine.get_target_data().get_data_layout());
+
+    Ok(machine)
+}
+
+fn triple_name(triple: &TargetTriple) -> String {
+    triple.as_str().to_string_lossy().to_string()
+}
+
+fn optimization_level(level: OptLevel) -> OptimizationLevel {
+    match level {
+        OptLevel::O0 => OptimizationLevel::None,
+        OptLevel::O1 => OptimizationLevel::Less,
+        OptLevel::O2 => OptimizationLevel::Default,
+        OptLevel::O3 => OptimizationLevel::Aggressive,
+    }
+}
+
+// the output in the current directory, named after the module
+pub fn default_output(source: &Source, kind: EmitKind) -> PathBuf {
+    let mut path = PathBuf::from(&source.module_name);
+    path.set_extension(kind.extension());
+
+    path
+}
+
+fn write_output(path: &Path, bytes: &[u8]) -> Result<(), DriverError> {
+    if path == Path::new(STDIO_PATH) {
+        return io::stdout()
+            .write_all(bytes)
+            .map_err(|error| DriverError::Io(format!("cannot write stdout: {error}")));
+    }
+
+    fs::write(path, bytes)
+        .map_err(|error| DriverError::Io(format!("cannot write {}: {error}", path.display())))
+}
+
+// writes what `emit` produced, linking executables on the way
+pub fn write_artifact(
+    source: &Source,
+    kind: EmitKind,
+    bytes: &[u8],
+    path: &Path,
+) -> Result<(), DriverError> {
+    match kind {
+        EmitKind::Exe => link(source, bytes, path, &[]),
+        _ => write_output(path, bytes),
+    }
+}
+
+// links an object file into an executable with the system C compiler,
+// `CC` overrides which one, the libraries are passed to it as `-l`
+pub fn link(
+    source: &Source,
+    object: &[u8],
+    path: &Path,
+    libraries: &[String],
+) -> Result<(), DriverError> {
+    if path == Path::new(STDIO_PATH) {
+        return Err(DriverError::Io(
+            "cannot write an executable to stdout".to_string(),
+        ));
+    }
+
+    let linker = env::var("CC").unwrap_or_else(|_| "cc".to_string());
+    let mut command = process::Command::new(&linker);
+    command.arg("-o").arg(path);
+
+    with_object(source, object, &linker, command, |command, object_path| {
+        command.arg(object_path);
+        command.args(libraries.iter().map(|library| format!("-l{library}")));
+    })
+}
+
+// archives an object file into a static library with `ar`, `AR` overrides which one
+pub fn archive(source: &Source, object: &[u8], path: &Path) -> Result<(), DriverError> {
+    if path == Path::new(STDIO_PATH) {
+        return Err(DriverError::Io(
+            "cannot write a static library to stdout".to_string(),
+        ));
+    }
+
+    // `ar` adds to an existing archive
+    if path.exists() {
+        fs::remove_file(path).map_err(|error| {
+            DriverError::Io(format!("cannot remove {}: {error}", path.display()))
+        })?;
+    }
+
+    let archiver = env::var("AR").unwrap_or_else(|_| "ar".to_string());
+    let mut command = process::Command::new(&archiver);
+    command.arg("rcs").arg(path);
+
+    with_object(
+        source,
+        object,
+        &archiver,
+        command,
+        |command, object_path| {
+            command.arg(object_path);
+        },
+    )
+}
+
+// runs the tool on the object written to a temporary file
+fn with_object(
+    source: &Source,
+    object: &[u8],
+    tool: &str,
+    mut command: process::Command,
+    add_object: impl FnOnce(&mut process::Command, &Path),
+) -> Result<(), DriverError> {
+    let object_path = env::temp_dir().join(format!("{}-{}.o", source.module_name, process::id()));
+
+    fs::write(&object_path, object).map_err(|error| {
+        DriverError::Io(format!("cannot write {}: {error}", object_path.display()))
+    })?;
+
+    add_object(&mut command, &object_path);
+    let status = command.status();
+
+    let _ = fs::remove_file(&object_path);
+
+    match status {
+        Ok(status) if status.success() => Ok(()),
+        Ok(status) => Err(DriverError::Backend(format!(
+            "running {tool} failed: {status}"
+        ))),
+        Err(error) => Err(DriverError::Backend(format!("cannot run {tool}: {error}"))),
+    }
+}
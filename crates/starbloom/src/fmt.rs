@@ -0,0 +1,92 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use formatter::Formatter;
+use lexer::lexer::Lexer;
+use parser::Parser;
+
+use crate::{
+    cli::{FmtArgs, EXIT_COMPILE_ERROR, EXIT_IO_ERROR, EXIT_SUCCESS},
+    driver::SOURCE_EXTENSION,
+};
+
+// formats the given files and directories in place, with `--check`
+// only reports unformatted files, returns the process exit code
+pub fn run(args: &FmtArgs) -> i32 {
+    let check = args.check;
+    let mut paths = args.paths.clone();
+
+    if paths.is_empty() {
+        paths.push(PathBuf::from("."));
+    }
+
+    let mut files = vec![];
+    for path in &paths {
+        if let Err(error) = collect_sources(path, &mut files) {
+            eprintln!("error: cannot read {}: {error}", path.display());
+            return EXIT_IO_ERROR;
+        }
+    }
+
+    let mut exit_code = EXIT_SUCCESS;
+
+    for file in files {
+        let contents = match fs::read_to_string(&file) {
+            Ok(contents) => contents,
+            Err(error) => {
+                eprintln!("error: cannot read {}: {error}", file.display());
+                exit_code = EXIT_IO_ERROR;
+                continue;
+            }
+        };
+
+        let tokens = match Lexer::new().try_run_lossless(&contents) {
+            Ok(tokens) => tokens,
+            Err(diagnostic) => {
+                eprintln!("{}", diagnostic.render(&file.to_string_lossy(), &contents));
+                exit_code = exit_code.max(EXIT_COMPILE_ERROR);
+                continue;
+            }
+        };
+        let cst = Parser::new().run_lossless(&tokens);
+        let formatted = Formatter::new().run(&cst);
+
+        if formatted == contents {
+            continue;
+        }
+
+        if check {
+            println!("{} is not formatted", file.display());
+            exit_code = exit_code.max(EXIT_COMPILE_ERROR);
+        } else if let Err(error) = fs::write(&file, formatted) {
+            eprintln!("error: cannot write {}: {error}", file.display());
+            exit_code = EXIT_IO_ERROR;
+        }
+    }
+
+    exit_code
+}
+
+fn collect_sources(path: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    if !path.is_dir() {
+        files.push(path.to_path_buf());
+        return Ok(());
+    }
+
+    let mut entries = fs::read_dir(path)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<Vec<PathBuf>>>()?;
+    entries.sort();
+
+    for entry in entries {
+        if entry.is_dir() {
+            collect_sources(&entry, files)?;
+        } else if entry.extension().is_some_and(|ext| ext == SOURCE_EXTENSION) {
+            files.push(entry);
+        }
+    }
+
+    Ok(())
+}
@@ -1,35 +1,67 @@
-use std::{env, fs, path::PathBuf};
+use std::{env, path::PathBuf, process};
 
-use inkwell::context::Context;
-use lexer::lexer::Lexer;
-use parser::Parser;
-use preprocessor::Preprocessor;
+use clap::Parser;
+use cli::{Cli, CodegenArgs, Command, DumpFormat, EmitKind, EXIT_SUCCESS};
+use driver::{DriverError, Source};
+use project::Project;
+
+mod cli;
+mod driver;
+mod fmt;
+mod project;
+mod timings;
 
 fn main() {
-    let filename = env::args().nth(1).unwrap();
-    let filename = PathBuf::from(filename);
-    let contents = fs::read_to_string(&filename).unwrap();
-
-    let tokens = Lexer::new().run(&contents);
-    let ast = Parser::new().run(&tokens);
-    let last = Preprocessor::new().run(ast);
-
-    let module_name = filename
-        .file_name()
-        .unwrap()
-        .to_str()
-        .unwrap()
-        .split_once('.')
-        .unwrap()
-        .0;
-
-    let llvm_cx = Context::create();
-    let module_generator = ir::generator::module::LLVMModuleGenerator::new(&llvm_cx, module_name);
-
-    module_generator.generate_from_ast(last);
-
-    module_generator
-        .module()
-        .print_to_file(filename.parent().unwrap().join(format!("{module_name}.ll")))
-        .unwrap();
+    let cli = Cli::parse();
+
+    let result = match &cli.command {
+        Command::Build(args) => match &args.input {
+            Some(input) => Source::read(input).and_then(|source| {
+                let kind = args.emit.unwrap_or(EmitKind::Exe);
+                let bytes = driver::emit(&source, kind, args.format, &args.codegen)?;
+                let output = args
+                    .output
+                    .clone()
+                    .unwrap_or_else(|| driver::default_output(&source, kind));
+
+                driver::write_artifact(&source, kind, &bytes, &output)
+            }),
+            // without a source file the current directory is the project
+            None => env::current_dir()
+                .map_err(|error| {
+                    DriverError::Io(format!("cannot read the current directory: {error}"))
+                })
+                .and_then(|root| Project::open(&root))
+                .and_then(|project| project.build(args)),
+        },
+        Command::Run(args) => Source::read(&args.input.input).and_then(|source| {
+            let exit_code = driver::run(&source, &args.codegen, &args.args)?;
+
+            process::exit(exit_code)
+        }),
+        Command::Check(args) => Source::read(&args.input.input).and_then(|source| {
+            driver::emit(
+                &source,
+                EmitKind::LlvmIr,
+                DumpFormat::Tree,
+                &CodegenArgs::default(),
+            )
+            .map(|_| ())
+        }),
+        Command::Fmt(args) => process::exit(fmt::run(args)),
+        Command::Emit(args) => Source::read(&args.input.input).and_then(|source| {
+            let bytes = driver::emit(&source, args.kind, args.format, &args.codegen)?;
+            let output = args.output.clone().unwrap_or_else(|| PathBuf::from("-"));
+
+            driver::write_artifact(&source, args.kind, &bytes, &output)
+        }),
+    };
+
+    process::exit(match result {
+        Ok(()) => EXIT_SUCCESS,
+        Err(error) => {
+            eprintln!("{error}");
+            error.exit_code()
+        }
+    });
 }
@@ -0,0 +1,206 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+
+use crate::{
+    cli::{BuildArgs, EmitKind, OptLevel},
+    driver::{self, DriverError, Source},
+};
+
+pub const MANIFEST_FILE: &str = "bloom.toml";
+const TARGET_DIR: &str = "target";
+
+// the contents of a bloom.toml
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Manifest {
+    pub package: Package,
+    #[serde(default)]
+    pub profile: Profiles,
+    #[serde(default)]
+    pub link: Link,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Package {
+    pub name: String,
+    pub version: String,
+    // the file `main` or the exported functions are in, relative to the project
+    #[serde(default = "default_entry")]
+    pub entry: PathBuf,
+    // the directories imports are looked up in, relative to the project
+    #[serde(default = "default_sources")]
+    pub sources: Vec<PathBuf>,
+    #[serde(default)]
+    pub output: OutputType,
+}
+
+fn default_entry() -> PathBuf {
+    PathBuf::from("src/main.bloom")
+}
+
+fn default_sources() -> Vec<PathBuf> {
+    vec![PathBuf::from("src")]
+}
+
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum OutputType {
+    #[default]
+    Executable,
+    StaticLibrary,
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct Profiles {
+    #[serde(default)]
+    pub dev: Profile,
+    #[serde(default)]
+    pub release: Profile,
+}
+
+// unset settings fall back to the defaults of the profile
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Profile {
+    pub opt_level: Option<u8>,
+    pub debug: Option<bool>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct Link {
+    // system libraries the executable is linked against, e.g. `m` for libm
+    #[serde(default)]
+    pub libraries: Vec<String>,
+}
+
+pub struct Project {
+    pub root: PathBuf,
+    pub manifest: Manifest,
+}
+
+impl Project {
+    pub fn open(root: &Path) -> Result<Self, DriverError> {
+        let path = root.join(MANIFEST_FILE);
+
+        let contents = fs::read_to_string(&path)
+            .map_err(|error| DriverError::Io(format!("cannot read {}: {error}", path.display())))?;
+
+        let manifest: Manifest = toml::from_str(&contents).map_err(|error| {
+            DriverError::Compile(format!("error: invalid {}: {error}", path.display()))
+        })?;
+
+        let name = &manifest.package.name;
+        if name.is_empty()
+            || name.starts_with(|c: char| c.is_ascii_digit())
+            || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            return Err(DriverError::Compile(format!(
+                "error: invalid {}: package name `{name}` must be an identifier",
+                path.display()
+            )));
+        }
+
+        let version = &manifest.package.version;
+        if version.split('.').count() != 3
+            || !version
+                .split('.')
+                .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()))
+        {
+            return Err(DriverError::Compile(format!(
+                "error: invalid {}: version `{version}` must be of the form major.minor.patch",
+                path.display()
+            )));
+        }
+
+        for (profile, settings) in [
+            ("dev", &manifest.profile.dev),
+            ("release", &manifest.profile.release),
+        ] {
+            if settings.opt_level.is_some_and(|level| level > 3) {
+                return Err(DriverError::Compile(format!(
+                    "error: invalid {}: opt-level of profile `{profile}` must be between 0 and 3",
+                    path.display()
+                )));
+            }
+        }
+
+        Ok(Self {
+            root: root.to_path_buf(),
+            manifest,
+        })
+    }
+
+    // builds the package into target/dev or target/release
+    pub fn build(&self, args: &BuildArgs) -> Result<(), DriverError> {
+        let package = &self.manifest.package;
+        let (profile_name, profile, default_opt_level) = match args.release {
+            true => ("release", &self.manifest.profile.release, 3),
+            false => ("dev", &self.manifest.profile.dev, 0),
+        };
+
+        // flags on the command line win over the profile
+        let mut codegen = args.codegen.clone();
+        codegen.opt_level = codegen.opt_level.or(Some(opt_level(
+            profile.opt_level.unwrap_or(default_opt_level),
+        )));
+        codegen.debug_info |= profile.debug.unwrap_or(false);
+
+        let mut source = Source::read(&self.root.join(&package.entry))?;
+        source.module_name = package.name.clone();
+        source.roots = package
+            .sources
+            .iter()
+            .map(|directory| self.root.join(directory))
+            .collect();
+
+        let directory = self.root.join(TARGET_DIR).join(profile_name);
+        fs::create_dir_all(&directory).map_err(|error| {
+            DriverError::Io(format!("cannot create {}: {error}", directory.display()))
+        })?;
+
+        let kind = args.emit.unwrap_or(match package.output {
+            OutputType::Executable => EmitKind::Exe,
+            OutputType::StaticLibrary => EmitKind::Obj,
+        });
+        let bytes = driver::emit(&source, kind, args.format, &codegen)?;
+
+        let library = args.emit.is_none() && package.output == OutputType::StaticLibrary;
+        let output = args.output.clone().unwrap_or_else(|| match library {
+            true => directory.join(format!("lib{}.a", package.name)),
+            false => directory.join(driver::default_output(&source, kind)),
+        });
+
+        match kind {
+            // the library comes with a header for its exported functions
+            EmitKind::Obj if library => {
+                driver::archive(&source, &bytes, &output)?;
+
+                let header = driver::emit(&source, EmitKind::CHeader, args.format, &codegen)?;
+                driver::write_artifact(
+                    &source,
+                    EmitKind::CHeader,
+                    &header,
+                    &output.with_file_name(format!("{}.h", package.name)),
+                )
+            }
+            EmitKind::Exe => driver::link(&source, &bytes, &output, &self.manifest.link.libraries),
+            _ => driver::write_artifact(&source, kind, &bytes, &output),
+        }
+    }
+}
+
+fn opt_level(level: u8) -> OptLevel {
+    match level {
+        0 => OptLevel::O0,
+        1 => OptLevel::O1,
+        2 => OptLevel::O2,
+        _ => OptLevel::O3,
+    }
+}
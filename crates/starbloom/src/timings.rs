@@ -0,0 +1,40 @@
+use std::time::{Duration, Instant};
+
+// wall-clock time of each compiler stage, reported on stderr
+pub struct Timings {
+    enabled: bool,
+    stages: Vec<(&'static str, Duration)>,
+}
+
+impl Timings {
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            stages: vec![],
+        }
+    }
+
+    pub fn time<T>(&mut self, stage: &'static str, run: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let result = run();
+
+        if self.enabled {
+            self.stages.push((stage, start.elapsed()));
+        }
+
+        result
+    }
+
+    pub fn report(&self) {
+        if !self.enabled {
+            return;
+        }
+
+        let total = self.stages.iter().map(|(_, duration)| *duration).sum();
+
+        eprintln!("===-- stage timings --===");
+        for (stage, duration) in self.stages.iter().chain([&("total", total)]) {
+            eprintln!("  {stage:<16} {:>10.3}ms", duration.as_secs_f64() * 1000.0);
+        }
+    }
+}